@@ -15,6 +15,24 @@ impl Clocks {
     pub const fn xclk(&self) -> Hertz {
         self.xtal
     }
+    /// Peripheral bus clock frequency.
+    #[inline]
+    pub const fn bclk(&self) -> Hertz {
+        // todo: calculate from Clocks structure fields
+        Hertz(80_000_000)
+    }
+    /// 160-MHz multiplexer PLL clock frequency.
+    #[inline]
+    pub const fn mux_pll_160m(&self) -> Hertz {
+        // todo: calculate from Clocks structure fields
+        Hertz(160_000_000)
+    }
+    /// 32-kHz low power clock frequency.
+    #[inline]
+    pub const fn f32k_clk(&self) -> Hertz {
+        // todo: calculate from Clocks structure fields
+        Hertz(32_000)
+    }
     /// Universal Asynchronous Receiver/Transmitter clock frequency.
     #[inline]
     pub const fn uart_clock<const I: usize>(&self) -> Option<Hertz> {
@@ -1,25 +1,50 @@
 //! Display bus interface.
 
-use volatile_register::RW;
+use crate::clocks::Clocks;
+use crate::dma::{
+    BurstSize, DmaChannelConfig, DmaMode, LliPool, PeripheralId, TransferWidth, TypedChannel,
+    clean_dcache,
+};
+use crate::gpio::{self, Alternate};
+use as_slice::AsSlice;
+use core::ops::Deref;
+use core::sync::atomic::{Ordering, compiler_fence};
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_hal::spi::{Mode, Phase, Polarity};
+use embedded_time::rate::Hertz;
+use volatile_register::{RO, RW, WO};
 
 /// Display bus interface registers.
 #[repr(C)]
 pub struct RegisterBlock {
     /// Function configuration register.
-    pub config: RW<Config>,
-    _reserved: [u8; 0x7b],
+    pub config: RW<FunctionConfig>,
+    _reserved0: [u8; 0x4],
+    /// Pixel count and output format of pixel transfers.
+    pub pixel_count: RW<PixelCount>,
+    /// Duration of data phases and conditions in source clock.
+    pub period: RW<Period>,
+    /// Data bytes sent on normal transfers.
+    pub data_write: RW<u32>,
+    /// Data bytes received on normal transfers.
+    pub data_read: RO<u32>,
+    /// Interrupt configuration and state register.
+    pub interrupt_config: RW<InterruptConfig>,
+    _reserved1: [u8; 0x64],
     /// First-in first-out queue configuration 0.
     pub fifo_config_0: RW<FifoConfig0>,
     /// First-in first-out queue configuration 1.
     pub fifo_config_1: RW<FifoConfig1>,
+    /// Write data into first-in first-out queue.
+    pub fifo_write: WO<u32>,
 }
 
 /// Function configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
-pub struct Config(u32);
+pub struct FunctionConfig(u32);
 
-impl Config {
+impl FunctionConfig {
     const MASTER_ENABLE: u32 = 1 << 0;
     const SELECT_TYPE: u32 = 1 << 1;
     const COMMAND_ENABLE: u32 = 1 << 2;
@@ -277,6 +302,165 @@ impl Config {
     }
 }
 
+/// Pixel count and output format register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct PixelCount(u32);
+
+impl PixelCount {
+    const PIXEL_COUNT: u32 = 0xff_ffff;
+    const PIXEL_FORMAT: u32 = 1 << 31;
+
+    /// Set number of pixels in a pixel transfer.
+    #[inline]
+    pub const fn set_pixel_count(self, count: u32) -> Self {
+        Self((self.0 & !Self::PIXEL_COUNT) | (count & Self::PIXEL_COUNT))
+    }
+    /// Get number of pixels in a pixel transfer.
+    #[inline]
+    pub const fn pixel_count(self) -> u32 {
+        self.0 & Self::PIXEL_COUNT
+    }
+    /// Set pixel format sent on the bus.
+    #[inline]
+    pub const fn set_pixel_format(self, format: PixelFormat) -> Self {
+        Self((self.0 & !Self::PIXEL_FORMAT) | ((format as u32) << 31))
+    }
+    /// Get pixel format sent on the bus.
+    #[inline]
+    pub const fn pixel_format(self) -> PixelFormat {
+        match (self.0 & Self::PIXEL_FORMAT) >> 31 {
+            0 => PixelFormat::Rgb888,
+            _ => PixelFormat::Rgb565,
+        }
+    }
+}
+
+/// Pixel format sent on the bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PixelFormat {
+    /// 24-bit pixels, three bytes each.
+    Rgb888 = 0,
+    /// 16-bit pixels, two bytes each.
+    Rgb565 = 1,
+}
+
+/// Duration of data phases and conditions register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Period(u32);
+
+impl Period {
+    const START_CONDITION: u32 = 0xff;
+    const INTERVAL: u32 = 0xff << 8;
+    const DATA_PHASE_0: u32 = 0xff << 16;
+    const DATA_PHASE_1: u32 = 0xff << 24;
+
+    /// Set length of start condition.
+    #[inline]
+    pub const fn set_start_condition(self, val: u8) -> Self {
+        Self((self.0 & !Self::START_CONDITION) | (val as u32))
+    }
+    /// Get length of start condition.
+    #[inline]
+    pub const fn start_condition(self) -> u8 {
+        (self.0 & Self::START_CONDITION) as u8
+    }
+    /// Set length of interval between transfers.
+    #[inline]
+    pub const fn set_interval(self, val: u8) -> Self {
+        Self((self.0 & !Self::INTERVAL) | ((val as u32) << 8))
+    }
+    /// Get length of interval between transfers.
+    #[inline]
+    pub const fn interval(self) -> u8 {
+        ((self.0 & Self::INTERVAL) >> 8) as u8
+    }
+    /// Set length of data phase 0.
+    #[inline]
+    pub const fn set_data_phase_0(self, val: u8) -> Self {
+        Self((self.0 & !Self::DATA_PHASE_0) | ((val as u32) << 16))
+    }
+    /// Get length of data phase 0.
+    #[inline]
+    pub const fn data_phase_0(self) -> u8 {
+        ((self.0 & Self::DATA_PHASE_0) >> 16) as u8
+    }
+    /// Set length of data phase 1.
+    #[inline]
+    pub const fn set_data_phase_1(self, val: u8) -> Self {
+        Self((self.0 & !Self::DATA_PHASE_1) | ((val as u32) << 24))
+    }
+    /// Get length of data phase 1.
+    #[inline]
+    pub const fn data_phase_1(self) -> u8 {
+        ((self.0 & Self::DATA_PHASE_1) >> 24) as u8
+    }
+}
+
+/// Interrupt configuration and state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterruptConfig(u32);
+
+impl InterruptConfig {
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        (self.0 & (1 << (val as u32))) != 0
+    }
+    /// Set interrupt mask.
+    #[inline]
+    pub const fn mask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 8)))
+    }
+    /// Clear interrupt mask.
+    #[inline]
+    pub const fn unmask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32 + 8)))
+    }
+    /// Check if interrupt is masked.
+    #[inline]
+    pub const fn is_interrupt_masked(self, val: Interrupt) -> bool {
+        (self.0 & (1 << (val as u32 + 8))) != 0
+    }
+    /// Clear interrupt flag.
+    ///
+    /// Only `TransferEnd` can be cleared here; the queue interrupts follow the queue state.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 16)))
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 24)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32 + 24)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        (self.0 & (1 << (val as u32 + 24))) != 0
+    }
+}
+
+/// Interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// Transfer end interrupt.
+    TransferEnd = 0,
+    /// Transmit first-in first-out queue ready interrupt.
+    TransmitFifoReady = 1,
+    /// First-in first-out queue overflow or underflow interrupt.
+    FifoError = 2,
+}
+
 /// First-in first-out queue configuration 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -287,6 +471,7 @@ impl FifoConfig0 {
     const TRANSMIT_FIFO_CLEAR: u32 = 1 << 2;
     const TRANSMIT_FIFO_OVERFLOW: u32 = 1 << 4;
     const TRANSMIT_FIFO_UNDERFLOW: u32 = 1 << 5;
+    const INPUT_FORMAT: u32 = 0x7 << 24;
 
     /// Enable DMA transmit.
     #[inline]
@@ -318,6 +503,49 @@ impl FifoConfig0 {
     pub fn is_transmit_fifo_underflow(self) -> bool {
         self.0 & Self::TRANSMIT_FIFO_UNDERFLOW != 0
     }
+    /// Set layout of pixels in transmit queue words.
+    #[inline]
+    pub fn set_input_format(self, format: InputFormat) -> Self {
+        Self((self.0 & !Self::INPUT_FORMAT) | ((format as u32) << 24))
+    }
+    /// Get layout of pixels in transmit queue words.
+    #[inline]
+    pub fn input_format(self) -> InputFormat {
+        match (self.0 & Self::INPUT_FORMAT) >> 24 {
+            0 => InputFormat::Nrgb8888,
+            1 => InputFormat::Nbgr8888,
+            2 => InputFormat::Rgbn8888,
+            3 => InputFormat::Bgrn8888,
+            4 => InputFormat::Rgb888,
+            5 => InputFormat::Bgr888,
+            6 => InputFormat::Rgb565,
+            _ => InputFormat::Bgr565,
+        }
+    }
+}
+
+/// Layout of pixels in transmit queue words.
+///
+/// Letters are listed from the most significant bits; `N` marks an unused byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum InputFormat {
+    /// One 24-bit pixel per word, unused most significant byte.
+    Nrgb8888 = 0,
+    /// One 24-bit pixel per word with red and blue swapped, unused most significant byte.
+    Nbgr8888 = 1,
+    /// One 24-bit pixel per word, unused least significant byte.
+    Rgbn8888 = 2,
+    /// One 24-bit pixel per word with red and blue swapped, unused least significant byte.
+    Bgrn8888 = 3,
+    /// Packed 24-bit pixels.
+    Rgb888 = 4,
+    /// Packed 24-bit pixels with red and blue swapped.
+    Bgr888 = 5,
+    /// Two 16-bit pixels per word, first pixel in the lower half.
+    Rgb565 = 6,
+    /// Two 16-bit pixels per word with red and blue swapped, first pixel in the lower half.
+    Bgr565 = 7,
 }
 
 /// First-in first-out queue configuration 1.
@@ -346,15 +574,1121 @@ impl FifoConfig1 {
     }
 }
 
+/// Managed Display Bus Interface peripheral.
+pub struct Dbi<DBI, PADS> {
+    dbi: DBI,
+    pads: PADS,
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> Dbi<DBI, PADS> {
+    /// Create a new Display Bus Interface instance.
+    ///
+    /// Bus type is chosen from the pads; the peripheral runs on the 160-MHz multiplexer PLL.
+    #[inline]
+    pub fn new(dbi: DBI, pads: PADS, config: Config, clocks: &Clocks) -> Result<Self, ConfigError>
+    where
+        PADS: Pads,
+    {
+        let [phase_0, phase_1] = dbi_timing(clocks.mux_pll_160m(), config.frequency)?;
+
+        let mut function_config = FunctionConfig(0)
+            .disable_master()
+            .disable_continuous_transfer()
+            .disable_dummy_cycle()
+            .disable_deglitch()
+            .set_scl_polarity(config.mode.polarity == Polarity::IdleHigh)
+            .set_scl_phase(config.mode.phase == Phase::CaptureOnSecondTransition);
+        function_config = match (PADS::TYPE_B, PADS::THREE_WIRE) {
+            (true, _) => function_config.set_type_b(),
+            (false, true) => function_config.set_type_c().set_type_c_3_wire_mode(),
+            (false, false) => function_config.set_type_c().set_type_c_4_wire_mode(),
+        };
+
+        unsafe {
+            dbi.config.write(function_config);
+            dbi.period.write(
+                Period(0)
+                    .set_data_phase_0(phase_0)
+                    .set_data_phase_1(phase_1)
+                    .set_start_condition(phase_0)
+                    .set_interval(phase_1),
+            );
+            dbi.interrupt_config.write(
+                InterruptConfig(0)
+                    .mask_interrupt(Interrupt::TransferEnd)
+                    .mask_interrupt(Interrupt::TransmitFifoReady)
+                    .mask_interrupt(Interrupt::FifoError)
+                    .clear_interrupt(Interrupt::TransferEnd),
+            );
+            dbi.fifo_config_0
+                .write(FifoConfig0(0).disable_dma_transmit().clear_transmit_fifo());
+        }
+        Ok(Dbi { dbi, pads })
+    }
+
+    /// Sends a command followed by its parameters.
+    ///
+    /// Parameters are sent as normal data, at most four bytes per transfer.
+    #[inline]
+    pub fn write_command(&mut self, command: u8, parameters: &[u8]) {
+        let mut chunks = parameters.chunks(4);
+        dbi_write_normal(&self.dbi, Some(command), chunks.next().unwrap_or(&[]));
+        for chunk in chunks {
+            dbi_write_normal(&self.dbi, None, chunk);
+        }
+    }
+
+    /// Sends data bytes without a command.
+    #[inline]
+    pub fn write_data(&mut self, data: &[u8]) {
+        for chunk in data.chunks(4) {
+            dbi_write_normal(&self.dbi, None, chunk);
+        }
+    }
+
+    /// Sends a command and reads back up to four bytes.
+    ///
+    /// Some commands need dummy clock cycles between the command and the data, at most 16.
+    #[inline]
+    pub fn read(&mut self, command: u8, dummy_cycles: u8, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() > 4 {
+            return Err(Error::ReadTooLong);
+        }
+        if dummy_cycles > 16 {
+            return Err(Error::TooManyDummyCycles);
+        }
+        let mut config = self
+            .dbi
+            .config
+            .read()
+            .enable_command()
+            .set_command(command)
+            .set_data_read()
+            .set_data_normal();
+        config = match buf.len() {
+            0 => config.disable_data(),
+            len => config.enable_data().set_data_byte_count(len as u8 - 1),
+        };
+        config = match dummy_cycles {
+            0 => config.disable_dummy_cycle(),
+            cycles => config
+                .enable_dummy_cycle()
+                .set_dummy_cycle_count(cycles - 1),
+        };
+        dbi_start(&self.dbi, config);
+        dbi_wait(&self.dbi);
+        let word = self.dbi.data_read.read();
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = (word >> (8 * i)) as u8;
+        }
+        unsafe {
+            self.dbi
+                .config
+                .modify(|config| config.disable_dummy_cycle().set_data_write())
+        };
+        Ok(())
+    }
+
+    /// Streams pixels in pixel mode, optionally after a command such as memory write.
+    #[inline]
+    pub fn write_pixels<P: Pixel>(&mut self, command: Option<u8>, pixels: &[P]) {
+        let mut command = command;
+        for chunk in pixels.chunks(MAX_PIXEL_COUNT) {
+            dbi_start_pixels::<P>(&self.dbi, command.take(), chunk.len());
+            for word in chunk.chunks(P::PER_WORD).map(P::pack) {
+                while self.dbi.fifo_config_1.read().transmit_available_bytes() == 0 {
+                    core::hint::spin_loop();
+                }
+                unsafe { self.dbi.fifo_write.write(word) };
+            }
+            dbi_wait(&self.dbi);
+        }
+        if let Some(command) = command {
+            dbi_write_normal(&self.dbi, Some(command), &[]);
+        }
+    }
+
+    /// Streams pixels from an iterator in pixel mode, optionally after a command.
+    #[inline]
+    pub fn write_pixels_iter<P: Pixel>(
+        &mut self,
+        command: Option<u8>,
+        pixels: impl IntoIterator<Item = P>,
+    ) {
+        let mut pixels = pixels.into_iter();
+        let Some(first) = pixels.next() else {
+            if let Some(command) = command {
+                dbi_write_normal(&self.dbi, Some(command), &[]);
+            }
+            return;
+        };
+        let mut buf = [first; PIXEL_BUFFER_LENGTH];
+        let mut len = 1;
+        let mut command = command;
+        for pixel in pixels {
+            if len == buf.len() {
+                self.write_pixels(command.take(), &buf);
+                len = 0;
+            }
+            buf[len] = pixel;
+            len += 1;
+        }
+        self.write_pixels(command, &buf[..len]);
+    }
+
+    /// Hand over pixel transfers to a DMA channel.
+    ///
+    /// The channel comes with the DMA peripheral request of this DBI, i.e. `DbiTx`.
+    #[inline]
+    pub fn with_dma<'b, T: PeripheralId + Copy>(
+        self,
+        (channel, request): (TypedChannel<'b, T>, T),
+    ) -> DmaDbi<'b, DBI, PADS, T> {
+        unsafe {
+            self.dbi
+                .fifo_config_1
+                .modify(|val| val.set_transmit_threshold(0));
+            self.dbi
+                .fifo_config_0
+                .modify(|val| val.enable_dma_transmit().clear_transmit_fifo());
+        }
+        DmaDbi {
+            dbi: self.dbi,
+            pads: self.pads,
+            channel,
+            request,
+        }
+    }
+
+    /// Release the DBI instance and return the pads.
+    #[inline]
+    pub fn free(self) -> (DBI, PADS) {
+        (self.dbi, self.pads)
+    }
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> WriteOnlyDataCommand for Dbi<DBI, PADS> {
+    #[inline]
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        match cmd {
+            DataFormat::U8(commands) => {
+                for &command in commands {
+                    dbi_write_normal(&self.dbi, Some(command), &[]);
+                }
+            }
+            DataFormat::U8Iter(commands) => {
+                for command in commands {
+                    dbi_write_normal(&self.dbi, Some(command), &[]);
+                }
+            }
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        // Pixel mode sends each 16-bit word MSB-first, i.e. as big endian.
+        match buf {
+            DataFormat::U8(data) => self.write_data(data),
+            DataFormat::U8Iter(data) => {
+                let mut chunk = [0u8; 4];
+                let mut len = 0;
+                for byte in data {
+                    chunk[len] = byte;
+                    len += 1;
+                    if len == chunk.len() {
+                        dbi_write_normal(&self.dbi, None, &chunk);
+                        len = 0;
+                    }
+                }
+                self.write_data(&chunk[..len]);
+            }
+            DataFormat::U16BE(data) => self.write_pixels(None, data),
+            DataFormat::U16LE(data) => {
+                for word in data.iter_mut() {
+                    *word = word.swap_bytes();
+                }
+                self.write_pixels(None, data)
+            }
+            DataFormat::U16(data) => self.write_pixels_iter(
+                None,
+                data.iter().map(|word| u16::from_le(*word).swap_bytes()),
+            ),
+            DataFormat::U16BEIter(data) => self.write_pixels_iter(None, data),
+            DataFormat::U16LEIter(data) => self.write_pixels_iter(None, data.map(u16::swap_bytes)),
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        }
+        Ok(())
+    }
+}
+
+/// Display Bus Interface configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Serial clock frequency on type C, write strobe frequency on type B.
+    pub frequency: Hertz,
+    /// Serial clock polarity and phase, type C only.
+    pub mode: Mode,
+}
+
+impl Config {
+    /// Create a configuration with clock frequency and type C serial clock mode.
+    #[inline]
+    pub const fn new(frequency: Hertz, mode: Mode) -> Self {
+        Self { frequency, mode }
+    }
+    /// Set clock frequency.
+    #[inline]
+    pub const fn set_frequency(self, frequency: Hertz) -> Self {
+        Self { frequency, ..self }
+    }
+    /// Set type C serial clock polarity and phase.
+    #[inline]
+    pub const fn set_mode(self, mode: Mode) -> Self {
+        Self { mode, ..self }
+    }
+}
+
+/// Errors on bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Impossibly high frequency for current clock source.
+    FrequencyTooHigh,
+    /// Impossibly low frequency for current clock source.
+    FrequencyTooLow,
+}
+
+/// DBI error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// More than four bytes requested in one read.
+    ReadTooLong,
+    /// More than sixteen dummy cycles requested in one read.
+    TooManyDummyCycles,
+}
+
+/// Pixel value stored in memory for pixel mode transfers.
+pub trait Pixel: Copy + 'static {
+    /// Pixel format sent on the bus.
+    const FORMAT: PixelFormat;
+    /// Layout of pixels in transmit queue words.
+    const INPUT_FORMAT: InputFormat;
+    /// Number of pixels packed in one transmit queue word.
+    const PER_WORD: usize;
+    /// Packs up to `PER_WORD` pixels into a transmit queue word.
+    fn pack(pixels: &[Self]) -> u32;
+}
+
+impl Pixel for u16 {
+    const FORMAT: PixelFormat = PixelFormat::Rgb565;
+    const INPUT_FORMAT: InputFormat = InputFormat::Rgb565;
+    const PER_WORD: usize = 2;
+    #[inline]
+    fn pack(pixels: &[Self]) -> u32 {
+        pixels
+            .iter()
+            .enumerate()
+            .fold(0, |word, (i, &pixel)| word | (pixel as u32) << (16 * i))
+    }
+}
+
+impl Pixel for u32 {
+    const FORMAT: PixelFormat = PixelFormat::Rgb888;
+    const INPUT_FORMAT: InputFormat = InputFormat::Nrgb8888;
+    const PER_WORD: usize = 1;
+    #[inline]
+    fn pack(pixels: &[Self]) -> u32 {
+        pixels[0] & 0xff_ffff
+    }
+}
+
+/// Valid Display Bus Interface pads.
+///
+/// Type C pads are `(scl, sda, cs)` on 3-wire mode and `(scl, sda, cs, dc)` on 4-wire mode;
+/// type B pads are `(cs, dc, wr, rd, (d0, d1, d2, d3, d4, d5, d6, d7))`. Each pad must be
+/// internally connected to its signal, see `HasSclSignal`, `HasDataSignal` and related traits.
+pub trait Pads {
+    /// Checks if this pin configuration uses the parallel type B interface.
+    const TYPE_B: bool;
+    /// Checks if this pin configuration carries data/command selection inside type C frames.
+    const THREE_WIRE: bool;
+}
+
+impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> Pads
+    for (
+        Alternate<'a, N1, gpio::DbiC>,
+        Alternate<'b, N2, gpio::DbiC>,
+        Alternate<'c, N3, gpio::DbiC>,
+    )
+where
+    Alternate<'a, N1, gpio::DbiC>: HasSclSignal,
+    Alternate<'b, N2, gpio::DbiC>: HasSdaSignal,
+    Alternate<'c, N3, gpio::DbiC>: HasCsSignal,
+{
+    const TYPE_B: bool = false;
+    const THREE_WIRE: bool = true;
+}
+
+impl<'a, 'b, 'c, 'd, const N1: usize, const N2: usize, const N3: usize, const N4: usize> Pads
+    for (
+        Alternate<'a, N1, gpio::DbiC>,
+        Alternate<'b, N2, gpio::DbiC>,
+        Alternate<'c, N3, gpio::DbiC>,
+        Alternate<'d, N4, gpio::DbiC>,
+    )
+where
+    Alternate<'a, N1, gpio::DbiC>: HasSclSignal,
+    Alternate<'b, N2, gpio::DbiC>: HasSdaSignal,
+    Alternate<'c, N3, gpio::DbiC>: HasCsSignal,
+    Alternate<'d, N4, gpio::DbiC>: HasDcSignal,
+{
+    const TYPE_B: bool = false;
+    const THREE_WIRE: bool = false;
+}
+
+impl<
+    'a,
+    'b,
+    'c,
+    'd,
+    'e,
+    'f,
+    'g,
+    'h,
+    'i,
+    'j,
+    'k,
+    'l,
+    const N1: usize,
+    const N2: usize,
+    const N3: usize,
+    const N4: usize,
+    const N5: usize,
+    const N6: usize,
+    const N7: usize,
+    const N8: usize,
+    const N9: usize,
+    const N10: usize,
+    const N11: usize,
+    const N12: usize,
+> Pads
+    for (
+        Alternate<'a, N1, gpio::DbiB>,
+        Alternate<'b, N2, gpio::DbiB>,
+        Alternate<'c, N3, gpio::DbiB>,
+        Alternate<'d, N4, gpio::DbiB>,
+        (
+            Alternate<'e, N5, gpio::DbiB>,
+            Alternate<'f, N6, gpio::DbiB>,
+            Alternate<'g, N7, gpio::DbiB>,
+            Alternate<'h, N8, gpio::DbiB>,
+            Alternate<'i, N9, gpio::DbiB>,
+            Alternate<'j, N10, gpio::DbiB>,
+            Alternate<'k, N11, gpio::DbiB>,
+            Alternate<'l, N12, gpio::DbiB>,
+        ),
+    )
+where
+    Alternate<'a, N1, gpio::DbiB>: HasCsSignal,
+    Alternate<'b, N2, gpio::DbiB>: HasDcSignal,
+    Alternate<'c, N3, gpio::DbiB>: HasWrSignal,
+    Alternate<'d, N4, gpio::DbiB>: HasRdSignal,
+    Alternate<'e, N5, gpio::DbiB>: HasDataSignal<0>,
+    Alternate<'f, N6, gpio::DbiB>: HasDataSignal<1>,
+    Alternate<'g, N7, gpio::DbiB>: HasDataSignal<2>,
+    Alternate<'h, N8, gpio::DbiB>: HasDataSignal<3>,
+    Alternate<'i, N9, gpio::DbiB>: HasDataSignal<4>,
+    Alternate<'j, N10, gpio::DbiB>: HasDataSignal<5>,
+    Alternate<'k, N11, gpio::DbiB>: HasDataSignal<6>,
+    Alternate<'l, N12, gpio::DbiB>: HasDataSignal<7>,
+{
+    const TYPE_B: bool = true;
+    const THREE_WIRE: bool = false;
+}
+
+/// Check if target gpio `Pin` is internally connected to DBI type C serial clock signal.
+pub trait HasSclSignal {}
+
+impl<'a> HasSclSignal for Alternate<'a, 0, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 4, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 8, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 12, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 16, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 20, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 24, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 28, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 32, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 36, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 40, gpio::DbiC> {}
+impl<'a> HasSclSignal for Alternate<'a, 44, gpio::DbiC> {}
+
+/// Check if target gpio `Pin` is internally connected to DBI type C serial data signal.
+pub trait HasSdaSignal {}
+
+impl<'a> HasSdaSignal for Alternate<'a, 1, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 5, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 9, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 13, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 17, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 21, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 25, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 29, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 33, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 37, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 41, gpio::DbiC> {}
+impl<'a> HasSdaSignal for Alternate<'a, 45, gpio::DbiC> {}
+
+/// Check if target gpio `Pin` is internally connected to DBI chip select signal.
+pub trait HasCsSignal {}
+
+impl<'a> HasCsSignal for Alternate<'a, 2, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 6, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 10, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 14, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 18, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 22, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 26, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 30, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 34, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 38, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 42, gpio::DbiC> {}
+impl<'a> HasCsSignal for Alternate<'a, 0, gpio::DbiB> {}
+impl<'a> HasCsSignal for Alternate<'a, 12, gpio::DbiB> {}
+impl<'a> HasCsSignal for Alternate<'a, 24, gpio::DbiB> {}
+impl<'a> HasCsSignal for Alternate<'a, 36, gpio::DbiB> {}
+
+/// Check if target gpio `Pin` is internally connected to DBI data/command select signal.
+pub trait HasDcSignal {}
+
+impl<'a> HasDcSignal for Alternate<'a, 3, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 7, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 11, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 15, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 19, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 23, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 27, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 31, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 35, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 39, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 43, gpio::DbiC> {}
+impl<'a> HasDcSignal for Alternate<'a, 1, gpio::DbiB> {}
+impl<'a> HasDcSignal for Alternate<'a, 13, gpio::DbiB> {}
+impl<'a> HasDcSignal for Alternate<'a, 25, gpio::DbiB> {}
+impl<'a> HasDcSignal for Alternate<'a, 37, gpio::DbiB> {}
+
+/// Check if target gpio `Pin` is internally connected to DBI type B write strobe signal.
+pub trait HasWrSignal {}
+
+impl<'a> HasWrSignal for Alternate<'a, 2, gpio::DbiB> {}
+impl<'a> HasWrSignal for Alternate<'a, 14, gpio::DbiB> {}
+impl<'a> HasWrSignal for Alternate<'a, 26, gpio::DbiB> {}
+impl<'a> HasWrSignal for Alternate<'a, 38, gpio::DbiB> {}
+
+/// Check if target gpio `Pin` is internally connected to DBI type B read strobe signal.
+pub trait HasRdSignal {}
+
+impl<'a> HasRdSignal for Alternate<'a, 3, gpio::DbiB> {}
+impl<'a> HasRdSignal for Alternate<'a, 15, gpio::DbiB> {}
+impl<'a> HasRdSignal for Alternate<'a, 27, gpio::DbiB> {}
+impl<'a> HasRdSignal for Alternate<'a, 39, gpio::DbiB> {}
+
+/// Check if target gpio `Pin` is internally connected to DBI type B data signal index `I`.
+pub trait HasDataSignal<const I: usize> {}
+
+impl<'a> HasDataSignal<0> for Alternate<'a, 4, gpio::DbiB> {}
+impl<'a> HasDataSignal<1> for Alternate<'a, 5, gpio::DbiB> {}
+impl<'a> HasDataSignal<2> for Alternate<'a, 6, gpio::DbiB> {}
+impl<'a> HasDataSignal<3> for Alternate<'a, 7, gpio::DbiB> {}
+impl<'a> HasDataSignal<4> for Alternate<'a, 8, gpio::DbiB> {}
+impl<'a> HasDataSignal<5> for Alternate<'a, 9, gpio::DbiB> {}
+impl<'a> HasDataSignal<6> for Alternate<'a, 10, gpio::DbiB> {}
+impl<'a> HasDataSignal<7> for Alternate<'a, 11, gpio::DbiB> {}
+impl<'a> HasDataSignal<0> for Alternate<'a, 16, gpio::DbiB> {}
+impl<'a> HasDataSignal<1> for Alternate<'a, 17, gpio::DbiB> {}
+impl<'a> HasDataSignal<2> for Alternate<'a, 18, gpio::DbiB> {}
+impl<'a> HasDataSignal<3> for Alternate<'a, 19, gpio::DbiB> {}
+impl<'a> HasDataSignal<4> for Alternate<'a, 20, gpio::DbiB> {}
+impl<'a> HasDataSignal<5> for Alternate<'a, 21, gpio::DbiB> {}
+impl<'a> HasDataSignal<6> for Alternate<'a, 22, gpio::DbiB> {}
+impl<'a> HasDataSignal<7> for Alternate<'a, 23, gpio::DbiB> {}
+impl<'a> HasDataSignal<0> for Alternate<'a, 28, gpio::DbiB> {}
+impl<'a> HasDataSignal<1> for Alternate<'a, 29, gpio::DbiB> {}
+impl<'a> HasDataSignal<2> for Alternate<'a, 30, gpio::DbiB> {}
+impl<'a> HasDataSignal<3> for Alternate<'a, 31, gpio::DbiB> {}
+impl<'a> HasDataSignal<4> for Alternate<'a, 32, gpio::DbiB> {}
+impl<'a> HasDataSignal<5> for Alternate<'a, 33, gpio::DbiB> {}
+impl<'a> HasDataSignal<6> for Alternate<'a, 34, gpio::DbiB> {}
+impl<'a> HasDataSignal<7> for Alternate<'a, 35, gpio::DbiB> {}
+impl<'a> HasDataSignal<0> for Alternate<'a, 40, gpio::DbiB> {}
+impl<'a> HasDataSignal<1> for Alternate<'a, 41, gpio::DbiB> {}
+impl<'a> HasDataSignal<2> for Alternate<'a, 42, gpio::DbiB> {}
+impl<'a> HasDataSignal<3> for Alternate<'a, 43, gpio::DbiB> {}
+impl<'a> HasDataSignal<4> for Alternate<'a, 44, gpio::DbiB> {}
+impl<'a> HasDataSignal<5> for Alternate<'a, 45, gpio::DbiB> {}
+
+/// Managed Display Bus Interface peripheral with DMA pixel transfers.
+pub struct DmaDbi<'b, DBI, PADS, T> {
+    dbi: DBI,
+    pads: PADS,
+    channel: TypedChannel<'b, T>,
+    request: T,
+}
+
+impl<'b, DBI: Deref<Target = RegisterBlock>, PADS, T: PeripheralId + Copy>
+    DmaDbi<'b, DBI, PADS, T>
+{
+    /// Starts a DMA pixel transfer of an owned frame buffer, optionally after a command.
+    ///
+    /// The buffer must be word aligned and fill whole transmit queue words, i.e. hold an
+    /// even number of `u16` pixels; other buffers and empty ones are returned back.
+    #[inline]
+    pub fn write_pixels<B, P>(
+        &mut self,
+        command: Option<u8>,
+        buffer: B,
+    ) -> Result<DmaTransfer<'_, 'b, DBI, PADS, T, B>, B>
+    where
+        B: Deref + 'static,
+        B::Target: AsSlice<Element = P>,
+        P: Pixel,
+    {
+        let slice = buffer.as_slice();
+        let (ptr, len) = (slice.as_ptr() as usize, slice.len());
+        if len == 0 || len > MAX_PIXEL_COUNT || len % P::PER_WORD != 0 || ptr % 4 != 0 {
+            return Err(buffer);
+        }
+        self.channel.configure(DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            src_req: None,
+            dst_req: Some(self.request),
+            src_addr_inc: true,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::Word,
+            dst_transfer_width: TransferWidth::Word,
+        });
+        clean_dcache(ptr, core::mem::size_of_val(slice));
+        dbi_start_pixels::<P>(&self.dbi, command, len);
+        let mut transfer = DmaTransfer {
+            dbi: self,
+            buffer: Some(buffer),
+            ptr,
+            words: len / P::PER_WORD,
+            offset: 0,
+            done: false,
+        };
+        transfer.start_chunk();
+        Ok(transfer)
+    }
+
+    /// Release DMA channel and return the blocking DBI peripheral.
+    #[inline]
+    pub fn free(self) -> (Dbi<DBI, PADS>, TypedChannel<'b, T>) {
+        self.channel.stop();
+        unsafe {
+            self.dbi
+                .fifo_config_0
+                .modify(|val| val.disable_dma_transmit())
+        };
+        let dbi = Dbi {
+            dbi: self.dbi,
+            pads: self.pads,
+        };
+        (dbi, self.channel)
+    }
+}
+
+/// Ongoing DMA pixel transfer on an owned frame buffer.
+///
+/// Buffers longer than 4064 words are moved in several chunks; the CPU only restarts the
+/// channel between chunks, when the transfer is polled.
+pub struct DmaTransfer<'s, 'b, DBI: Deref<Target = RegisterBlock>, PADS, T: PeripheralId + Copy, B>
+{
+    dbi: &'s mut DmaDbi<'b, DBI, PADS, T>,
+    buffer: Option<B>,
+    ptr: usize,
+    words: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl<'s, 'b, DBI: Deref<Target = RegisterBlock>, PADS, T: PeripheralId + Copy, B>
+    DmaTransfer<'s, 'b, DBI, PADS, T, B>
+{
+    /// Checks whether the transfer has ended, starting the next chunk if needed.
+    #[inline]
+    pub fn is_done(&mut self) -> bool {
+        self.poll()
+    }
+
+    /// Waits for the transfer to end and returns the buffer.
+    #[inline]
+    pub fn wait(mut self) -> B {
+        while !self.poll() {
+            core::hint::spin_loop();
+        }
+        self.buffer.take().unwrap()
+    }
+
+    #[inline]
+    fn start_chunk(&mut self) {
+        let chunk = core::cmp::min(self.words - self.offset, DMA_CHUNK_LENGTH);
+        let mut lli = [LliPool::new()];
+        self.dbi.channel.lli_config(
+            &mut lli,
+            1,
+            (self.ptr + self.offset * 4) as u32,
+            &self.dbi.dbi.fifo_write as *const _ as u32,
+            0,
+            chunk as u32,
+        );
+        compiler_fence(Ordering::Release);
+        self.dbi.channel.lli_load(&lli[0]);
+        self.dbi.channel.start();
+    }
+
+    #[inline]
+    fn poll(&mut self) -> bool {
+        if self.done {
+            return true;
+        }
+        if self.offset < self.words {
+            if self.dbi.channel.is_busy() {
+                return false;
+            }
+            self.offset += core::cmp::min(self.words - self.offset, DMA_CHUNK_LENGTH);
+            if self.offset < self.words {
+                self.start_chunk();
+                return false;
+            }
+        }
+        // All words are queued, wait for the last pixel to leave the bus.
+        let dbi = &*self.dbi.dbi;
+        if !dbi
+            .interrupt_config
+            .read()
+            .has_interrupt(Interrupt::TransferEnd)
+        {
+            return false;
+        }
+        compiler_fence(Ordering::Acquire);
+        self.finish();
+        true
+    }
+
+    #[inline]
+    fn finish(&mut self) {
+        self.dbi.channel.stop();
+        let dbi = &*self.dbi.dbi;
+        unsafe {
+            dbi.interrupt_config
+                .modify(|val| val.clear_interrupt(Interrupt::TransferEnd));
+            dbi.config.modify(|config| config.disable_master());
+        }
+        self.done = true;
+    }
+}
+
+impl<'s, 'b, DBI: Deref<Target = RegisterBlock>, PADS, T: PeripheralId + Copy, B> Drop
+    for DmaTransfer<'s, 'b, DBI, PADS, T, B>
+{
+    /// Stops the DMA channel and the peripheral before the buffer could be released.
+    #[inline]
+    fn drop(&mut self) {
+        if !self.done {
+            self.finish();
+            unsafe {
+                self.dbi
+                    .dbi
+                    .fifo_config_0
+                    .modify(|val| val.clear_transmit_fifo())
+            };
+        }
+    }
+}
+
+/// Largest pixel count of one pixel transfer.
+const MAX_PIXEL_COUNT: usize = 0xff_ffff;
+/// Pixels buffered on the stack when streaming from iterators.
+const PIXEL_BUFFER_LENGTH: usize = 64;
+/// Maximum words moved by one linked list item.
+const DMA_CHUNK_LENGTH: usize = 4064;
+
+/// Compute durations of the two phases of one bit in peripheral clocks.
+///
+/// Each phase lasts 1 ~ 256 peripheral clocks. The resulting frequency never exceeds
+/// the requested one.
+#[inline]
+fn dbi_timing(source: Hertz, frequency: Hertz) -> Result<[u8; 2], ConfigError> {
+    if frequency.0 == 0 {
+        return Err(ConfigError::FrequencyTooLow);
+    }
+    let total = source.0.div_ceil(frequency.0);
+    if total < 2 {
+        return Err(ConfigError::FrequencyTooHigh);
+    }
+    if total > 2 * 256 {
+        return Err(ConfigError::FrequencyTooLow);
+    }
+    let phase_0 = total.div_ceil(2);
+    let phase_1 = total - phase_0;
+    Ok([(phase_0 - 1) as u8, (phase_1 - 1) as u8])
+}
+
+/// Starts a transfer with given function configuration.
+#[inline]
+fn dbi_start(dbi: &RegisterBlock, config: FunctionConfig) {
+    unsafe {
+        dbi.interrupt_config
+            .modify(|val| val.clear_interrupt(Interrupt::TransferEnd));
+        dbi.config.write(config.disable_master());
+        dbi.config.write(config.enable_master());
+    }
+}
+
+/// Waits for the ongoing transfer to end and stops the peripheral.
+#[inline]
+fn dbi_wait(dbi: &RegisterBlock) {
+    while !dbi
+        .interrupt_config
+        .read()
+        .has_interrupt(Interrupt::TransferEnd)
+    {
+        core::hint::spin_loop();
+    }
+    unsafe {
+        dbi.interrupt_config
+            .modify(|val| val.clear_interrupt(Interrupt::TransferEnd));
+        dbi.config.modify(|config| config.disable_master());
+    }
+}
+
+/// Sends an optional command and up to four bytes of normal data.
+#[inline]
+fn dbi_write_normal(dbi: &RegisterBlock, command: Option<u8>, data: &[u8]) {
+    let mut config = dbi.config.read().set_data_write().set_data_normal();
+    config = match command {
+        Some(command) => config.enable_command().set_command(command),
+        None => config.disable_command(),
+    };
+    config = match data.len() {
+        0 => config.disable_data(),
+        len => {
+            let word = data
+                .iter()
+                .enumerate()
+                .fold(0, |word, (i, &byte)| word | (byte as u32) << (8 * i));
+            unsafe { dbi.data_write.write(word) };
+            config.enable_data().set_data_byte_count(len as u8 - 1)
+        }
+    };
+    dbi_start(dbi, config);
+    dbi_wait(dbi);
+}
+
+/// Starts a pixel transfer of `count` pixels, optionally after a command.
+#[inline]
+fn dbi_start_pixels<P: Pixel>(dbi: &RegisterBlock, command: Option<u8>, count: usize) {
+    unsafe {
+        dbi.pixel_count.write(
+            PixelCount(0)
+                .set_pixel_count(count as u32)
+                .set_pixel_format(P::FORMAT),
+        );
+        dbi.fifo_config_0
+            .modify(|val| val.set_input_format(P::INPUT_FORMAT));
+    }
+    let mut config = dbi
+        .config
+        .read()
+        .set_data_write()
+        .set_data_pixel()
+        .enable_data();
+    config = match command {
+        Some(command) => config.enable_command().set_command(command),
+        None => config.disable_command(),
+    };
+    dbi_start(dbi, config);
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        ConfigError, Dbi, DmaDbi, Error, FifoConfig0, InputFormat, Interrupt, InterruptConfig,
+        Period, PixelCount, PixelFormat, RegisterBlock, dbi_timing,
+    };
+    use crate::dma::mock::{MockDma, MockRequest};
+    use core::cell::UnsafeCell;
     use core::mem::offset_of;
+    use core::ops::Deref;
+    use core::ptr::addr_of_mut;
+    use display_interface::{DataFormat, WriteOnlyDataCommand};
+    use embedded_time::rate::Hertz;
+
+    /// Register block in memory, with every transfer ended and room in the queue.
+    struct MockDbi(UnsafeCell<[u32; 0x23]>);
+
+    impl MockDbi {
+        fn new(data_read: u32) -> Dbi<MockDbi, ()> {
+            let mut regs = [0u32; 0x23];
+            regs[0x14 / 4] = data_read;
+            regs[0x18 / 4] = 1 << (Interrupt::TransferEnd as u32);
+            regs[0x84 / 4] = 0x8;
+            Dbi {
+                dbi: MockDbi(UnsafeCell::new(regs)),
+                pads: (),
+            }
+        }
+    }
+
+    impl Deref for MockDbi {
+        type Target = RegisterBlock;
+
+        fn deref(&self) -> &RegisterBlock {
+            unsafe { &*(self.0.get() as *const RegisterBlock) }
+        }
+    }
+
+    impl MockDbi {
+        /// Raise or clear transfer end state, as the bus finishes or starts sending pixels.
+        fn set_transfer_end(&self, raised: bool) {
+            unsafe {
+                (*self.0.get())[0x18 / 4] = (raised as u32) << (Interrupt::TransferEnd as u32)
+            };
+        }
+    }
+
+    fn dma_dbi(dma: &MockDma) -> DmaDbi<'_, MockDbi, (), MockRequest> {
+        let request = MockRequest {
+            id: 22,
+            address: 0x3000_1088,
+        };
+        MockDbi::new(0).with_dma((dma.channel(0), request))
+    }
 
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, pixel_count), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, period), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, data_write), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, data_read), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_config), 0x18);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_0), 0x80);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, fifo_write), 0x88);
+    }
+
+    #[test]
+    fn struct_pixel_count_functions() {
+        let mut val = PixelCount(0).set_pixel_count(0x12_3456);
+        assert_eq!(val.pixel_count(), 0x12_3456);
+        assert_eq!(val.0, 0x0012_3456);
+        val = val.set_pixel_format(PixelFormat::Rgb565);
+        assert_eq!(val.pixel_format(), PixelFormat::Rgb565);
+        assert_eq!(val.0, 0x8012_3456);
+        val = val.set_pixel_format(PixelFormat::Rgb888);
+        assert_eq!(val.pixel_format(), PixelFormat::Rgb888);
+        assert_eq!(val.0, 0x0012_3456);
+    }
+
+    #[test]
+    fn struct_period_functions() {
+        let val = Period(0)
+            .set_start_condition(0x12)
+            .set_interval(0x34)
+            .set_data_phase_0(0x56)
+            .set_data_phase_1(0x78);
+        assert_eq!(val.0, 0x7856_3412);
+        assert_eq!(val.start_condition(), 0x12);
+        assert_eq!(val.interval(), 0x34);
+        assert_eq!(val.data_phase_0(), 0x56);
+        assert_eq!(val.data_phase_1(), 0x78);
+    }
+
+    #[test]
+    fn struct_interrupt_config_functions() {
+        let mut val = InterruptConfig(0x1);
+        assert!(val.has_interrupt(Interrupt::TransferEnd));
+        assert!(!val.has_interrupt(Interrupt::FifoError));
+        val = val.mask_interrupt(Interrupt::TransmitFifoReady);
+        assert!(val.is_interrupt_masked(Interrupt::TransmitFifoReady));
+        assert_eq!(val.0, 0x0000_0201);
+        val = val.unmask_interrupt(Interrupt::TransmitFifoReady);
+        assert!(!val.is_interrupt_masked(Interrupt::TransmitFifoReady));
+        val = val.clear_interrupt(Interrupt::TransferEnd);
+        assert_eq!(val.0, 0x0001_0001);
+        val = InterruptConfig(0).enable_interrupt(Interrupt::FifoError);
+        assert!(val.is_interrupt_enabled(Interrupt::FifoError));
+        assert_eq!(val.0, 0x0400_0000);
+        val = val.disable_interrupt(Interrupt::FifoError);
+        assert!(!val.is_interrupt_enabled(Interrupt::FifoError));
+    }
+
+    #[test]
+    fn struct_fifo_config_0_functions() {
+        let mut val = FifoConfig0(0).set_input_format(InputFormat::Rgb565);
+        assert_eq!(val.input_format(), InputFormat::Rgb565);
+        assert_eq!(val.0, 0x0600_0000);
+        val = val.set_input_format(InputFormat::Nrgb8888);
+        assert_eq!(val.input_format(), InputFormat::Nrgb8888);
+        assert_eq!(val.0, 0x0000_0000);
+    }
+
+    #[test]
+    fn function_dbi_timing() {
+        let source = Hertz(160_000_000);
+        assert_eq!(dbi_timing(source, Hertz(40_000_000)), Ok([1, 1]));
+        assert_eq!(dbi_timing(source, Hertz(30_000_000)), Ok([2, 2]));
+        assert_eq!(dbi_timing(source, Hertz(80_000_000)), Ok([0, 0]));
+        assert_eq!(dbi_timing(source, Hertz(400_000)), Ok([199, 199]));
+        assert_eq!(
+            dbi_timing(source, Hertz(200_000_000)),
+            Err(ConfigError::FrequencyTooHigh)
+        );
+        assert_eq!(
+            dbi_timing(source, Hertz(100_000)),
+            Err(ConfigError::FrequencyTooLow)
+        );
+        assert_eq!(
+            dbi_timing(source, Hertz(0)),
+            Err(ConfigError::FrequencyTooLow)
+        );
+    }
+
+    #[test]
+    fn blocking_dbi_command_and_read() {
+        let mut dbi = MockDbi::new(0x0052_8185);
+        dbi.write_command(0x2a, &[0x00, 0x00, 0x00, 0xef, 0x01]);
+        let config = dbi.dbi.config.read();
+        assert!(!config.is_master_enabled());
+        assert!(!config.is_command_enabled());
+        assert!(config.is_data_enabled());
+        assert_eq!(config.data_byte_count(), 0);
+        assert_eq!(dbi.dbi.data_write.read(), 0x01);
+
+        dbi.write_command(0x11, &[]);
+        let config = dbi.dbi.config.read();
+        assert!(config.is_command_enabled());
+        assert!(!config.is_data_enabled());
+        assert_eq!(config.command(), 0x11);
+
+        let mut id = [0u8; 3];
+        dbi.read(0x04, 1, &mut id).unwrap();
+        assert_eq!(id, [0x85, 0x81, 0x52]);
+        assert!(dbi.dbi.config.read().is_data_write());
+        assert_eq!(dbi.read(0x04, 0, &mut [0u8; 5]), Err(Error::ReadTooLong));
+        assert_eq!(dbi.read(0x04, 17, &mut id), Err(Error::TooManyDummyCycles));
+    }
+
+    #[test]
+    fn display_interface_send_data() {
+        let mut dbi = MockDbi::new(0);
+        dbi.send_commands(DataFormat::U8(&[0x2c])).unwrap();
+        assert_eq!(dbi.dbi.config.read().command(), 0x2c);
+
+        let mut pixels = [0x1234u16, 0x5678, 0x9abc];
+        dbi.send_data(DataFormat::U16BE(&mut pixels)).unwrap();
+        let config = dbi.dbi.config.read();
+        assert!(config.is_data_pixel());
+        assert!(!config.is_command_enabled());
+        assert_eq!(dbi.dbi.pixel_count.read().pixel_count(), 3);
+        assert_eq!(
+            dbi.dbi.pixel_count.read().pixel_format(),
+            PixelFormat::Rgb565
+        );
+        assert_eq!(unsafe { (*dbi.dbi.0.get())[0x88 / 4] }, 0x9abc);
+
+        dbi.send_data(DataFormat::U16LEIter(&mut [0x1234u16, 0x5678].into_iter()))
+            .unwrap();
+        assert_eq!(dbi.dbi.pixel_count.read().pixel_count(), 2);
+        assert_eq!(unsafe { (*dbi.dbi.0.get())[0x88 / 4] }, 0x7856_3412);
+
+        dbi.send_data(DataFormat::U8(&[0xaa, 0x55])).unwrap();
+        let config = dbi.dbi.config.read();
+        assert!(config.is_data_normal());
+        assert_eq!(config.data_byte_count(), 1);
+        assert_eq!(dbi.dbi.data_write.read(), 0x55aa);
+    }
+
+    #[test]
+    fn struct_dma_dbi_buffer_checks() {
+        static mut BUFFER: [u32; 4] = [0; 4];
+        let dma = MockDma::new();
+        let mut dbi = dma_dbi(&dma);
+        assert!(dbi.dbi.fifo_config_0.read().is_dma_transmit_enabled());
+        let pixels = || unsafe { &mut *(addr_of_mut!(BUFFER) as *mut [u16; 8]) };
+        // Unaligned start, odd number of 16-bit pixels and empty buffer.
+        assert!(dbi.write_pixels(None, &pixels()[1..5]).is_err());
+        assert!(dbi.write_pixels(None, &pixels()[..3]).is_err());
+        assert!(dbi.write_pixels(None, &pixels()[..0]).is_err());
+        // Nothing was started.
+        assert!(!dbi.dbi.config.read().is_master_enabled());
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+    }
+
+    #[test]
+    fn struct_dma_dbi_chunked_write() {
+        static mut BUFFER: [u32; 4066] = [0; 4066];
+        let dma = MockDma::new();
+        let mut dbi = dma_dbi(&dma);
+        let buffer = unsafe { &*addr_of_mut!(BUFFER) };
+        let ptr = buffer.as_ptr() as u32;
+        let mut transfer = dbi.write_pixels(Some(0x2c), buffer).ok().unwrap();
+        // Pixel transfer covers the whole buffer, DMA moves the first 4064 words.
+        let config = transfer.dbi.dbi.config.read();
+        assert!(config.is_master_enabled() && config.is_data_pixel());
+        assert!(config.is_command_enabled());
+        assert_eq!(config.command(), 0x2c);
+        let pixel_count = transfer.dbi.dbi.pixel_count.read();
+        assert_eq!(pixel_count.pixel_count(), 4066);
+        assert_eq!(pixel_count.pixel_format(), PixelFormat::Rgb888);
+        assert_eq!(dma.channel_reg(0, 0x00), ptr);
+        let fifo_write = &transfer.dbi.dbi.fifo_write as *const _ as u32;
+        assert_eq!(dma.channel_reg(0, 0x04), fifo_write);
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 4064);
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 1);
+        transfer.dbi.dbi.set_transfer_end(false);
+        assert!(!transfer.is_done());
+
+        // Second chunk continues where DMA stopped.
+        dma.complete(0);
+        assert!(!transfer.is_done());
+        assert_eq!(dma.channel_reg(0, 0x00), ptr + 4064 * 4);
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 2);
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 1);
+
+        // Every word is queued, but the last pixels are still on the bus.
+        dma.complete(0);
+        assert!(!transfer.is_done());
+        assert!(transfer.dbi.dbi.config.read().is_master_enabled());
+
+        transfer.dbi.dbi.set_transfer_end(true);
+        let returned = transfer.wait();
+        assert_eq!(returned.as_ptr() as u32, ptr);
+        assert!(!dbi.dbi.config.read().is_master_enabled());
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+
+        let (dbi, _) = dbi.free();
+        assert!(!dbi.dbi.fifo_config_0.read().is_dma_transmit_enabled());
+    }
+
+    #[test]
+    fn struct_dma_dbi_drop() {
+        static mut BUFFER: [u32; 2] = [0; 2];
+        let dma = MockDma::new();
+        let mut dbi = dma_dbi(&dma);
+        dbi.dbi.set_transfer_end(false);
+        let buffer = unsafe { &*(addr_of_mut!(BUFFER) as *const [u16; 4]) };
+        let transfer = dbi.write_pixels(None, buffer).ok().unwrap();
+        assert_eq!(transfer.dbi.dbi.pixel_count.read().pixel_count(), 4);
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 2);
+        // Dropping an ongoing transfer stops both DMA channel and peripheral.
+        drop(transfer);
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+        assert!(!dbi.dbi.config.read().is_master_enabled());
     }
 }
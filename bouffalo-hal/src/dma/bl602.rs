@@ -0,0 +1,92 @@
+//! BL602 DMA peripheral requests and data register addresses.
+
+use super::PeripheralId;
+
+/// Peripheral for DMA 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Periph4Dma0 {
+    /// UART0 receive.
+    Uart0Rx,
+    /// UART0 transmit.
+    Uart0Tx,
+    /// UART1 receive.
+    Uart1Rx,
+    /// UART1 transmit.
+    Uart1Tx,
+    /// I2C0 receive.
+    I2c0Rx = 6,
+    /// I2C0 transmit.
+    I2c0Tx,
+    /// SPI0 receive.
+    Spi0Rx = 10,
+    /// SPI0 transmit.
+    Spi0Tx,
+    /// GPADC.
+    GpAdc = 22,
+    /// GPDAC.
+    GpDac,
+}
+
+impl PeripheralId for Periph4Dma0 {
+    #[inline]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+    #[inline]
+    fn address(&self) -> Option<u32> {
+        self.data_register().map(|addr| addr as u32)
+    }
+}
+
+impl Periph4Dma0 {
+    /// Get data register of this request, if it has a fixed one.
+    #[inline]
+    pub const fn data_register(self) -> Option<DmaAddr> {
+        match self {
+            Periph4Dma0::Uart0Rx => Some(DmaAddr::Uart0Rx),
+            Periph4Dma0::Uart0Tx => Some(DmaAddr::Uart0Tx),
+            Periph4Dma0::Uart1Rx => Some(DmaAddr::Uart1Rx),
+            Periph4Dma0::Uart1Tx => Some(DmaAddr::Uart1Tx),
+            Periph4Dma0::I2c0Rx => Some(DmaAddr::I2c0Rx),
+            Periph4Dma0::I2c0Tx => Some(DmaAddr::I2c0Tx),
+            Periph4Dma0::Spi0Rx => Some(DmaAddr::Spi0Rx),
+            Periph4Dma0::Spi0Tx => Some(DmaAddr::Spi0Tx),
+            Periph4Dma0::GpAdc => Some(DmaAddr::AdcRx),
+            Periph4Dma0::GpDac => Some(DmaAddr::DacTx),
+        }
+    }
+}
+
+/// DMA peripheral data register address definition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DmaAddr {
+    Uart0Tx = 0x4000A000 + 0x88,
+    Uart0Rx = 0x4000A000 + 0x8C,
+    Uart1Tx = 0x4000A100 + 0x88,
+    Uart1Rx = 0x4000A100 + 0x8C,
+    I2c0Tx = 0x4000A300 + 0x88,
+    I2c0Rx = 0x4000A300 + 0x8C,
+    Spi0Tx = 0x4000A200 + 0x88,
+    Spi0Rx = 0x4000A200 + 0x8C,
+    AdcRx = 0x40002000 + 0x04,
+    DacTx = 0x40002000 + 0x48,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DmaAddr, Periph4Dma0};
+    use crate::dma::{Endpoint, PeripheralId};
+
+    #[test]
+    fn function_data_register() {
+        assert_eq!(Periph4Dma0::Spi0Tx.id(), 11);
+        assert_eq!(Periph4Dma0::GpAdc.id(), 22);
+        assert_eq!(Periph4Dma0::GpDac.id(), 23);
+        assert_eq!(Periph4Dma0::Uart0Tx.data_register(), Some(DmaAddr::Uart0Tx));
+        assert_eq!(Periph4Dma0::GpAdc.address(), Some(0x40002004));
+
+        let endpoint = Endpoint::new(Periph4Dma0::Uart1Rx).unwrap();
+        assert_eq!(endpoint.request(), Periph4Dma0::Uart1Rx);
+        assert_eq!(endpoint.address(), 0x4000A18C);
+    }
+}
@@ -0,0 +1,199 @@
+//! BL808 DMA peripheral requests and data register addresses.
+
+use super::PeripheralId;
+
+/// Peripheral for DMA 0/1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Periph4Dma01 {
+    /// UART0 receive.
+    Uart0Rx,
+    /// UART0 transmit.
+    Uart0Tx,
+    /// UART1 receive.
+    Uart1Rx,
+    /// UART1 transmit.
+    Uart1Tx,
+    /// UART2 receive.
+    Uart2Rx,
+    /// UART2 transmit.
+    Uart2Tx,
+    /// I2C0 receive.
+    I2c0Rx,
+    /// I2C0 transmit.
+    I2c0Tx,
+    /// IR transmit.
+    IrTx,
+    /// GPIO transmit.
+    GpioTx,
+    /// SPI0 receive.
+    Spi0Rx,
+    /// SPI0 transmit.
+    Spi0Tx,
+    /// AUDIO receive.
+    AudioRx,
+    /// AUDIO transmit.
+    AudioTx,
+    /// I2C1 receive.
+    I2c1Rx,
+    /// I2C1 transmit.
+    I2c1Tx,
+    /// I2S receive.
+    I2sRx,
+    /// I2S transmit.
+    I2sTx,
+    /// PDM receive.
+    PdmRx,
+    /// GPADC.
+    GpAdc = 22,
+    /// GPDAC.
+    GpDac,
+}
+
+/// Peripheral for DMA 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Periph4Dma2 {
+    /// UART3 receive.
+    Uart3Rx,
+    /// UART3 transmit.
+    Uart3Tx,
+    /// SPI1 receive.
+    Spi1Rx,
+    /// SPI1 transmit.
+    Spi1Tx,
+    /// I2C2 receive.
+    I2c2Rx = 6,
+    /// I2C2 transmit.
+    I2c2Tx,
+    /// I2C3 receive.
+    I2c3Rx,
+    /// I2C3 transmit.
+    I2c3Tx,
+    /// DSI receive.
+    DsiRx,
+    /// DSI transmit.
+    DsiTx,
+    /// DBI receive.
+    DbiTx = 22,
+}
+
+impl PeripheralId for Periph4Dma01 {
+    #[inline]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+    #[inline]
+    fn address(&self) -> Option<u32> {
+        self.data_register().map(|addr| addr as u32)
+    }
+}
+impl PeripheralId for Periph4Dma2 {
+    #[inline]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+    #[inline]
+    fn address(&self) -> Option<u32> {
+        self.data_register().map(|addr| addr as u32)
+    }
+}
+
+impl Periph4Dma01 {
+    /// Get data register of this request, if it has a fixed one.
+    #[inline]
+    pub const fn data_register(self) -> Option<DmaAddr> {
+        match self {
+            Periph4Dma01::Uart0Rx => Some(DmaAddr::Uart0Rx),
+            Periph4Dma01::Uart0Tx => Some(DmaAddr::Uart0Tx),
+            Periph4Dma01::Uart1Rx => Some(DmaAddr::Uart1Rx),
+            Periph4Dma01::Uart1Tx => Some(DmaAddr::Uart1Tx),
+            Periph4Dma01::Uart2Rx => Some(DmaAddr::Uart2Rx),
+            Periph4Dma01::Uart2Tx => Some(DmaAddr::Uart2Tx),
+            Periph4Dma01::I2c0Rx => Some(DmaAddr::I2c0Rx),
+            Periph4Dma01::I2c0Tx => Some(DmaAddr::I2c0Tx),
+            Periph4Dma01::IrTx => Some(DmaAddr::IrTx),
+            Periph4Dma01::GpioTx => Some(DmaAddr::WoTx),
+            Periph4Dma01::Spi0Rx => Some(DmaAddr::Spi0Rx),
+            Periph4Dma01::Spi0Tx => Some(DmaAddr::Spi0Tx),
+            Periph4Dma01::I2c1Rx => Some(DmaAddr::I2c1Rx),
+            Periph4Dma01::I2c1Tx => Some(DmaAddr::I2c1Tx),
+            Periph4Dma01::I2sRx => Some(DmaAddr::I2sRx),
+            Periph4Dma01::I2sTx => Some(DmaAddr::I2sTx),
+            Periph4Dma01::GpAdc => Some(DmaAddr::AdcRx),
+            Periph4Dma01::GpDac => Some(DmaAddr::DacTx),
+            Periph4Dma01::AudioRx | Periph4Dma01::AudioTx | Periph4Dma01::PdmRx => None,
+        }
+    }
+}
+
+impl Periph4Dma2 {
+    /// Get data register of this request, if it has a fixed one.
+    #[inline]
+    pub const fn data_register(self) -> Option<DmaAddr> {
+        match self {
+            Periph4Dma2::Uart3Rx => Some(DmaAddr::Uart3Rx),
+            Periph4Dma2::Uart3Tx => Some(DmaAddr::Uart3Tx),
+            Periph4Dma2::Spi1Rx => Some(DmaAddr::Spi1Rx),
+            Periph4Dma2::Spi1Tx => Some(DmaAddr::Spi1Tx),
+            Periph4Dma2::I2c2Rx => Some(DmaAddr::I2c2Rx),
+            Periph4Dma2::I2c2Tx => Some(DmaAddr::I2c2Tx),
+            Periph4Dma2::I2c3Rx => Some(DmaAddr::I2c3Rx),
+            Periph4Dma2::I2c3Tx => Some(DmaAddr::I2c3Tx),
+            Periph4Dma2::DsiRx | Periph4Dma2::DsiTx | Periph4Dma2::DbiTx => None,
+        }
+    }
+}
+
+/// DMA peripheral data register address definition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DmaAddr {
+    Uart0Tx = 0x2000A000 + 0x88,
+    Uart0Rx = 0x2000A000 + 0x8C,
+    Uart1Tx = 0x2000A100 + 0x88,
+    Uart1Rx = 0x2000A100 + 0x8C,
+    Uart2Tx = 0x2000AA00 + 0x88,
+    Uart2Rx = 0x2000AA00 + 0x8C,
+    Uart3Tx = 0x30002000 + 0x88,
+    Uart3Rx = 0x30002000 + 0x8C,
+    I2c0Tx = 0x2000A300 + 0x88,
+    I2c0Rx = 0x2000A300 + 0x8C,
+    I2c1Tx = 0x2000A900 + 0x88,
+    I2c1Rx = 0x2000A900 + 0x8C,
+    I2c2Tx = 0x30003000 + 0x88,
+    I2c2Rx = 0x30003000 + 0x8C,
+    I2c3Tx = 0x30004000 + 0x88,
+    I2c3Rx = 0x30004000 + 0x8C,
+    Spi0Tx = 0x2000A200 + 0x88,
+    Spi0Rx = 0x2000A200 + 0x8C,
+    Spi1Tx = 0x30008000 + 0x88,
+    Spi1Rx = 0x30008000 + 0x8C,
+    I2sTx = 0x2000AB00 + 0x88,
+    I2sRx = 0x2000AB00 + 0x8C,
+    AdcRx = 0x20002000 + 0x04,
+    DacTx = 0x20002000 + 0x48,
+    IrTx = 0x2000A600 + 0x88,
+    WoTx = 0x20000000 + 0xB04,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DmaAddr, Periph4Dma01, Periph4Dma2};
+    use crate::dma::{Endpoint, PeripheralId};
+
+    #[test]
+    fn function_data_register() {
+        assert_eq!(
+            Periph4Dma01::Uart0Tx.data_register(),
+            Some(DmaAddr::Uart0Tx)
+        );
+        assert_eq!(Periph4Dma01::GpAdc.address(), Some(0x20002004));
+        assert_eq!(Periph4Dma01::GpioTx.address(), Some(0x20000B04));
+        assert_eq!(Periph4Dma01::PdmRx.address(), None);
+        assert_eq!(Periph4Dma2::Spi1Rx.address(), Some(0x3000808C));
+        assert_eq!(Periph4Dma2::DbiTx.address(), None);
+
+        let endpoint = Endpoint::new(Periph4Dma01::I2sTx).unwrap();
+        assert_eq!(endpoint.request(), Periph4Dma01::I2sTx);
+        assert_eq!(endpoint.address(), 0x2000AB88);
+        assert_eq!(Endpoint::new(Periph4Dma01::AudioRx), None);
+    }
+}
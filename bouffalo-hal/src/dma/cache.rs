@@ -0,0 +1,113 @@
+//! Data cache maintenance for memory shared with DMA.
+//!
+//! DMA controllers do not snoop the write-back data cache of the BL808 C906 (DSP) core, so
+//! buffers must be written back before DMA reads them and discarded before the CPU reads
+//! what DMA wrote. On cores without such a cache these functions do nothing.
+//!
+//! Alternatively, `bouffalo-rt` places statics in `.nocache` link section into memory the
+//! DSP core does not cache, e.g. `#[unsafe(link_section = ".nocache")]` on linked list items.
+//! Like `.bss`, this section is zeroed on startup, so it holds zero-initialized statics only.
+
+/// Data cache line size in bytes.
+pub const CACHE_LINE_SIZE: usize = 64;
+
+/// Write back cached data of `len` bytes from `address` to memory, so DMA reads it.
+#[inline]
+pub fn clean_dcache(address: usize, len: usize) {
+    for (line, _) in lines(address, len) {
+        c906::clean_line(line);
+    }
+    c906::sync();
+}
+
+/// Write back and discard cached data of `len` bytes from `address`.
+///
+/// Use on a buffer before DMA writes it, so no dirty line is written over DMA data later.
+#[inline]
+pub fn flush_dcache(address: usize, len: usize) {
+    for (line, _) in lines(address, len) {
+        c906::flush_line(line);
+    }
+    c906::sync();
+}
+
+/// Discard cached data of `len` bytes from `address`, so CPU reads what DMA wrote.
+///
+/// Lines only partly covered by the range are written back before discarded.
+///
+/// # Safety
+///
+/// CPU writes to the range that were not written back to memory are lost.
+#[inline]
+pub unsafe fn invalidate_dcache(address: usize, len: usize) {
+    for (line, whole) in lines(address, len) {
+        match whole {
+            true => c906::invalidate_line(line),
+            false => c906::flush_line(line),
+        }
+    }
+    c906::sync();
+}
+
+/// Start addresses of cache lines covering a range, and whether the range covers each line.
+#[inline]
+fn lines(address: usize, len: usize) -> impl Iterator<Item = (usize, bool)> {
+    let start = address & !(CACHE_LINE_SIZE - 1);
+    let end = address + len;
+    (start..end)
+        .step_by(CACHE_LINE_SIZE)
+        .map(move |line| (line, line >= address && line + CACHE_LINE_SIZE <= end))
+}
+
+/// T-Head cache instructions, encoded with `a0` as the address operand.
+#[cfg(target_arch = "riscv64")]
+mod c906 {
+    use core::arch::asm;
+
+    /// `dcache.cva a0`
+    #[inline(always)]
+    pub fn clean_line(line: usize) {
+        unsafe { asm!(".long 0x0255000b", in("a0") line, options(nostack)) }
+    }
+    /// `dcache.civa a0`
+    #[inline(always)]
+    pub fn flush_line(line: usize) {
+        unsafe { asm!(".long 0x0275000b", in("a0") line, options(nostack)) }
+    }
+    /// `dcache.iva a0`
+    #[inline(always)]
+    pub fn invalidate_line(line: usize) {
+        unsafe { asm!(".long 0x0265000b", in("a0") line, options(nostack)) }
+    }
+    /// `sync`, waits for cache operations to complete.
+    #[inline(always)]
+    pub fn sync() {
+        unsafe { asm!(".long 0x0180000b", options(nostack)) }
+    }
+}
+
+#[cfg(not(target_arch = "riscv64"))]
+mod c906 {
+    #[inline(always)]
+    pub fn clean_line(_line: usize) {}
+    #[inline(always)]
+    pub fn flush_line(_line: usize) {}
+    #[inline(always)]
+    pub fn invalidate_line(_line: usize) {}
+    #[inline(always)]
+    pub fn sync() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lines;
+
+    #[test]
+    fn function_lines() {
+        assert!(lines(0x1000, 0).eq([]));
+        assert!(lines(0x1000, 0x80).eq([(0x1000, true), (0x1040, true)]));
+        assert!(lines(0x1010, 0x40).eq([(0x1000, false), (0x1040, false)]));
+        assert!(lines(0x1010, 0x90).eq([(0x1000, false), (0x1040, true), (0x1080, false)]));
+        assert!(lines(0x107f, 1).eq([(0x1040, false)]));
+    }
+}
@@ -4,6 +4,7 @@ use core::ops::{Deref, DerefMut};
 use crate::glb;
 
 use super::LliPool;
+use super::cache::clean_dcache;
 use super::config::{DmaChannelConfig, Mem2MemChannelConfig, PeripheralId};
 use super::register::{
     ErrorClear, LliTransfer, RegisterBlock, TransferCompleteClear, TransferWidth,
@@ -43,8 +44,8 @@ pub struct FourChannels<'a, T> {
 
 /// Channel with dedicated peripheral type.
 pub struct TypedChannel<'a, T> {
-    inner: UntypedChannel<'a>,
-    _type_of_peripheral: PhantomData<T>,
+    pub(super) inner: UntypedChannel<'a>,
+    pub(super) _type_of_peripheral: PhantomData<T>,
 }
 
 impl<'a, T: PeripheralId> TypedChannel<'a, T> {
@@ -117,8 +118,8 @@ impl<'a, T> DerefMut for TypedChannel<'a, T> {
 
 /// Channel without a dedicated peripheral type.
 pub struct UntypedChannel<'a> {
-    dma: &'a RegisterBlock,
-    channel_id: usize,
+    pub(super) dma: &'a RegisterBlock,
+    pub(super) channel_id: usize,
 }
 
 impl<'a> UntypedChannel<'a> {
@@ -202,11 +203,13 @@ impl<'a> UntypedChannel<'a> {
 
             lli_pool[i as usize].control = ctrl_cfg;
         }
+        clean_lli(&lli_pool[..lli_count as usize]);
     }
     /// Enable linked list continous mode.
     #[inline]
     pub fn lli_link_head(&self, lli_pool: &mut [LliPool], used_count: usize) {
         lli_pool[used_count - 1].next_lli = (&lli_pool[0] as *const LliPool) as u32;
+        clean_lli(&lli_pool[used_count - 1..used_count]);
         unsafe {
             self.dma.channels[self.channel_id]
                 .linked_list_item
@@ -270,21 +273,39 @@ impl<'a> UntypedChannel<'a> {
             }
         }
 
+        clean_lli(&lli_pool[..lli_count_used_offset]);
+        self.lli_load(&lli_pool[0]);
+        lli_count_used_offset as i32
+    }
+    /// Load the first linked list item into channel registers.
+    #[inline]
+    pub fn lli_load(&self, lli: &LliPool) {
         unsafe {
             self.dma.channels[self.channel_id]
                 .source_address
-                .write(lli_pool[0].src_addr);
+                .write(lli.src_addr);
             self.dma.channels[self.channel_id]
                 .destination_address
-                .write(lli_pool[0].dst_addr);
+                .write(lli.dst_addr);
             self.dma.channels[self.channel_id]
                 .linked_list_item
-                .write(lli_pool[0].next_lli);
+                .write(lli.next_lli);
             self.dma.channels[self.channel_id]
                 .control
-                .write(lli_pool[0].control);
+                .write(lli.control);
         }
-        lli_count_used_offset as i32
+    }
+    /// Get current source address of the ongoing transfer.
+    #[inline]
+    pub fn source_address(&self) -> u32 {
+        self.dma.channels[self.channel_id].source_address.read()
+    }
+    /// Get current destination address of the ongoing transfer.
+    #[inline]
+    pub fn destination_address(&self) -> u32 {
+        self.dma.channels[self.channel_id]
+            .destination_address
+            .read()
     }
     /// Start DMA transfer.
     #[inline]
@@ -314,6 +335,12 @@ impl<'a> UntypedChannel<'a> {
     }
 }
 
+/// Write back linked list items, which DMA fetches from memory.
+#[inline]
+pub(super) fn clean_lli(lli: &[LliPool]) {
+    clean_dcache(lli.as_ptr() as usize, core::mem::size_of_val(lli));
+}
+
 impl<'a, T> EightChannels<'a, T> {
     #[doc(hidden)]
     pub fn __new<const D: usize>(dma: &'a RegisterBlock, glb: &glb::v2::RegisterBlock) -> Self {
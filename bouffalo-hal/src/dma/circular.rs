@@ -0,0 +1,640 @@
+use core::future::poll_fn;
+use core::sync::atomic::{Ordering, compiler_fence};
+use core::task::{Poll, Waker};
+
+use super::cache::{clean_dcache, flush_dcache, invalidate_dcache};
+use super::channel::{TypedChannel, clean_lli};
+use super::config::{DmaChannelConfig, PeripheralId};
+use super::register::{BurstSize, DmaMode, TransferCompleteClear, TransferWidth};
+use super::transfer::{DmaState, Error, check_buffer, width_bytes};
+use super::{Endpoint, LliPool};
+
+/// Maximum transfer units moved by one linked list item.
+const LLI_TRANSFER_SIZE: usize = 4064;
+
+/// Buffers lent to a circular DMA channel.
+#[derive(Debug)]
+pub struct CircularBuffers {
+    /// Circular buffer continuously filled or drained by DMA.
+    pub buffer: &'static mut [u8],
+    /// Linked list items forming the ring.
+    pub lli: &'static mut [LliPool],
+}
+
+impl<'a, T: PeripheralId + Copy> TypedChannel<'a, T> {
+    /// Continuously receive from a peripheral endpoint into a circular buffer.
+    ///
+    /// The buffer is split into `segments` equal parts, each raising a completion interrupt;
+    /// use 2 segments for half and full completion, i.e. ping-pong buffering.
+    #[inline]
+    pub fn into_circular_reader(
+        self,
+        endpoint: Endpoint<T>,
+        buffers: CircularBuffers,
+        segments: usize,
+        width: TransferWidth,
+    ) -> Result<CircularReader<'a, T>, Error> {
+        Ring::new(self, endpoint, buffers, segments, width, false)
+            .map(|ring| CircularReader { ring })
+    }
+
+    /// Continuously send a circular buffer to a peripheral endpoint.
+    ///
+    /// The buffer is played from its start, so it should be filled before this call. It is
+    /// split into `segments` equal parts, each raising a completion interrupt when played.
+    #[inline]
+    pub fn into_circular_writer(
+        self,
+        endpoint: Endpoint<T>,
+        buffers: CircularBuffers,
+        segments: usize,
+        width: TransferWidth,
+    ) -> Result<CircularWriter<'a, T>, Error> {
+        Ring::new(self, endpoint, buffers, segments, width, true)
+            .map(|ring| CircularWriter { ring })
+    }
+}
+
+/// Linked list items looping over a circular buffer, and software position on it.
+struct Ring<'a, T> {
+    channel: TypedChannel<'a, T>,
+    buffers: CircularBuffers,
+    segments: usize,
+    to_peripheral: bool,
+    position: usize,
+    dma_seen: usize,
+    moved: usize,
+    completed: Option<usize>,
+}
+
+impl<'a, T: PeripheralId + Copy> Ring<'a, T> {
+    #[inline]
+    fn new(
+        mut channel: TypedChannel<'a, T>,
+        endpoint: Endpoint<T>,
+        buffers: CircularBuffers,
+        segments: usize,
+        width: TransferWidth,
+        to_peripheral: bool,
+    ) -> Result<Self, Error> {
+        let len = buffers.buffer.len();
+        let unit = width_bytes(width);
+        let segment = len / core::cmp::max(segments, 1);
+        check_buffer(buffers.buffer.as_ptr() as usize, segment, width)?;
+        if segments < 2 || segment * segments != len {
+            return Err(Error::Misaligned);
+        }
+        let items_per_segment = (segment / unit).div_ceil(LLI_TRANSFER_SIZE);
+        if buffers.lli.len() < items_per_segment * segments {
+            return Err(Error::LliPoolTooSmall);
+        }
+        let (direction, src_req, dst_req) = match to_peripheral {
+            true => (DmaMode::Mem2Periph, None, Some(endpoint.request())),
+            false => (DmaMode::Periph2Mem, Some(endpoint.request()), None),
+        };
+        channel.configure(DmaChannelConfig {
+            direction,
+            src_req,
+            dst_req,
+            src_addr_inc: to_peripheral,
+            dst_addr_inc: !to_peripheral,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: width,
+            dst_transfer_width: width,
+        });
+        let mut ring = Ring {
+            channel,
+            buffers,
+            segments,
+            to_peripheral,
+            position: 0,
+            dma_seen: 0,
+            moved: 0,
+            completed: None,
+        };
+        ring.link(endpoint.address(), segment, items_per_segment, unit);
+        clean_lli(&ring.buffers.lli[..items_per_segment * segments]);
+        let base = ring.buffers.buffer.as_ptr() as usize;
+        match to_peripheral {
+            true => clean_dcache(base, len),
+            false => flush_dcache(base, len),
+        }
+        compiler_fence(Ordering::Release);
+        ring.channel.lli_load(&ring.buffers.lli[0]);
+        ring.channel.start();
+        Ok(ring)
+    }
+
+    /// Link items of every segment into one ring; only the last item of a segment interrupts.
+    #[inline]
+    fn link(&mut self, peripheral: u32, segment: usize, items: usize, unit: usize) {
+        let base = self.buffers.buffer.as_mut_ptr() as usize;
+        let last_len = segment / unit - (items - 1) * LLI_TRANSFER_SIZE;
+        for idx in 0..self.segments {
+            let memory = (base + idx * segment) as u32;
+            let (src, dst) = match self.to_peripheral {
+                true => (memory, peripheral),
+                false => (peripheral, memory),
+            };
+            let first = idx * items;
+            self.channel.lli_config(
+                &mut self.buffers.lli[first..],
+                items as u32,
+                src,
+                dst,
+                (LLI_TRANSFER_SIZE * unit) as u32,
+                last_len as u32,
+            );
+            if idx != 0 {
+                self.buffers.lli[first - 1].next_lli =
+                    &self.buffers.lli[first] as *const LliPool as u32;
+            }
+        }
+        let last = self.segments * items - 1;
+        self.buffers.lli[last].next_lli = &self.buffers.lli[0] as *const LliPool as u32;
+    }
+
+    /// Byte offset of DMA on the circular buffer.
+    ///
+    /// Address register is read until two reads agree, so a value torn by linked list item
+    /// reload is never used.
+    #[inline]
+    fn dma_position(&self) -> usize {
+        let read = || match self.to_peripheral {
+            true => self.channel.source_address(),
+            false => self.channel.destination_address(),
+        };
+        let mut address = read();
+        loop {
+            let again = read();
+            if again == address {
+                break;
+            }
+            address = again;
+        }
+        let base = self.buffers.buffer.as_ptr() as usize as u32;
+        address.wrapping_sub(base) as usize % self.buffers.buffer.len()
+    }
+
+    /// Split `len` bytes from `offset` of the circular buffer into at most two ranges.
+    #[inline]
+    fn ranges(&self, offset: usize, len: usize) -> [(usize, usize); 2] {
+        let base = self.buffers.buffer.as_ptr() as usize;
+        let first = core::cmp::min(len, self.buffers.buffer.len() - offset);
+        [(base + offset, first), (base, len - first)]
+    }
+
+    #[inline]
+    fn segment_len(&self) -> usize {
+        self.buffers.buffer.len() / self.segments
+    }
+
+    /// Follow DMA position, counting bytes moved by DMA and the segment completed last.
+    ///
+    /// Segment completion without DMA crossing a segment boundary since the last call means
+    /// DMA went around the whole buffer in between, which is counted as well.
+    #[inline]
+    fn update(&mut self) {
+        let (dma, id) = (self.channel.dma, self.channel.channel_id);
+        let completed = dma
+            .interrupts
+            .raw_transfer_complete
+            .read()
+            .if_raw_cplt_int_occurs(id as u8);
+        if completed {
+            unsafe {
+                dma.interrupts
+                    .transfer_complete_clear
+                    .write(TransferCompleteClear::default().clear_cplt_int(id as u8))
+            };
+        }
+        let len = self.buffers.buffer.len();
+        let segment_len = self.segment_len();
+        let position = self.dma_position();
+        let mut moved = (position + len - self.dma_seen) % len;
+        if completed {
+            if self.dma_seen % segment_len + moved < segment_len {
+                moved += len;
+            }
+            let current = position / segment_len;
+            self.completed = Some((current + self.segments - 1) % self.segments);
+        }
+        self.dma_seen = position;
+        self.moved = self.moved.saturating_add(moved);
+    }
+
+    /// Clear and return the segment completed last, if any completed since the last call.
+    #[inline]
+    fn completed_segment(&mut self) -> Option<usize> {
+        self.update();
+        self.completed.take()
+    }
+
+    #[inline]
+    async fn wait_segment(&mut self, state: &DmaState) -> usize {
+        let (dma, id) = (self.channel.dma, self.channel.channel_id);
+        state.register(dma);
+        poll_fn(|cx| {
+            state.wakers[id].register(cx.waker());
+            match self.completed_segment() {
+                Some(idx) => Poll::Ready(idx),
+                None => {
+                    unsafe {
+                        dma.channels[id]
+                            .config
+                            .modify(|val| val.enable_cplt_int().enable_err_int())
+                    };
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    #[inline]
+    fn free(self) -> (TypedChannel<'a, T>, CircularBuffers) {
+        self.channel.stop();
+        (self.channel, self.buffers)
+    }
+}
+
+/// Circular DMA receiving from a peripheral.
+///
+/// Data not read before DMA wraps around the buffer is overwritten, which is reported as
+/// `Error::Overrun` on the next read.
+pub struct CircularReader<'a, T> {
+    ring: Ring<'a, T>,
+}
+
+impl<'a, T: PeripheralId + Copy> CircularReader<'a, T> {
+    /// Number of received bytes waiting in the circular buffer.
+    ///
+    /// Returns `Error::Overrun` if DMA has overwritten bytes not yet read; they are dropped,
+    /// and reading resumes from the current DMA position. Overruns are detected by counting
+    /// completed segments, so call this at least once a segment to catch every one.
+    #[inline]
+    pub fn available(&mut self) -> Result<usize, Error> {
+        self.ring.update();
+        if self.ring.moved >= self.ring.buffers.buffer.len() {
+            self.ring.position = self.ring.dma_seen;
+            self.ring.moved = 0;
+            return Err(Error::Overrun);
+        }
+        Ok(self.ring.moved)
+    }
+    /// Copy received bytes into `buf`, returning the number of bytes read.
+    ///
+    /// Returns `Error::Overrun` if DMA has overwritten bytes not yet read, see `available`.
+    #[inline]
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = core::cmp::min(self.available()?, buf.len());
+        compiler_fence(Ordering::Acquire);
+        for (address, bytes) in self.ring.ranges(self.ring.position, len) {
+            // Reader buffer is only written by DMA.
+            unsafe { invalidate_dcache(address, bytes) };
+        }
+        let ring = self.ring.buffers.buffer.as_ptr();
+        let ring_len = self.ring.buffers.buffer.len();
+        for slot in buf.iter_mut().take(len) {
+            *slot = unsafe { ring.add(self.ring.position).read_volatile() };
+            self.ring.position = (self.ring.position + 1) % ring_len;
+        }
+        self.ring.moved -= len;
+        Ok(len)
+    }
+    /// Number of segments of the circular buffer.
+    #[inline]
+    pub fn segments(&self) -> usize {
+        self.ring.segments
+    }
+    /// Get received data of segment `idx`, e.g. a half of ping-pong buffer.
+    ///
+    /// Only read a segment after it completes; DMA keeps filling the other segments.
+    #[inline]
+    pub fn segment(&self, idx: usize) -> &[u8] {
+        let len = self.ring.segment_len();
+        compiler_fence(Ordering::Acquire);
+        let data = &self.ring.buffers.buffer[idx * len..(idx + 1) * len];
+        // Reader buffer is only written by DMA.
+        unsafe { invalidate_dcache(data.as_ptr() as usize, len) };
+        data
+    }
+    /// Check for a completed segment and return its index.
+    ///
+    /// Completion flag is cleared; when several segments completed in between, only the
+    /// latest one is returned.
+    #[inline]
+    pub fn completed_segment(&mut self) -> Option<usize> {
+        self.ring.completed_segment()
+    }
+    /// Wait for a segment to complete asynchronously and return its index.
+    ///
+    /// `state` must be notified with `DmaState::on_interrupt` in the DMA interrupt handler.
+    #[inline]
+    pub async fn wait_segment(&mut self, state: &DmaState) -> usize {
+        self.ring.wait_segment(state).await
+    }
+    /// Wake `waker` on the next segment completion, for drivers waiting on other events too.
+    #[inline]
+    pub(crate) fn listen(&self, state: &DmaState, waker: &Waker) {
+        self.ring.channel.listen(state, waker);
+    }
+    /// Stop DMA and release the channel and buffers.
+    #[inline]
+    pub fn free(self) -> (TypedChannel<'a, T>, CircularBuffers) {
+        self.ring.free()
+    }
+}
+
+/// Circular DMA sending to a peripheral.
+///
+/// Data not refilled before DMA wraps around the buffer is sent again.
+pub struct CircularWriter<'a, T> {
+    ring: Ring<'a, T>,
+}
+
+impl<'a, T: PeripheralId + Copy> CircularWriter<'a, T> {
+    /// Number of bytes that can be written without overwriting data not yet sent.
+    #[inline]
+    pub fn space(&self) -> usize {
+        let len = self.ring.buffers.buffer.len();
+        (self.ring.dma_position() + len - self.ring.position - 1) % len
+    }
+    /// Copy bytes from `buf` ahead of DMA, returning the number of bytes written.
+    #[inline]
+    pub fn write(&mut self, buf: &[u8]) -> usize {
+        let len = core::cmp::min(self.space(), buf.len());
+        let ranges = self.ring.ranges(self.ring.position, len);
+        let ring = self.ring.buffers.buffer.as_mut_ptr();
+        let ring_len = self.ring.buffers.buffer.len();
+        for byte in buf.iter().take(len) {
+            unsafe { ring.add(self.ring.position).write_volatile(*byte) };
+            self.ring.position = (self.ring.position + 1) % ring_len;
+        }
+        compiler_fence(Ordering::Release);
+        for (address, bytes) in ranges {
+            clean_dcache(address, bytes);
+        }
+        len
+    }
+    /// Number of segments of the circular buffer.
+    #[inline]
+    pub fn segments(&self) -> usize {
+        self.ring.segments
+    }
+    /// Refill data of segment `idx` in `f`, e.g. a half of ping-pong buffer.
+    ///
+    /// Only refill a segment after it completes; DMA keeps sending the other segments.
+    /// Refilled data is written back from data cache before this function returns.
+    #[inline]
+    pub fn fill_segment<R>(&mut self, idx: usize, f: impl FnOnce(&mut [u8]) -> R) -> R {
+        let len = self.ring.segment_len();
+        let data = &mut self.ring.buffers.buffer[idx * len..(idx + 1) * len];
+        let ans = f(data);
+        compiler_fence(Ordering::Release);
+        clean_dcache(data.as_ptr() as usize, len);
+        ans
+    }
+    /// Check for a completed segment and return its index.
+    ///
+    /// Completion flag is cleared; when several segments completed in between, only the
+    /// latest one is returned.
+    #[inline]
+    pub fn completed_segment(&mut self) -> Option<usize> {
+        self.ring.completed_segment()
+    }
+    /// Wait for a segment to complete asynchronously and return its index.
+    ///
+    /// `state` must be notified with `DmaState::on_interrupt` in the DMA interrupt handler.
+    #[inline]
+    pub async fn wait_segment(&mut self, state: &DmaState) -> usize {
+        self.ring.wait_segment(state).await
+    }
+    /// Stop DMA and release the channel and buffers.
+    #[inline]
+    pub fn free(self) -> (TypedChannel<'a, T>, CircularBuffers) {
+        self.ring.free()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircularBuffers;
+    use crate::dma::mock::{MockDma, endpoint};
+    use crate::dma::{DmaState, Error, LliPool, TransferWidth};
+    use core::future::Future;
+
+    #[test]
+    fn struct_circular_errors() {
+        static mut BUFFER: [u8; 100] = [0; 100];
+        static mut LLI: [LliPool; 2] = [LliPool::new(); 2];
+        let dma = MockDma::new();
+        let buffers = || CircularBuffers {
+            buffer: unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) },
+            lli: unsafe { &mut *core::ptr::addr_of_mut!(LLI) },
+        };
+        let ans = dma.channel(0).into_circular_reader(
+            endpoint(22, 0x20002004),
+            buffers(),
+            3,
+            TransferWidth::Byte,
+        );
+        assert!(matches!(ans, Err(Error::Misaligned)));
+        let ans = dma.channel(0).into_circular_reader(
+            endpoint(22, 0x20002004),
+            buffers(),
+            1,
+            TransferWidth::Byte,
+        );
+        assert!(matches!(ans, Err(Error::Misaligned)));
+        let ans = dma.channel(0).into_circular_reader(
+            endpoint(22, 0x20002004),
+            buffers(),
+            4,
+            TransferWidth::Byte,
+        );
+        assert!(matches!(ans, Err(Error::LliPoolTooSmall)));
+    }
+
+    #[test]
+    fn struct_circular_reader() {
+        static mut BUFFER: [u8; 16] = [0; 16];
+        static mut LLI: [LliPool; 2] = [LliPool::new(); 2];
+        let dma = MockDma::new();
+        let buffers = CircularBuffers {
+            buffer: unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) },
+            lli: unsafe { &mut *core::ptr::addr_of_mut!(LLI) },
+        };
+        let base = buffers.buffer.as_ptr() as usize as u32;
+        let mut reader = dma
+            .channel(1)
+            .into_circular_reader(endpoint(22, 0x20002004), buffers, 2, TransferWidth::Byte)
+            .ok()
+            .unwrap();
+        let lli = unsafe { &*core::ptr::addr_of!(LLI) };
+        assert_eq!(lli[0].src_addr, 0x20002004);
+        assert_eq!(lli[0].dst_addr, base);
+        assert_eq!(lli[1].dst_addr, base.wrapping_add(8));
+        assert_eq!(lli[0].next_lli, &lli[1] as *const LliPool as u32);
+        assert_eq!(lli[1].next_lli, &lli[0] as *const LliPool as u32);
+        for item in lli {
+            assert_eq!(item.control.transfer_size(), 8);
+            assert!(item.control.is_cplt_int_enabled());
+        }
+        assert_eq!(reader.segments(), 2);
+
+        let buffer = unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) };
+        buffer[..5].copy_from_slice(b"hello");
+        dma.set_channel_reg(1, 0x04, base.wrapping_add(5));
+        assert_eq!(reader.available(), Ok(5));
+        let mut buf = [0u8; 3];
+        assert_eq!(reader.read(&mut buf), Ok(3));
+        assert_eq!(&buf, b"hel");
+        assert_eq!(reader.available(), Ok(2));
+        assert_eq!(reader.completed_segment(), None);
+
+        // DMA passed the first half and is filling the second one.
+        dma.set_channel_reg(1, 0x04, base.wrapping_add(9));
+        dma.set_raw_complete(1, true);
+        assert_eq!(reader.completed_segment(), Some(0));
+        dma.set_raw_complete(1, false);
+        assert_eq!(&reader.segment(0)[..5], b"hello");
+        // Wrapped around the end of buffer.
+        dma.set_channel_reg(1, 0x04, base.wrapping_add(16));
+        dma.set_raw_complete(1, true);
+        assert_eq!(reader.completed_segment(), Some(1));
+        dma.set_raw_complete(1, false);
+        assert_eq!(reader.available(), Ok(13));
+
+        let (_channel, buffers) = reader.free();
+        assert_eq!(buffers.buffer.len(), 16);
+    }
+
+    #[test]
+    fn struct_circular_reader_overrun() {
+        static mut BUFFER: [u8; 16] = [0; 16];
+        static mut LLI: [LliPool; 2] = [LliPool::new(); 2];
+        let dma = MockDma::new();
+        let buffers = CircularBuffers {
+            buffer: unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) },
+            lli: unsafe { &mut *core::ptr::addr_of_mut!(LLI) },
+        };
+        let base = buffers.buffer.as_ptr() as usize as u32;
+        let mut reader = dma
+            .channel(0)
+            .into_circular_reader(endpoint(22, 0x20002004), buffers, 2, TransferWidth::Byte)
+            .ok()
+            .unwrap();
+        dma.set_channel_reg(0, 0x04, base.wrapping_add(3));
+        assert_eq!(reader.available(), Ok(3));
+        // Segment completed while DMA is back in the same segment, i.e. one whole lap.
+        dma.set_channel_reg(0, 0x04, base.wrapping_add(4));
+        dma.set_raw_complete(0, true);
+        let mut buf = [0u8; 4];
+        assert_eq!(reader.read(&mut buf), Err(Error::Overrun));
+        dma.set_raw_complete(0, false);
+        // Reading resumes from DMA position.
+        assert_eq!(reader.available(), Ok(0));
+        dma.set_channel_reg(0, 0x04, base.wrapping_add(10));
+        dma.set_raw_complete(0, true);
+        assert_eq!(reader.read(&mut buf), Ok(4));
+        dma.set_raw_complete(0, false);
+        assert_eq!(reader.completed_segment(), Some(0));
+        assert_eq!(reader.available(), Ok(2));
+        // Unread bytes filling the whole buffer are overrun as well.
+        dma.set_channel_reg(0, 0x04, base.wrapping_add(8));
+        dma.set_raw_complete(0, true);
+        assert_eq!(reader.available(), Err(Error::Overrun));
+    }
+
+    #[test]
+    fn struct_circular_long_segments() {
+        static mut WORDS: [u32; 2500] = [0; 2500];
+        static mut LLI: [LliPool; 4] = [LliPool::new(); 4];
+        let dma = MockDma::new();
+        let words = unsafe { &mut *core::ptr::addr_of_mut!(WORDS) };
+        let buffers = CircularBuffers {
+            buffer: unsafe { core::slice::from_raw_parts_mut(words.as_mut_ptr().cast(), 10000) },
+            lli: unsafe { &mut *core::ptr::addr_of_mut!(LLI) },
+        };
+        let base = buffers.buffer.as_ptr() as usize as u32;
+        let writer = dma
+            .channel(0)
+            .into_circular_writer(endpoint(17, 0x2000AB88), buffers, 2, TransferWidth::Word)
+            .ok()
+            .unwrap();
+        let lli = unsafe { &*core::ptr::addr_of!(LLI) };
+        // Each half has 1250 words in a single item.
+        assert_eq!(lli[0].src_addr, base);
+        assert_eq!(lli[0].dst_addr, 0x2000AB88);
+        assert_eq!(lli[0].control.transfer_size(), 1250);
+        assert_eq!(lli[1].src_addr, base.wrapping_add(5000));
+        assert_eq!(lli[1].next_lli, &lli[0] as *const LliPool as u32);
+        let _ = writer.free();
+
+        static mut BYTES: [u8; 10000] = [0; 10000];
+        let dma = MockDma::new();
+        let buffers = CircularBuffers {
+            buffer: unsafe { &mut *core::ptr::addr_of_mut!(BYTES) },
+            lli: unsafe { &mut *core::ptr::addr_of_mut!(LLI) },
+        };
+        let base = buffers.buffer.as_ptr() as usize as u32;
+        let mut writer = dma
+            .channel(0)
+            .into_circular_writer(endpoint(17, 0x2000AB88), buffers, 2, TransferWidth::Byte)
+            .ok()
+            .unwrap();
+        let lli = unsafe { &*core::ptr::addr_of!(LLI) };
+        // Each half takes two items, only the second one interrupts.
+        assert_eq!(lli[0].control.transfer_size(), 4064);
+        assert!(!lli[0].control.is_cplt_int_enabled());
+        assert_eq!(lli[1].control.transfer_size(), 5000 - 4064);
+        assert!(lli[1].control.is_cplt_int_enabled());
+        assert_eq!(lli[1].src_addr, base.wrapping_add(4064));
+        assert_eq!(lli[2].src_addr, base.wrapping_add(5000));
+        assert_eq!(lli[1].next_lli, &lli[2] as *const LliPool as u32);
+        assert_eq!(lli[3].next_lli, &lli[0] as *const LliPool as u32);
+
+        dma.set_channel_reg(0, 0x00, base.wrapping_add(100));
+        assert_eq!(writer.space(), 99);
+        assert_eq!(writer.write(&[0x5a; 200]), 99);
+        assert_eq!(writer.space(), 0);
+        assert_eq!(writer.fill_segment(1, |data| data.len()), 5000);
+    }
+
+    #[test]
+    fn struct_circular_wait_segment() {
+        static mut HALF_WORDS: [u16; 32] = [0; 32];
+        static mut LLI: [LliPool; 4] = [LliPool::new(); 4];
+        let dma = MockDma::new();
+        let state = DmaState::new();
+        let half_words = unsafe { &mut *core::ptr::addr_of_mut!(HALF_WORDS) };
+        let buffers = CircularBuffers {
+            buffer: unsafe { core::slice::from_raw_parts_mut(half_words.as_mut_ptr().cast(), 64) },
+            lli: unsafe { &mut *core::ptr::addr_of_mut!(LLI) },
+        };
+        let base = buffers.buffer.as_ptr() as usize as u32;
+        let mut reader = dma
+            .channel(2)
+            .into_circular_reader(
+                endpoint(12, 0x2000AB8C),
+                buffers,
+                4,
+                TransferWidth::HalfWord,
+            )
+            .ok()
+            .unwrap();
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        {
+            let mut wait = core::pin::pin!(reader.wait_segment(&state));
+            assert!(wait.as_mut().poll(&mut cx).is_pending());
+            dma.set_channel_reg(2, 0x04, base.wrapping_add(40));
+            unsafe { *dma.regs() = 1 << 2 };
+            dma.set_raw_complete(2, true);
+            state.on_interrupt();
+            assert_eq!(wait.as_mut().poll(&mut cx), core::task::Poll::Ready(1));
+        }
+        assert_eq!(reader.segment(1).len(), 16);
+    }
+}
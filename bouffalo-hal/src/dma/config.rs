@@ -25,93 +25,54 @@ pub struct Mem2MemChannelConfig {
     pub dst_transfer_width: TransferWidth,
 }
 
-/// Peripheral for DMA 0/1.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum Periph4Dma01 {
-    /// UART0 receive.
-    Uart0Rx,
-    /// UART0 transmit.
-    Uart0Tx,
-    /// UART1 receive.
-    Uart1Rx,
-    /// UART1 transmit.
-    Uart1Tx,
-    /// UART2 receive.
-    Uart2Rx,
-    /// UART2 transmit.
-    Uart2Tx,
-    /// I2C0 receive.
-    I2c0Rx,
-    /// I2C0 transmit.
-    I2c0Tx,
-    /// IR transmit.
-    IrTx,
-    /// GPIO transmit.
-    GpioTx,
-    /// SPI0 receive.
-    Spi0Rx,
-    /// SPI0 transmit.
-    Spi0Tx,
-    /// AUDIO receive.
-    AudioRx,
-    /// AUDIO transmit.
-    AudioTx,
-    /// I2C1 receive.
-    I2c1Rx,
-    /// I2C1 transmit.
-    I2c1Tx,
-    /// I2S receive.
-    I2sRx,
-    /// I2S transmit.
-    I2sTx,
-    /// PDM receive.
-    PdmRx,
-    /// GPADC.
-    GpAdc = 22,
-    /// GPDAC.
-    GpDac,
+/// DMA peripheral request of a chip.
+pub trait PeripheralId {
+    /// Request line number on DMA controller.
+    fn id(&self) -> u8;
+    /// Address of data register serving this request, if it is fixed on this chip.
+    #[inline]
+    fn address(&self) -> Option<u32> {
+        None
+    }
 }
 
-/// Peripheral for DMA 2.
+/// Peripheral side of a DMA transfer.
+///
+/// Pairs a peripheral request with the data register it reads from or writes to.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum Periph4Dma2 {
-    /// UART3 receive.
-    Uart3Rx,
-    /// UART3 transmit.
-    Uart3Tx,
-    /// SPI1 receive.
-    Spi1Rx,
-    /// SPI1 transmit.
-    Spi1Tx,
-    /// I2C2 receive.
-    I2c2Rx = 6,
-    /// I2C2 transmit.
-    I2c2Tx,
-    /// I2C3 receive.
-    I2c3Rx,
-    /// I2C3 transmit.
-    I2c3Tx,
-    /// DSI receive.
-    DsiRx,
-    /// DSI transmit.
-    DsiTx,
-    /// DBI receive.
-    DbiTx = 22,
+pub struct Endpoint<T> {
+    request: T,
+    address: u32,
 }
 
-pub trait PeripheralId {
-    fn id(&self) -> u8;
+impl<T: PeripheralId> Endpoint<T> {
+    /// Create endpoint from data register address table of this chip.
+    ///
+    /// Returns `None` if the request has no fixed data register.
+    #[inline]
+    pub fn new(request: T) -> Option<Self> {
+        let address = request.address()?;
+        Some(Self { request, address })
+    }
+    /// Create endpoint on data register of a peripheral driver.
+    #[inline]
+    pub(crate) fn from_register<R>(request: T, register: &R) -> Self {
+        Self {
+            request,
+            address: register as *const R as u32,
+        }
+    }
 }
 
-impl PeripheralId for Periph4Dma01 {
+impl<T: Copy> Endpoint<T> {
+    /// Get peripheral request of this endpoint.
     #[inline]
-    fn id(&self) -> u8 {
-        *self as u8
+    pub fn request(&self) -> T {
+        self.request
     }
-}
-impl PeripheralId for Periph4Dma2 {
+    /// Get data register address of this endpoint.
     #[inline]
-    fn id(&self) -> u8 {
-        *self as u8
+    pub fn address(&self) -> u32 {
+        self.address
     }
 }
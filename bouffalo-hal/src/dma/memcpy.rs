@@ -0,0 +1,502 @@
+use core::future::poll_fn;
+use core::sync::atomic::{Ordering, compiler_fence};
+use core::task::Poll;
+
+use super::LliPool;
+use super::cache::{clean_dcache, flush_dcache, invalidate_dcache};
+use super::channel::{TypedChannel, UntypedChannel};
+use super::config::{Mem2MemChannelConfig, PeripheralId};
+use super::register::{BurstSize, DmaMode, ErrorClear, TransferCompleteClear, TransferWidth};
+use super::transfer::{DmaState, Error, width_bytes};
+
+/// Maximum transfer units moved by one linked list item.
+const LLI_TRANSFER_SIZE: usize = 4064;
+
+/// DMA accelerated memory copy and fill on one channel.
+///
+/// Copies are moved by linked list items of `lli`, in several rounds when the pool is too
+/// small to cover a buffer. Bytes before and after the part DMA can move in whole transfer
+/// units are copied by the CPU. Fill patterns are read by DMA from this structure, so it
+/// should be placed in memory DMA can access, e.g. a static.
+pub struct DmaMemcpy<'a, T> {
+    channel: TypedChannel<'a, T>,
+    lli: &'static mut [LliPool],
+    pattern: u32,
+}
+
+impl<'a, T: PeripheralId + Copy> DmaMemcpy<'a, T> {
+    /// Create memory copy service on a channel with its linked list item pool.
+    #[inline]
+    pub fn new(channel: TypedChannel<'a, T>, lli: &'static mut [LliPool]) -> Self {
+        Self {
+            channel,
+            lli,
+            pattern: 0,
+        }
+    }
+    /// Copy `source` into `destination`, blocking until done.
+    #[inline]
+    pub fn copy(&mut self, destination: &mut [u8], source: &[u8]) -> Result<(), Error> {
+        let body = self.prepare_copy(destination, source)?;
+        self.run(body)
+    }
+    /// Copy `source` into `destination` asynchronously.
+    ///
+    /// `state` must be notified with `DmaState::on_interrupt` in the DMA interrupt handler.
+    /// Dropping the future stops the copy midway.
+    ///
+    /// # Safety
+    ///
+    /// DMA keeps accessing both buffers until the returned future completes or is dropped.
+    /// The future must not be leaked, e.g. by `core::mem::forget`, while it is pending;
+    /// otherwise DMA may write into `destination` after its borrow ends.
+    #[inline]
+    pub async unsafe fn copy_async(
+        &mut self,
+        destination: &mut [u8],
+        source: &[u8],
+        state: &DmaState,
+    ) -> Result<(), Error> {
+        let body = self.prepare_copy(destination, source)?;
+        self.run_async(body, state).await
+    }
+    /// Fill `destination` with 4-byte `pattern` in little endian, blocking until done.
+    ///
+    /// The pattern repeats from the first byte of `destination`; use e.g. `0x01010101 * v`
+    /// to set every byte to `v`.
+    #[inline]
+    pub fn fill(&mut self, destination: &mut [u8], pattern: u32) -> Result<(), Error> {
+        let body = self.prepare_fill(destination, pattern)?;
+        self.run(body)
+    }
+    /// Fill `destination` with 4-byte `pattern` in little endian asynchronously.
+    ///
+    /// `state` must be notified with `DmaState::on_interrupt` in the DMA interrupt handler.
+    /// Dropping the future stops the fill midway.
+    ///
+    /// # Safety
+    ///
+    /// Same as `copy_async`: the returned future must not be leaked while it is pending.
+    #[inline]
+    pub async unsafe fn fill_async(
+        &mut self,
+        destination: &mut [u8],
+        pattern: u32,
+        state: &DmaState,
+    ) -> Result<(), Error> {
+        let body = self.prepare_fill(destination, pattern)?;
+        self.run_async(body, state).await
+    }
+    /// Release the channel and linked list item pool.
+    #[inline]
+    pub fn free(self) -> (TypedChannel<'a, T>, &'static mut [LliPool]) {
+        (self.channel, self.lli)
+    }
+
+    /// Copy unaligned head and tail by CPU, and configure channel for the rest.
+    #[inline]
+    fn prepare_copy(&mut self, destination: &mut [u8], source: &[u8]) -> Result<Body, Error> {
+        if destination.len() != source.len() {
+            return Err(Error::LengthMismatch);
+        }
+        let src = source.as_ptr() as usize;
+        let dst = destination.as_mut_ptr() as usize;
+        let width = match (src ^ dst) & 3 {
+            0 => TransferWidth::Word,
+            2 => TransferWidth::HalfWord,
+            _ => TransferWidth::Byte,
+        };
+        let (head, len) = split(dst, destination.len(), width);
+        self.prepare(width, true, len)?;
+        destination[..head].copy_from_slice(&source[..head]);
+        destination[head + len..].copy_from_slice(&source[head + len..]);
+        Ok(Body {
+            src: src + head,
+            src_increment: true,
+            dst: dst + head,
+            len,
+            unit: width_bytes(width),
+        })
+    }
+
+    /// Fill unaligned head and tail by CPU, and configure channel for the rest.
+    #[inline]
+    fn prepare_fill(&mut self, destination: &mut [u8], pattern: u32) -> Result<Body, Error> {
+        let dst = destination.as_mut_ptr() as usize;
+        let (head, len) = split(dst, destination.len(), TransferWidth::Word);
+        self.prepare(TransferWidth::Word, false, len)?;
+        let bytes = pattern.to_le_bytes();
+        for (idx, byte) in destination.iter_mut().enumerate() {
+            if idx < head || idx >= head + len {
+                *byte = bytes[idx % 4];
+            }
+        }
+        // Words DMA writes start at byte `head` of the pattern.
+        self.pattern = pattern.rotate_right(8 * head as u32);
+        let src = &self.pattern as *const u32 as usize;
+        clean_dcache(src, 4);
+        Ok(Body {
+            src,
+            src_increment: false,
+            dst: dst + head,
+            len,
+            unit: 4,
+        })
+    }
+
+    #[inline]
+    fn prepare(
+        &mut self,
+        width: TransferWidth,
+        src_increment: bool,
+        len: usize,
+    ) -> Result<(), Error> {
+        if len != 0 && self.lli.is_empty() {
+            return Err(Error::LliPoolTooSmall);
+        }
+        self.channel.memory_to_memory(Mem2MemChannelConfig {
+            direction: DmaMode::Mem2Mem,
+            src_addr_inc: src_increment,
+            dst_addr_inc: true,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: width,
+            dst_transfer_width: width,
+        });
+        Ok(())
+    }
+
+    #[inline]
+    fn run(&mut self, body: Body) -> Result<(), Error> {
+        let _stop = Stop(&self.channel);
+        body.prepare_cache();
+        let mut offset = 0;
+        while offset < body.len {
+            offset += body.start(&self.channel, self.lli, offset);
+            let ans = loop {
+                if let Poll::Ready(ans) = status(&self.channel) {
+                    break ans;
+                }
+                core::hint::spin_loop();
+            };
+            if let Err(e) = ans {
+                body.complete_cache();
+                return Err(e);
+            }
+        }
+        body.complete_cache();
+        Ok(())
+    }
+
+    #[inline]
+    async fn run_async(&mut self, body: Body, state: &DmaState) -> Result<(), Error> {
+        let _stop = Stop(&self.channel);
+        let (dma, id) = (self.channel.dma, self.channel.channel_id);
+        state.register(dma);
+        body.prepare_cache();
+        let mut offset = 0;
+        while offset < body.len {
+            offset += body.start(&self.channel, self.lli, offset);
+            let ans = poll_fn(|cx| {
+                state.wakers[id].register(cx.waker());
+                let ans = status(&self.channel);
+                if ans.is_pending() {
+                    unsafe {
+                        dma.channels[id]
+                            .config
+                            .modify(|val| val.enable_cplt_int().enable_err_int())
+                    };
+                }
+                ans
+            })
+            .await;
+            if let Err(e) = ans {
+                body.complete_cache();
+                return Err(e);
+            }
+        }
+        body.complete_cache();
+        Ok(())
+    }
+}
+
+/// Part of a copy or fill moved by DMA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Body {
+    src: usize,
+    src_increment: bool,
+    dst: usize,
+    /// Length in bytes, a multiple of `unit`.
+    len: usize,
+    unit: usize,
+}
+
+impl Body {
+    /// Write back source, and flush destination before DMA writes it.
+    #[inline]
+    fn prepare_cache(&self) {
+        if self.src_increment {
+            clean_dcache(self.src, self.len);
+        }
+        flush_dcache(self.dst, self.len);
+    }
+    /// Discard stale cache lines of destination written by DMA.
+    #[inline]
+    fn complete_cache(&self) {
+        // Destination is borrowed by the copy and not written by CPU meanwhile.
+        unsafe { invalidate_dcache(self.dst, self.len) };
+    }
+    /// Start moving as many bytes from `offset` as the pool covers, returning the count.
+    #[inline]
+    fn start(&self, channel: &UntypedChannel, lli: &mut [LliPool], offset: usize) -> usize {
+        let units = core::cmp::min(
+            (self.len - offset) / self.unit,
+            lli.len() * LLI_TRANSFER_SIZE,
+        );
+        let items = units.div_ceil(LLI_TRANSFER_SIZE);
+        let last_len = units - (items - 1) * LLI_TRANSFER_SIZE;
+        let src = match self.src_increment {
+            true => self.src + offset,
+            false => self.src,
+        };
+        channel.lli_config(
+            lli,
+            items as u32,
+            src as u32,
+            (self.dst + offset) as u32,
+            (LLI_TRANSFER_SIZE * self.unit) as u32,
+            last_len as u32,
+        );
+        let (dma, id) = (channel.dma, channel.channel_id);
+        unsafe {
+            dma.interrupts
+                .transfer_complete_clear
+                .write(TransferCompleteClear::default().clear_cplt_int(id as u8))
+        };
+        compiler_fence(Ordering::Release);
+        channel.lli_load(&lli[0]);
+        channel.start();
+        units * self.unit
+    }
+}
+
+/// Split buffer at `address` of `len` bytes into CPU copied head, and length DMA moves.
+#[inline]
+fn split(address: usize, len: usize, width: TransferWidth) -> (usize, usize) {
+    let unit = width_bytes(width);
+    let head = core::cmp::min(address.wrapping_neg() & (unit - 1), len);
+    (head, (len - head) / unit * unit)
+}
+
+/// Check whether the current round ended.
+#[inline]
+fn status(channel: &UntypedChannel) -> Poll<Result<(), Error>> {
+    let (dma, id) = (channel.dma, channel.channel_id);
+    if dma
+        .interrupts
+        .raw_error
+        .read()
+        .if_raw_error_occurs(id as u8)
+    {
+        return Poll::Ready(Err(Error::BusError));
+    }
+    if channel.is_busy() {
+        return Poll::Pending;
+    }
+    compiler_fence(Ordering::Acquire);
+    Poll::Ready(Ok(()))
+}
+
+/// Stops the channel and clears its flags when a copy ends or is abandoned.
+struct Stop<'s, 'a>(&'s UntypedChannel<'a>);
+
+impl Drop for Stop<'_, '_> {
+    #[inline]
+    fn drop(&mut self) {
+        let (dma, id) = (self.0.dma, self.0.channel_id);
+        self.0.stop();
+        unsafe {
+            dma.interrupts
+                .transfer_complete_clear
+                .write(TransferCompleteClear::default().clear_cplt_int(id as u8));
+            dma.interrupts
+                .error_clear
+                .write(ErrorClear::default().clear_err_int(id as u8));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Body, DmaMemcpy, split};
+    use crate::dma::mock::MockDma;
+    use crate::dma::{
+        BurstSize, DmaMode, DmaState, Error, LliPool, Mem2MemChannelConfig, TransferWidth,
+    };
+    use core::future::Future;
+
+    #[test]
+    fn function_split() {
+        assert_eq!(split(0x1000, 16, TransferWidth::Word), (0, 16));
+        assert_eq!(split(0x1001, 10, TransferWidth::Word), (3, 4));
+        assert_eq!(split(0x1003, 2, TransferWidth::Word), (1, 0));
+        assert_eq!(split(0x1001, 6, TransferWidth::HalfWord), (1, 4));
+        assert_eq!(split(0x1001, 3, TransferWidth::Byte), (0, 3));
+    }
+
+    #[test]
+    fn struct_memcpy_software_parts() {
+        static mut LLI: [LliPool; 2] = [LliPool::new(); 2];
+        static mut EMPTY: [LliPool; 0] = [];
+        let dma = MockDma::new();
+        let lli = unsafe { &mut *core::ptr::addr_of_mut!(LLI) };
+        let mut memcpy = DmaMemcpy::new(dma.channel(0), lli);
+
+        let mut words = [0u32; 2];
+        let bytes = unsafe { core::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, 8) };
+        let source_words = [u32::from_le_bytes(*b"xabc"), u32::from_le_bytes(*b"defg")];
+        let source = unsafe { core::slice::from_raw_parts(source_words.as_ptr() as *const u8, 8) };
+        // Too short for a whole word on an unaligned address; copied by CPU only.
+        let body = memcpy
+            .prepare_copy(&mut bytes[1..4], &source[1..4])
+            .unwrap();
+        assert_eq!(body.len, 0);
+        assert_eq!(&bytes[..4], b"\0abc");
+        assert_eq!(memcpy.fill(&mut bytes[5..8], 0x44332211), Ok(()));
+        assert_eq!(&bytes[4..], &[0, 0x11, 0x22, 0x33]);
+        assert_eq!(
+            memcpy.copy(&mut bytes[..2], b"abc"),
+            Err(Error::LengthMismatch)
+        );
+        // Nothing started by DMA.
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+
+        let (channel, _) = memcpy.free();
+        let empty = unsafe { &mut *core::ptr::addr_of_mut!(EMPTY) };
+        let mut memcpy = DmaMemcpy::new(channel, empty);
+        assert_eq!(memcpy.fill(&mut bytes[..], 0), Err(Error::LliPoolTooSmall));
+        // Buffers shorter than a transfer unit need no linked list items.
+        assert_eq!(memcpy.fill(&mut bytes[1..3], 0x5A5A5A5A), Ok(()));
+        assert_eq!(&bytes[..4], b"\0ZZc");
+    }
+
+    #[test]
+    fn struct_memcpy_prepare_copy() {
+        static mut LLI: [LliPool; 1] = [LliPool::new(); 1];
+        let dma = MockDma::new();
+        let lli = unsafe { &mut *core::ptr::addr_of_mut!(LLI) };
+        let mut memcpy = DmaMemcpy::new(dma.channel(2), lli);
+
+        let mut words = [0u32; 2];
+        let bytes = unsafe { core::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, 8) };
+        let source_words = [u32::from_le_bytes(*b"abcd"), u32::from_le_bytes(*b"efgh")];
+        let source = unsafe { core::slice::from_raw_parts(source_words.as_ptr() as *const u8, 8) };
+        let (dst, src) = (bytes.as_ptr() as usize, source.as_ptr() as usize);
+        // Addresses differ by two bytes modulo four; DMA moves half words.
+        let body = memcpy.prepare_copy(&mut bytes[1..6], &source[3..]).unwrap();
+        assert_eq!(
+            body,
+            Body {
+                src: src + 4,
+                src_increment: true,
+                dst: dst + 2,
+                len: 4,
+                unit: 2,
+            }
+        );
+        assert_eq!(bytes, b"\0d\0\0\0\0\0\0");
+        assert_eq!(dma.channel_reg(2, 0x0c) & (0b11 << 18), 1 << 18);
+    }
+
+    #[test]
+    fn struct_memcpy_prepare_fill() {
+        static mut LLI: [LliPool; 1] = [LliPool::new(); 1];
+        let dma = MockDma::new();
+        let lli = unsafe { &mut *core::ptr::addr_of_mut!(LLI) };
+        let mut memcpy = DmaMemcpy::new(dma.channel(1), lli);
+
+        let mut words = [0u32; 4];
+        let bytes = unsafe { core::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, 16) };
+        let dst = bytes.as_ptr() as usize;
+        let body = memcpy.prepare_fill(&mut bytes[1..14], 0x44332211).unwrap();
+        assert_eq!(
+            body,
+            Body {
+                src: &memcpy.pattern as *const u32 as usize,
+                src_increment: false,
+                dst: dst + 4,
+                len: 8,
+                unit: 4,
+            }
+        );
+        // DMA words start from the fourth pattern byte.
+        assert_eq!(memcpy.pattern, 0x33221144);
+        assert_eq!(&bytes[..4], &[0, 0x11, 0x22, 0x33]);
+        assert_eq!(&bytes[12..], &[0x44, 0x11, 0, 0]);
+        // Source address is fixed for fills.
+        assert_eq!(dma.channel_reg(1, 0x0c) & (1 << 26), 0);
+        assert_ne!(dma.channel_reg(1, 0x0c) & (1 << 27), 0);
+    }
+
+    #[test]
+    fn struct_body_start() {
+        static mut LLI: [LliPool; 2] = [LliPool::new(); 2];
+        let dma = MockDma::new();
+        let mut channel = dma.channel(3);
+        channel.memory_to_memory(Mem2MemChannelConfig {
+            direction: DmaMode::Mem2Mem,
+            src_addr_inc: true,
+            dst_addr_inc: true,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::HalfWord,
+            dst_transfer_width: TransferWidth::HalfWord,
+        });
+        let lli = unsafe { &mut *core::ptr::addr_of_mut!(LLI) };
+        let body = Body {
+            src: 0x5000_0000,
+            src_increment: true,
+            dst: 0x2202_0000,
+            len: 10000 * 2,
+            unit: 2,
+        };
+        // Two items cover 8128 half words; the rest is moved in the next round.
+        assert_eq!(body.start(&channel, lli, 0), 8128 * 2);
+        assert_eq!(lli[0].src_addr, 0x5000_0000);
+        assert_eq!(lli[1].src_addr, 0x5000_0000 + 4064 * 2);
+        assert_eq!(lli[1].dst_addr, 0x2202_0000 + 4064 * 2);
+        assert_eq!(lli[0].next_lli, &lli[1] as *const LliPool as u32);
+        assert_eq!(lli[1].next_lli, 0);
+        assert_eq!(dma.channel_reg(3, 0x00), 0x5000_0000);
+        assert_eq!(dma.channel_reg(3, 0x04), 0x2202_0000);
+        assert_eq!(dma.channel_reg(3, 0x10) & 1, 1);
+
+        assert_eq!(body.start(&channel, lli, 8128 * 2), 1872 * 2);
+        assert_eq!(lli[0].src_addr, 0x5000_0000 + 8128 * 2);
+        assert_eq!(lli[0].dst_addr, 0x2202_0000 + 8128 * 2);
+        assert_eq!(lli[0].control.transfer_size(), 1872);
+        assert_eq!(lli[0].next_lli, 0);
+    }
+
+    #[test]
+    fn async_memcpy_fill_cancel() {
+        static mut LLI: [LliPool; 1] = [LliPool::new(); 1];
+        static mut WORDS: [u32; 4] = [0; 4];
+        let dma = MockDma::new();
+        let state = DmaState::new();
+        let lli = unsafe { &mut *core::ptr::addr_of_mut!(LLI) };
+        let mut memcpy = DmaMemcpy::new(dma.channel(2), lli);
+        let destination = unsafe { &mut *(core::ptr::addr_of_mut!(WORDS) as *mut [u8; 16]) };
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        {
+            // The future is dropped within this scope, before `destination` is released.
+            let fill = unsafe { memcpy.fill_async(destination, 0x5a5a_5a5a, &state) };
+            let mut fill = core::pin::pin!(fill);
+            assert!(fill.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(dma.channel_reg(2, 0x0c) & 0xfff, 4);
+            assert_eq!(dma.channel_reg(2, 0x10) & 1, 1);
+        }
+        // Abandoned fill stops the channel.
+        assert_eq!(dma.channel_reg(2, 0x10) & 1, 0);
+    }
+}
@@ -0,0 +1,62 @@
+//! Mock DMA controller for unit tests of DMA users.
+
+use super::{Endpoint, PeripheralId, RegisterBlock, TypedChannel, UntypedChannel};
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+
+/// Peripheral request with data register at a given address.
+#[derive(Clone, Copy)]
+pub(crate) struct MockRequest {
+    pub id: u8,
+    pub address: u32,
+}
+
+impl PeripheralId for MockRequest {
+    fn id(&self) -> u8 {
+        self.id
+    }
+    fn address(&self) -> Option<u32> {
+        Some(self.address)
+    }
+}
+
+pub(crate) fn endpoint(id: u8, address: u32) -> Endpoint<MockRequest> {
+    Endpoint::new(MockRequest { id, address }).unwrap()
+}
+
+/// DMA register block in memory; channel registers start at 0x100 with a 0x100 stride.
+pub(crate) struct MockDma(UnsafeCell<[u32; 0x900 / 4]>);
+
+impl MockDma {
+    pub fn new() -> Self {
+        MockDma(UnsafeCell::new([0; 0x900 / 4]))
+    }
+    pub fn regs(&self) -> *mut u32 {
+        self.0.get() as *mut u32
+    }
+    pub fn channel(&self, channel_id: usize) -> TypedChannel<'_, MockRequest> {
+        let dma = unsafe { &*(self.0.get() as *const RegisterBlock) };
+        TypedChannel {
+            inner: UntypedChannel { dma, channel_id },
+            _type_of_peripheral: PhantomData,
+        }
+    }
+    pub fn channel_reg(&self, channel_id: usize, offset: usize) -> u32 {
+        unsafe { *self.regs().add((0x100 + channel_id * 0x100 + offset) / 4) }
+    }
+    pub fn set_channel_reg(&self, channel_id: usize, offset: usize, val: u32) {
+        unsafe { *self.regs().add((0x100 + channel_id * 0x100 + offset) / 4) = val };
+    }
+    /// Hardware clears channel enable bit once the chunk is moved.
+    pub fn complete(&self, channel_id: usize) {
+        unsafe { *self.regs().add((0x110 + channel_id * 0x100) / 4) &= !1 };
+    }
+    /// Set or clear raw transfer complete state of a channel.
+    pub fn set_raw_complete(&self, channel_id: usize, raised: bool) {
+        let raw = unsafe { &mut *self.regs().add(0x14 / 4) };
+        match raised {
+            true => *raw |= 1 << channel_id,
+            false => *raw &= !(1 << channel_id),
+        }
+    }
+}
@@ -1,7 +1,6 @@
-use super::{
-    PeripheralId,
-    config::{Periph4Dma01, Periph4Dma2},
-};
+use super::PeripheralId;
+#[cfg(feature = "bl808")]
+use super::{Periph4Dma01, Periph4Dma2};
 use volatile_register::{RO, RW, WO};
 
 /// Direct Memory Access peripheral registers.
@@ -270,6 +269,9 @@ impl LliControl {
     const SRC_BST_SIZE: u32 = 0x3 << 12;
     const TRANSFER_SIZE: u32 = 0xFFF;
 
+    /// Control word with every field cleared.
+    pub(crate) const EMPTY: Self = Self(0);
+
     /// Enable completion interrupt.
     #[inline]
     pub const fn enable_cplt_int(self) -> Self {
@@ -454,6 +456,7 @@ pub enum DmaMode {
 }
 
 /// DMA peripheral request definition
+#[cfg(feature = "bl808")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DmaPeriphReq {
     /// Dma request for peripheral for DMA 0/1.
@@ -580,6 +583,35 @@ impl ChannelConfig {
     pub fn clear_dst_periph(self) -> Self {
         Self(self.0 & !Self::DST_PERIPH)
     }
+    /// Set source peripheral for any DMA.
+    #[inline]
+    pub fn set_src_periph(self, periph: impl PeripheralId) -> Self {
+        Self((self.0 & !Self::SRC_PERIPH) | ((periph.id() as u32) << 1))
+    }
+    /// Clear source peripheral for any DMA.
+    #[inline]
+    pub fn clear_src_periph(self) -> Self {
+        Self(self.0 & !Self::SRC_PERIPH)
+    }
+    /// Enable channel.
+    #[inline]
+    pub const fn enable_ch(self) -> Self {
+        Self((self.0 & !Self::CH_EN) | 1)
+    }
+    /// Disable channel.
+    #[inline]
+    pub const fn disable_ch(self) -> Self {
+        Self((self.0 & !Self::CH_EN) | 0)
+    }
+    /// Check if channel is enabled.
+    #[inline]
+    pub const fn is_ch_enabled(self) -> bool {
+        (self.0 & Self::CH_EN) != 0
+    }
+}
+
+#[cfg(feature = "bl808")]
+impl ChannelConfig {
     /// Set destination peripheral for DMA 0/1.
     #[inline]
     pub const fn set_dst_periph4dma01(self, periph: Periph4Dma01) -> Self {
@@ -636,16 +668,6 @@ impl ChannelConfig {
             _ => unreachable!(),
         }
     }
-    /// Set source peripheral for any DMA.
-    #[inline]
-    pub fn set_src_periph(self, periph: impl PeripheralId) -> Self {
-        Self((self.0 & !Self::SRC_PERIPH) | ((periph.id() as u32) << 1))
-    }
-    /// Clear source peripheral for any DMA.
-    #[inline]
-    pub fn clear_src_periph(self) -> Self {
-        Self(self.0 & !Self::SRC_PERIPH)
-    }
     /// Set source peripheral for DMA 0/1.
     #[inline]
     pub const fn set_src_periph4dma01(self, periph: Periph4Dma01) -> Self {
@@ -702,21 +724,6 @@ impl ChannelConfig {
             _ => unreachable!(),
         }
     }
-    /// Enable channel.
-    #[inline]
-    pub const fn enable_ch(self) -> Self {
-        Self((self.0 & !Self::CH_EN) | 1)
-    }
-    /// Disable channel.
-    #[inline]
-    pub const fn disable_ch(self) -> Self {
-        Self((self.0 & !Self::CH_EN) | 0)
-    }
-    /// Check if channel is enabled.
-    #[inline]
-    pub const fn is_ch_enabled(self) -> bool {
-        (self.0 & Self::CH_EN) != 0
-    }
 }
 
 #[cfg(test)]
@@ -724,9 +731,11 @@ mod tests {
     use super::{
         BurstSize, ChannelConfig, ChannelRegisters, DmaMode, EnabledChannels, EndianMode,
         ErrorClear, ErrorState, GlobalConfig, GlobalState, InterruptRegisters, LliControl,
-        Periph4Dma01, Periph4Dma2, RawError, RawTransferComplete, RegisterBlock,
-        TransferCompleteClear, TransferCompleteState, TransferWidth,
+        RawError, RawTransferComplete, RegisterBlock, TransferCompleteClear, TransferCompleteState,
+        TransferWidth,
     };
+    #[cfg(feature = "bl808")]
+    use super::{Periph4Dma01, Periph4Dma2};
     use core::mem::offset_of;
 
     #[test]
@@ -980,6 +989,19 @@ mod tests {
             assert_eq!(val.0, tmp_val);
         }
 
+        val = ChannelConfig(0x0);
+        val = val.enable_ch();
+        assert!(val.is_ch_enabled());
+        assert_eq!(val.0, 0x00000001);
+        val = val.disable_ch();
+        assert!(!val.is_ch_enabled());
+        assert_eq!(val.0, 0x00000000);
+    }
+
+    #[cfg(feature = "bl808")]
+    #[test]
+    fn struct_channel_config_periph4dma_functions() {
+        let mut val: ChannelConfig;
         val = ChannelConfig(0x0);
         // The number 'i' is not related to the actual register, but only to make the code more concise.
         for i in 0..21 as u8 {
@@ -1155,13 +1177,5 @@ mod tests {
             assert_eq!(val.src_periph4dma2(), tmp_periph);
             assert_eq!(val.0, tmp_val);
         }
-
-        val = ChannelConfig(0x0);
-        val = val.enable_ch();
-        assert!(val.is_ch_enabled());
-        assert_eq!(val.0, 0x00000001);
-        val = val.disable_ch();
-        assert!(!val.is_ch_enabled());
-        assert_eq!(val.0, 0x00000000);
     }
 }
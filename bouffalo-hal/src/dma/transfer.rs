@@ -0,0 +1,648 @@
+use core::future::poll_fn;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicUsize, Ordering, compiler_fence};
+use core::task::{Poll, Waker};
+
+use as_slice::{AsMutSlice, AsSlice};
+
+use super::cache::{clean_dcache, flush_dcache, invalidate_dcache};
+use super::channel::{TypedChannel, UntypedChannel};
+use super::config::{DmaChannelConfig, Mem2MemChannelConfig, PeripheralId};
+use super::register::{
+    BurstSize, DmaMode, ErrorClear, RegisterBlock, TransferCompleteClear, TransferWidth,
+};
+use super::{Endpoint, LliPool};
+
+/// Maximum transfer units moved by one linked list item.
+const CHUNK_LENGTH: usize = 4064;
+
+/// Errors on DMA transfers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Buffer has no data to transfer.
+    EmptyBuffer,
+    /// Buffer address or length is not a multiple of transfer width.
+    Misaligned,
+    /// Source and destination buffers have different lengths.
+    LengthMismatch,
+    /// Bus error occurred while transferring.
+    BusError,
+    /// Not enough linked list items to cover the buffer.
+    LliPoolTooSmall,
+    /// Circular buffer data was overwritten by DMA before it was read.
+    Overrun,
+}
+
+impl<'a, T: PeripheralId + Copy> TypedChannel<'a, T> {
+    /// Start copying an owned source buffer into an owned destination buffer.
+    ///
+    /// Both buffers must have the same length, and their addresses and length must be
+    /// multiples of `width`. Buffers are returned back with the error otherwise.
+    #[inline]
+    pub fn transfer_memory<S, D>(
+        &mut self,
+        source: S,
+        mut destination: D,
+        width: TransferWidth,
+    ) -> Result<MemoryTransfer<'_, 'a, T, S, D>, (Error, S, D)>
+    where
+        S: Deref + 'static,
+        S::Target: AsSlice<Element = u8>,
+        D: DerefMut + 'static,
+        D::Target: AsMutSlice<Element = u8>,
+    {
+        let src = source.as_slice();
+        let dst = destination.as_mut_slice();
+        let (src_ptr, src_len) = (src.as_ptr() as usize, src.len());
+        let (dst_ptr, dst_len) = (dst.as_mut_ptr() as usize, dst.len());
+        let checked = match src_len == dst_len {
+            true => check_buffer(src_ptr, src_len, width)
+                .and_then(|_| check_buffer(dst_ptr, dst_len, width)),
+            false => Err(Error::LengthMismatch),
+        };
+        if let Err(e) = checked {
+            return Err((e, source, destination));
+        }
+        self.memory_to_memory(Mem2MemChannelConfig {
+            direction: DmaMode::Mem2Mem,
+            src_addr_inc: true,
+            dst_addr_inc: true,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: width,
+            dst_transfer_width: width,
+        });
+        let endpoints = Endpoints::new(src_ptr, true, dst_ptr, true, src_len, width);
+        let resources = Resources {
+            source,
+            destination,
+        };
+        Ok(Transfer::new(self, resources, endpoints))
+    }
+
+    /// Start sending an owned buffer to a peripheral endpoint.
+    ///
+    /// Buffer address and length must be multiples of `width`; the buffer is returned back
+    /// with the error otherwise.
+    #[inline]
+    pub fn transfer_to_peripheral<S>(
+        &mut self,
+        source: S,
+        endpoint: Endpoint<T>,
+        width: TransferWidth,
+    ) -> Result<Transfer<'_, 'a, T, S>, (Error, S)>
+    where
+        S: Deref + 'static,
+        S::Target: AsSlice<Element = u8>,
+    {
+        let src = source.as_slice();
+        let (ptr, len) = (src.as_ptr() as usize, src.len());
+        if let Err(e) = check_buffer(ptr, len, width) {
+            return Err((e, source));
+        }
+        self.configure(DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            src_req: None,
+            dst_req: Some(endpoint.request()),
+            src_addr_inc: true,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: width,
+            dst_transfer_width: width,
+        });
+        let endpoints = Endpoints::new(ptr, true, endpoint.address() as usize, false, len, width);
+        Ok(Transfer::new(self, source, endpoints))
+    }
+
+    /// Start receiving from a peripheral endpoint into an owned buffer.
+    ///
+    /// Buffer address and length must be multiples of `width`; the buffer is returned back
+    /// with the error otherwise.
+    #[inline]
+    pub fn transfer_from_peripheral<D>(
+        &mut self,
+        endpoint: Endpoint<T>,
+        mut destination: D,
+        width: TransferWidth,
+    ) -> Result<Transfer<'_, 'a, T, D>, (Error, D)>
+    where
+        D: DerefMut + 'static,
+        D::Target: AsMutSlice<Element = u8>,
+    {
+        let dst = destination.as_mut_slice();
+        let (ptr, len) = (dst.as_mut_ptr() as usize, dst.len());
+        if let Err(e) = check_buffer(ptr, len, width) {
+            return Err((e, destination));
+        }
+        self.configure(DmaChannelConfig {
+            direction: DmaMode::Periph2Mem,
+            src_req: Some(endpoint.request()),
+            dst_req: None,
+            src_addr_inc: false,
+            dst_addr_inc: true,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: width,
+            dst_transfer_width: width,
+        });
+        let endpoints = Endpoints::new(endpoint.address() as usize, false, ptr, true, len, width);
+        Ok(Transfer::new(self, destination, endpoints))
+    }
+}
+
+/// Owned buffer pair of a memory to memory transfer.
+#[derive(Copy, Clone, Debug)]
+pub struct Resources<S, D> {
+    /// Transfer source buffer.
+    pub source: S,
+    /// Transfer destination buffer.
+    pub destination: D,
+}
+
+/// Ongoing memory to memory DMA transfer.
+pub type MemoryTransfer<'c, 'a, T, S, D> = Transfer<'c, 'a, T, Resources<S, D>>;
+
+/// Bytes of one transfer unit.
+#[inline]
+pub(super) const fn width_bytes(width: TransferWidth) -> usize {
+    match width {
+        TransferWidth::Byte => 1,
+        TransferWidth::HalfWord => 2,
+        TransferWidth::Word => 4,
+        TransferWidth::DoubleWord => 8,
+    }
+}
+
+/// Check that a buffer is not empty and is aligned to transfer width.
+#[inline]
+pub(super) fn check_buffer(ptr: usize, len: usize, width: TransferWidth) -> Result<(), Error> {
+    let bytes = width_bytes(width);
+    if len == 0 {
+        Err(Error::EmptyBuffer)
+    } else if !ptr.is_multiple_of(bytes) || !len.is_multiple_of(bytes) {
+        Err(Error::Misaligned)
+    } else {
+        Ok(())
+    }
+}
+
+/// Addresses and progress of an ongoing transfer, in transfer units.
+#[derive(Clone, Copy, Debug)]
+struct Endpoints {
+    src: usize,
+    src_increment: bool,
+    dst: usize,
+    dst_increment: bool,
+    unit: usize,
+    len: usize,
+    offset: usize,
+}
+
+impl Endpoints {
+    #[inline]
+    fn new(
+        src: usize,
+        src_increment: bool,
+        dst: usize,
+        dst_increment: bool,
+        bytes: usize,
+        width: TransferWidth,
+    ) -> Self {
+        let unit = width_bytes(width);
+        Endpoints {
+            src,
+            src_increment,
+            dst,
+            dst_increment,
+            unit,
+            len: bytes / unit,
+            offset: 0,
+        }
+    }
+    /// Transfer units moved by the current chunk.
+    #[inline]
+    fn chunk(&self) -> usize {
+        core::cmp::min(self.len - self.offset, CHUNK_LENGTH)
+    }
+    /// Write back source buffer, and flush destination buffer before DMA writes it.
+    #[inline]
+    fn prepare_cache(&self) {
+        let bytes = self.len * self.unit;
+        if self.src_increment {
+            clean_dcache(self.src, bytes);
+        }
+        if self.dst_increment {
+            flush_dcache(self.dst, bytes);
+        }
+    }
+    /// Discard stale cache lines of destination buffer written by DMA.
+    #[inline]
+    fn complete_cache(&self) {
+        if self.dst_increment {
+            // Destination buffer is owned by the transfer and not written by CPU meanwhile.
+            unsafe { invalidate_dcache(self.dst, self.len * self.unit) };
+        }
+    }
+    /// Source and destination addresses of the current chunk.
+    #[inline]
+    fn addresses(&self) -> (u32, u32) {
+        let bytes = self.offset * self.unit;
+        let src = match self.src_increment {
+            true => self.src + bytes,
+            false => self.src,
+        };
+        let dst = match self.dst_increment {
+            true => self.dst + bytes,
+            false => self.dst,
+        };
+        (src as u32, dst as u32)
+    }
+}
+
+/// Progress of a DMA transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Progress {
+    Ongoing,
+    Done,
+    Failed,
+}
+
+/// Ongoing DMA transfer on owned buffers.
+///
+/// Buffers longer than 4064 transfer units are moved in several chunks; the CPU restarts
+/// the channel between chunks, when the transfer is polled. Dropping an ongoing transfer
+/// stops the channel before the buffers are released.
+pub struct Transfer<'c, 'a, T, R> {
+    channel: &'c mut TypedChannel<'a, T>,
+    resources: Option<R>,
+    endpoints: Endpoints,
+    progress: Progress,
+}
+
+impl<'c, 'a, T, R> Transfer<'c, 'a, T, R> {
+    #[inline]
+    fn new(channel: &'c mut TypedChannel<'a, T>, resources: R, endpoints: Endpoints) -> Self {
+        let mut transfer = Transfer {
+            channel,
+            resources: Some(resources),
+            endpoints,
+            progress: Progress::Ongoing,
+        };
+        transfer.endpoints.prepare_cache();
+        transfer.start_chunk();
+        transfer
+    }
+
+    /// Checks whether the transfer has ended, starting the next chunk if needed.
+    #[inline]
+    pub fn is_done(&mut self) -> bool {
+        self.poll() != Progress::Ongoing
+    }
+
+    /// Waits for the transfer to end and returns the buffers.
+    #[inline]
+    pub fn wait(mut self) -> Result<R, (Error, R)> {
+        while self.poll() == Progress::Ongoing {
+            core::hint::spin_loop();
+        }
+        self.take_result()
+    }
+
+    /// Waits for the transfer to end asynchronously and returns the buffers.
+    ///
+    /// `state` must be notified with `DmaState::on_interrupt` in the DMA interrupt handler.
+    #[inline]
+    pub async fn wait_async(mut self, state: &DmaState) -> Result<R, (Error, R)> {
+        let (dma, id) = (self.channel.dma, self.channel.channel_id);
+        state.register(dma);
+        poll_fn(|cx| {
+            state.wakers[id].register(cx.waker());
+            if self.poll() != Progress::Ongoing {
+                return Poll::Ready(());
+            }
+            unsafe {
+                dma.channels[id]
+                    .config
+                    .modify(|val| val.enable_cplt_int().enable_err_int())
+            };
+            Poll::Pending
+        })
+        .await;
+        self.take_result()
+    }
+
+    /// Stops the transfer and returns the buffers.
+    ///
+    /// Data may be partially transferred.
+    #[inline]
+    pub fn cancel(mut self) -> R {
+        if self.progress == Progress::Ongoing {
+            self.finish(Progress::Done);
+        }
+        self.resources.take().unwrap()
+    }
+
+    #[inline]
+    fn take_result(&mut self) -> Result<R, (Error, R)> {
+        let resources = self.resources.take().unwrap();
+        match self.progress {
+            Progress::Failed => Err((Error::BusError, resources)),
+            _ => Ok(resources),
+        }
+    }
+
+    #[inline]
+    fn start_chunk(&mut self) {
+        let (dma, id) = (self.channel.dma, self.channel.channel_id);
+        let (src, dst) = self.endpoints.addresses();
+        let mut lli = [LliPool::new()];
+        self.channel
+            .lli_config(&mut lli, 1, src, dst, 0, self.endpoints.chunk() as u32);
+        unsafe {
+            dma.interrupts
+                .transfer_complete_clear
+                .write(TransferCompleteClear::default().clear_cplt_int(id as u8))
+        };
+        compiler_fence(Ordering::Release);
+        self.channel.lli_load(&lli[0]);
+        self.channel.start();
+    }
+
+    #[inline]
+    fn poll(&mut self) -> Progress {
+        if self.progress != Progress::Ongoing {
+            return self.progress;
+        }
+        let (dma, id) = (self.channel.dma, self.channel.channel_id);
+        if dma
+            .interrupts
+            .raw_error
+            .read()
+            .if_raw_error_occurs(id as u8)
+        {
+            self.finish(Progress::Failed);
+            return self.progress;
+        }
+        if self.channel.is_busy() {
+            return Progress::Ongoing;
+        }
+        self.endpoints.offset += self.endpoints.chunk();
+        if self.endpoints.offset < self.endpoints.len {
+            self.start_chunk();
+        } else {
+            compiler_fence(Ordering::Acquire);
+            self.finish(Progress::Done);
+        }
+        self.progress
+    }
+
+    #[inline]
+    fn finish(&mut self, progress: Progress) {
+        let (dma, id) = (self.channel.dma, self.channel.channel_id);
+        self.channel.stop();
+        unsafe {
+            dma.interrupts
+                .transfer_complete_clear
+                .write(TransferCompleteClear::default().clear_cplt_int(id as u8));
+            dma.interrupts
+                .error_clear
+                .write(ErrorClear::default().clear_err_int(id as u8));
+        }
+        self.endpoints.complete_cache();
+        self.progress = progress;
+    }
+}
+
+impl<'c, 'a, T, R> Drop for Transfer<'c, 'a, T, R> {
+    /// Stops the DMA channel before the buffers could be released.
+    #[inline]
+    fn drop(&mut self) {
+        if self.progress == Progress::Ongoing {
+            self.finish(Progress::Done);
+        }
+    }
+}
+
+/// Wakers as the state for async/await DMA transfers.
+///
+/// One state serves all the channels of a DMA peripheral.
+#[derive(Debug)]
+pub struct DmaState {
+    pub(super) wakers: [atomic_waker::AtomicWaker; 8],
+    ref_to_dma: AtomicUsize,
+}
+
+impl DmaState {
+    /// Creates the waker state for a DMA peripheral.
+    #[inline]
+    pub const fn new() -> DmaState {
+        DmaState {
+            wakers: [const { atomic_waker::AtomicWaker::new() }; 8],
+            ref_to_dma: AtomicUsize::new(0),
+        }
+    }
+    /// Point this state to the DMA peripheral being waited on.
+    #[inline]
+    pub(super) fn register(&self, dma: &RegisterBlock) {
+        self.ref_to_dma
+            .store(dma as *const _ as usize, Ordering::Release);
+    }
+    /// Use this state to handle interrupt.
+    ///
+    /// Interrupts of pending channels are masked and their waiting tasks are woken.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let dma = self.ref_to_dma.load(Ordering::Acquire) as *const RegisterBlock;
+        if dma.is_null() {
+            return;
+        }
+        let dma = unsafe { &*dma };
+        let state = dma.interrupts.global_state.read();
+        for (id, waker) in self.wakers.iter().enumerate() {
+            if state.if_int_occurs(id as u8) {
+                unsafe {
+                    dma.channels[id]
+                        .config
+                        .modify(|val| val.disable_cplt_int().disable_err_int())
+                };
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl UntypedChannel<'_> {
+    /// Clear completion and error states of this channel, e.g. before starting it.
+    #[inline]
+    pub(crate) fn clear_states(&self) {
+        let id = self.channel_id as u8;
+        unsafe {
+            self.dma
+                .interrupts
+                .transfer_complete_clear
+                .write(TransferCompleteClear::default().clear_cplt_int(id));
+            self.dma
+                .interrupts
+                .error_clear
+                .write(ErrorClear::default().clear_err_int(id));
+        }
+    }
+    /// Wake `waker` on completion or error of this channel, unmasking its interrupts.
+    ///
+    /// For drivers waiting on DMA along with their own events. `state` must be notified
+    /// with `DmaState::on_interrupt` in the DMA interrupt handler.
+    #[inline]
+    pub(crate) fn listen(&self, state: &DmaState, waker: &Waker) {
+        state.register(self.dma);
+        state.wakers[self.channel_id].register(waker);
+        unsafe {
+            self.dma.channels[self.channel_id]
+                .config
+                .modify(|val| val.enable_cplt_int().enable_err_int())
+        };
+    }
+}
+
+impl Default for DmaState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DmaState, Error, Transfer, check_buffer};
+    use crate::dma::TransferWidth;
+    use crate::dma::mock::{MockDma, endpoint};
+    use core::future::Future;
+
+    #[test]
+    fn function_check_buffer() {
+        assert_eq!(
+            check_buffer(0x1000, 0, TransferWidth::Byte),
+            Err(Error::EmptyBuffer)
+        );
+        assert_eq!(check_buffer(0x1001, 3, TransferWidth::Byte), Ok(()));
+        assert_eq!(check_buffer(0x1002, 6, TransferWidth::HalfWord), Ok(()));
+        assert_eq!(
+            check_buffer(0x1001, 6, TransferWidth::HalfWord),
+            Err(Error::Misaligned)
+        );
+        assert_eq!(
+            check_buffer(0x1004, 6, TransferWidth::Word),
+            Err(Error::Misaligned)
+        );
+        assert_eq!(check_buffer(0x1008, 16, TransferWidth::DoubleWord), Ok(()));
+    }
+
+    #[test]
+    fn struct_transfer_errors() {
+        static SOURCE: [u8; 8] = [0; 8];
+        static mut DESTINATION: [u8; 4] = [0; 4];
+        let dma = MockDma::new();
+        let mut channel = dma.channel(0);
+        let destination = unsafe { &mut *core::ptr::addr_of_mut!(DESTINATION) };
+        assert!(matches!(
+            channel.transfer_memory(&SOURCE, destination, TransferWidth::Byte),
+            Err((Error::LengthMismatch, _, _))
+        ));
+
+        assert!(matches!(
+            channel.transfer_to_peripheral(
+                &SOURCE[..6],
+                endpoint(1, 0x2000A088),
+                TransferWidth::Word,
+            ),
+            Err((Error::Misaligned, _))
+        ));
+        assert!(matches!(
+            channel.transfer_to_peripheral(
+                &SOURCE[..0],
+                endpoint(1, 0x2000A088),
+                TransferWidth::Byte,
+            ),
+            Err((Error::EmptyBuffer, _))
+        ));
+        // Nothing started on error.
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+    }
+
+    #[test]
+    fn struct_transfer_chunks() {
+        static SOURCE: [u8; 5000] = [0; 5000];
+        let dma = MockDma::new();
+        let mut channel = dma.channel(2);
+        let mut transfer: Transfer<'_, '_, _, _> = channel
+            .transfer_to_peripheral(&SOURCE[..], endpoint(1, 0x2000A088), TransferWidth::Byte)
+            .ok()
+            .unwrap();
+        let src = SOURCE.as_ptr() as usize as u32;
+        assert_eq!(dma.channel_reg(2, 0x00), src);
+        assert_eq!(dma.channel_reg(2, 0x04), 0x2000A088);
+        assert_eq!(dma.channel_reg(2, 0x0c) & 0xfff, 4064);
+        assert_eq!(dma.channel_reg(2, 0x10) & 1, 1);
+        assert!(!transfer.is_done());
+
+        dma.complete(2);
+        assert!(!transfer.is_done());
+        assert_eq!(dma.channel_reg(2, 0x00), src.wrapping_add(4064));
+        assert_eq!(dma.channel_reg(2, 0x04), 0x2000A088);
+        assert_eq!(dma.channel_reg(2, 0x0c) & 0xfff, 5000 - 4064);
+
+        dma.complete(2);
+        assert!(transfer.is_done());
+        let buffer = transfer.wait().ok().unwrap();
+        assert_eq!(buffer.len(), 5000);
+    }
+
+    #[test]
+    fn struct_transfer_bus_error() {
+        static SOURCE: [u32; 4] = [0; 4];
+        static mut DESTINATION: [u32; 4] = [0; 4];
+        let dma = MockDma::new();
+        let mut channel = dma.channel(1);
+        let destination = unsafe { &mut *core::ptr::addr_of_mut!(DESTINATION) };
+        let source: &[u8] = unsafe { core::slice::from_raw_parts(SOURCE.as_ptr().cast(), 16) };
+        let destination: &mut [u8] =
+            unsafe { core::slice::from_raw_parts_mut(destination.as_mut_ptr().cast(), 16) };
+        let transfer = channel
+            .transfer_memory(source, destination, TransferWidth::Word)
+            .ok()
+            .unwrap();
+        assert_eq!(dma.channel_reg(1, 0x0c) & 0xfff, 4);
+        // Raw error state of channel 1.
+        unsafe { *dma.regs().add(0x18 / 4) = 1 << 1 };
+        let ans = transfer.wait();
+        assert!(matches!(ans, Err((Error::BusError, _))));
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 0);
+    }
+
+    #[test]
+    fn struct_dma_state_wait_async() {
+        static SOURCE: [u8; 16] = [0; 16];
+        let dma = MockDma::new();
+        let state = DmaState::new();
+        // Unregistered state ignores interrupts.
+        state.on_interrupt();
+        let mut channel = dma.channel(3);
+        let transfer = channel
+            .transfer_to_peripheral(&SOURCE[..], endpoint(11, 0x2000A288), TransferWidth::Byte)
+            .ok()
+            .unwrap();
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut wait = core::pin::pin!(transfer.wait_async(&state));
+        assert!(wait.as_mut().poll(&mut cx).is_pending());
+        // Waiting unmasks channel interrupts.
+        assert_eq!(dma.channel_reg(3, 0x10) & (0b11 << 14), 0b11 << 14);
+        dma.complete(3);
+        unsafe { *dma.regs() = 1 << 3 };
+        state.on_interrupt();
+        assert_eq!(dma.channel_reg(3, 0x10) & (0b11 << 14), 0);
+        assert!(matches!(
+            wait.as_mut().poll(&mut cx),
+            core::task::Poll::Ready(Ok(_))
+        ));
+    }
+}
@@ -9,6 +9,12 @@ pub struct RegisterBlock {
     pub cpu_config_0: RW<CpuConfig0>,
     /// CPU clock configuration register 1.
     pub cpu_config_1: RW<CpuConfig1>,
+    _reserved0: [u8; 0x8],
+    /// Peripheral clock configuration register 0.
+    pub peripheral_config_0: RW<PeripheralConfig>,
+    _reserved1: [u8; 0x4],
+    /// Peripheral clock configuration register 3.
+    pub peripheral_config_3: RW<PeripheralConfig>,
 }
 
 /// CPU clock source.
@@ -33,6 +39,26 @@ pub enum CpuRootClockSource {
     Pll = 1,
 }
 
+/// Inter-Integrated Circuit clock source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum I2cClockSource {
+    /// Multi-media subsystem bus clock.
+    Bclk = 0,
+    /// Crystal oscillator clock.
+    Xclk = 1,
+}
+
+/// Serial Peripheral Interface clock source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SpiClockSource {
+    /// 160-MHz multiplexer PLL.
+    MuxPll160M = 0,
+    /// Crystal oscillator clock.
+    Xclk = 1,
+}
+
 /// CPU clock configuration register 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -40,6 +66,8 @@ pub struct CpuConfig0(u32);
 
 impl CpuConfig0 {
     const CPU_CLOCK_ENABLE: u32 = 0x1 << 1;
+    const I2C_CLOCK_SELECT: u32 = 0x1 << 6;
+    const SPI_CLOCK_SELECT: u32 = 0x1 << 7;
     const CPU_CLOCK_SELECT: u32 = 0x3 << 8;
     const CPU_ROOT_CLOCK_SELECT: u32 = 0x1 << 11;
 
@@ -58,6 +86,32 @@ impl CpuConfig0 {
     pub const fn is_cpu_clock_enabled(self) -> bool {
         self.0 & Self::CPU_CLOCK_ENABLE != 0
     }
+    /// Set clock source for Inter-Integrated Circuit peripherals.
+    #[inline]
+    pub const fn set_i2c_clock_source(self, val: I2cClockSource) -> Self {
+        Self((self.0 & !Self::I2C_CLOCK_SELECT) | ((val as u32) << 6))
+    }
+    /// Get clock source for Inter-Integrated Circuit peripherals.
+    #[inline]
+    pub const fn i2c_clock_source(self) -> I2cClockSource {
+        match (self.0 & Self::I2C_CLOCK_SELECT) >> 6 {
+            0 => I2cClockSource::Bclk,
+            _ => I2cClockSource::Xclk,
+        }
+    }
+    /// Set clock source for Serial Peripheral Interface peripheral.
+    #[inline]
+    pub const fn set_spi_clock_source(self, val: SpiClockSource) -> Self {
+        Self((self.0 & !Self::SPI_CLOCK_SELECT) | ((val as u32) << 7))
+    }
+    /// Get clock source for Serial Peripheral Interface peripheral.
+    #[inline]
+    pub const fn spi_clock_source(self) -> SpiClockSource {
+        match (self.0 & Self::SPI_CLOCK_SELECT) >> 7 {
+            0 => SpiClockSource::MuxPll160M,
+            _ => SpiClockSource::Xclk,
+        }
+    }
     /// Set clock source for CPU.
     #[inline]
     pub const fn set_cpu_clock_source(self, val: CpuClockSource) -> Self {
@@ -108,11 +162,102 @@ impl CpuConfig1 {
     }
 }
 
+/// Peripheral clock configuration register.
+///
+/// Register 0 holds clock of multi-media I2C0 (I2C2) and SPI (SPI1), register 3 holds clock
+/// of multi-media I2C1 (I2C3); both use the same field layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct PeripheralConfig(u32);
+
+impl PeripheralConfig {
+    const I2C_CLOCK_DIVIDE: u32 = 0xff;
+    const I2C_CLOCK_DIVIDE_ENABLE: u32 = 0x1 << 8;
+    const I2C_CLOCK_ENABLE: u32 = 0x1 << 9;
+    const SPI_CLOCK_DIVIDE_ENABLE: u32 = 0x1 << 23;
+    const SPI_CLOCK_DIVIDE: u32 = 0xff << 24;
+
+    /// Set Inter-Integrated Circuit peripheral clock divide factor.
+    #[inline]
+    pub const fn set_i2c_clock_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::I2C_CLOCK_DIVIDE) | (val as u32))
+    }
+    /// Get Inter-Integrated Circuit peripheral clock divide factor.
+    #[inline]
+    pub const fn i2c_clock_divide(self) -> u8 {
+        (self.0 & Self::I2C_CLOCK_DIVIDE) as u8
+    }
+    /// Enable Inter-Integrated Circuit peripheral clock divider.
+    #[inline]
+    pub const fn enable_i2c_clock_divide(self) -> Self {
+        Self(self.0 | Self::I2C_CLOCK_DIVIDE_ENABLE)
+    }
+    /// Disable Inter-Integrated Circuit peripheral clock divider.
+    #[inline]
+    pub const fn disable_i2c_clock_divide(self) -> Self {
+        Self(self.0 & !Self::I2C_CLOCK_DIVIDE_ENABLE)
+    }
+    /// Check if Inter-Integrated Circuit peripheral clock divider is enabled.
+    #[inline]
+    pub const fn is_i2c_clock_divide_enabled(self) -> bool {
+        self.0 & Self::I2C_CLOCK_DIVIDE_ENABLE != 0
+    }
+    /// Enable clock for Inter-Integrated Circuit peripheral.
+    #[inline]
+    pub const fn enable_i2c_clock(self) -> Self {
+        Self(self.0 | Self::I2C_CLOCK_ENABLE)
+    }
+    /// Disable clock for Inter-Integrated Circuit peripheral.
+    #[inline]
+    pub const fn disable_i2c_clock(self) -> Self {
+        Self(self.0 & !Self::I2C_CLOCK_ENABLE)
+    }
+    /// Check if clock for Inter-Integrated Circuit peripheral is enabled.
+    #[inline]
+    pub const fn is_i2c_clock_enabled(self) -> bool {
+        self.0 & Self::I2C_CLOCK_ENABLE != 0
+    }
+    /// Set Serial Peripheral Interface peripheral clock divide factor.
+    #[inline]
+    pub const fn set_spi_clock_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::SPI_CLOCK_DIVIDE) | ((val as u32) << 24))
+    }
+    /// Get Serial Peripheral Interface peripheral clock divide factor.
+    #[inline]
+    pub const fn spi_clock_divide(self) -> u8 {
+        ((self.0 & Self::SPI_CLOCK_DIVIDE) >> 24) as u8
+    }
+    /// Enable Serial Peripheral Interface peripheral clock divider.
+    #[inline]
+    pub const fn enable_spi_clock_divide(self) -> Self {
+        Self(self.0 | Self::SPI_CLOCK_DIVIDE_ENABLE)
+    }
+    /// Disable Serial Peripheral Interface peripheral clock divider.
+    #[inline]
+    pub const fn disable_spi_clock_divide(self) -> Self {
+        Self(self.0 & !Self::SPI_CLOCK_DIVIDE_ENABLE)
+    }
+    /// Check if Serial Peripheral Interface peripheral clock divider is enabled.
+    #[inline]
+    pub const fn is_spi_clock_divide_enabled(self) -> bool {
+        self.0 & Self::SPI_CLOCK_DIVIDE_ENABLE != 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::glb::mm::{CpuClockSource, CpuRootClockSource};
+    use crate::glb::mm::{CpuClockSource, CpuRootClockSource, I2cClockSource, SpiClockSource};
 
-    use super::{CpuConfig0, CpuConfig1};
+    use super::{CpuConfig0, CpuConfig1, PeripheralConfig, RegisterBlock};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, cpu_config_0), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, cpu_config_1), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, peripheral_config_0), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, peripheral_config_3), 0x18);
+    }
 
     #[test]
     fn struct_cpu_config0_functions() {
@@ -151,6 +296,69 @@ mod tests {
         assert_eq!(config.cpu_root_clock_source(), CpuRootClockSource::Xclk);
     }
 
+    #[test]
+    fn struct_cpu_config0_i2c_clock_source() {
+        let mut config = CpuConfig0(0x0);
+        config = config.set_i2c_clock_source(I2cClockSource::Xclk);
+        assert_eq!(config.0, 0x00000040);
+        assert_eq!(config.i2c_clock_source(), I2cClockSource::Xclk);
+
+        config = config.set_i2c_clock_source(I2cClockSource::Bclk);
+        assert_eq!(config.0, 0x00000000);
+        assert_eq!(config.i2c_clock_source(), I2cClockSource::Bclk);
+    }
+
+    #[test]
+    fn struct_cpu_config0_spi_clock_source() {
+        let mut config = CpuConfig0(0x0);
+        config = config.set_spi_clock_source(SpiClockSource::Xclk);
+        assert_eq!(config.0, 0x00000080);
+        assert_eq!(config.spi_clock_source(), SpiClockSource::Xclk);
+
+        config = config.set_spi_clock_source(SpiClockSource::MuxPll160M);
+        assert_eq!(config.0, 0x00000000);
+        assert_eq!(config.spi_clock_source(), SpiClockSource::MuxPll160M);
+    }
+
+    #[test]
+    fn struct_peripheral_config_spi_functions() {
+        let mut config = PeripheralConfig(0x0);
+        config = config.set_spi_clock_divide(0xff);
+        assert_eq!(config.0, 0xff000000);
+        assert_eq!(config.spi_clock_divide(), 0xff);
+
+        config = PeripheralConfig(0x0);
+        config = config.enable_spi_clock_divide();
+        assert_eq!(config.0, 0x00800000);
+        assert!(config.is_spi_clock_divide_enabled());
+        config = config.disable_spi_clock_divide();
+        assert_eq!(config.0, 0x00000000);
+        assert!(!config.is_spi_clock_divide_enabled());
+    }
+
+    #[test]
+    fn struct_peripheral_config_functions() {
+        let mut config = PeripheralConfig(0x0);
+        config = config.set_i2c_clock_divide(0xff);
+        assert_eq!(config.0, 0x000000ff);
+        assert_eq!(config.i2c_clock_divide(), 0xff);
+
+        config = PeripheralConfig(0x0);
+        config = config.enable_i2c_clock_divide();
+        assert_eq!(config.0, 0x00000100);
+        assert!(config.is_i2c_clock_divide_enabled());
+        config = config.disable_i2c_clock_divide();
+        assert_eq!(config.0, 0x00000000);
+        assert!(!config.is_i2c_clock_divide_enabled());
+
+        config = config.enable_i2c_clock();
+        assert_eq!(config.0, 0x00000200);
+        assert!(config.is_i2c_clock_enabled());
+        config = config.disable_i2c_clock();
+        assert_eq!(config.0, 0x00000000);
+        assert!(!config.is_i2c_clock_enabled());
+    }
+
     #[test]
     fn struct_cpu_config1_functions() {
         let mut config = CpuConfig1(0x0);
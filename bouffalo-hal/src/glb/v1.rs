@@ -5,31 +5,70 @@ use volatile_register::{RO, RW, WO};
 /// Global configuration registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    _reserved0: [u8; 0x100],
+    _reserved0: [u8; 0xc],
+    /// Clock configuration register 3.
+    pub clock_config_3: RW<ClockConfig3>,
+    _reserved1: [u8; 0xf0],
     /// Generic Purpose Input/Output configuration register.
     pub gpio_config: [RW<GpioConfig>; 16],
-    _reserved1: [u8; 0x40],
+    _reserved2: [u8; 0x40],
     /// Read value from Generic Purpose Input/Output pads.
     pub gpio_input_value: RO<u32>,
-    _reserved2: [u8; 0x4],
+    _reserved3: [u8; 0x4],
     /// Write value to Generic Purpose Input/Output pads.
     pub gpio_output_value: RW<u32>,
-    _reserved3: [u8; 0x4],
+    _reserved4: [u8; 0x4],
     /// Enable output function of Generic Purpose Input/Output pads.
     pub gpio_output_enable: RW<u32>,
     /// Interrupt mask of Generic Purpose Input/Output pads.
     pub gpio_interrupt_mask: RW<u32>,
-    _reserved4: [u8; 0x10],
+    _reserved5: [u8; 0x10],
     /// Interrupt state of Generic Purpose Input/Output pads.
     pub gpio_interrupt_state: RO<u32>,
-    _reserved5: [u8; 0x4],
+    _reserved6: [u8; 0x4],
     /// Clear interrupt state of Generic Purpose Input/Output pads.
     pub gpio_interrupt_clear: WO<u32>,
-    _reserved6: [u8; 0xc],
+    _reserved7: [u8; 0xc],
     /// Generic Purpose Input/Output interrupt mode register.
     pub gpio_interrupt_mode: [RW<GpioInterruptMode>; 16],
 }
 
+/// Clock configuration register 3.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ClockConfig3(u32);
+
+impl ClockConfig3 {
+    const I2C_CLOCK_DIVIDE: u32 = 0xff << 16;
+    const I2C_CLOCK_ENABLE: u32 = 1 << 24;
+
+    /// Set Inter-Integrated Circuit peripheral clock divide factor.
+    #[inline]
+    pub const fn set_i2c_clock_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::I2C_CLOCK_DIVIDE) | ((val as u32) << 16))
+    }
+    /// Get Inter-Integrated Circuit peripheral clock divide factor.
+    #[inline]
+    pub const fn i2c_clock_divide(self) -> u8 {
+        ((self.0 & Self::I2C_CLOCK_DIVIDE) >> 16) as u8
+    }
+    /// Enable clock for Inter-Integrated Circuit peripheral.
+    #[inline]
+    pub const fn enable_i2c_clock(self) -> Self {
+        Self(self.0 | Self::I2C_CLOCK_ENABLE)
+    }
+    /// Disable clock for Inter-Integrated Circuit peripheral.
+    #[inline]
+    pub const fn disable_i2c_clock(self) -> Self {
+        Self(self.0 & !Self::I2C_CLOCK_ENABLE)
+    }
+    /// Check if clock for Inter-Integrated Circuit peripheral is enabled.
+    #[inline]
+    pub const fn is_i2c_clock_enabled(self) -> bool {
+        self.0 & Self::I2C_CLOCK_ENABLE != 0
+    }
+}
+
 /// Generic Purpose Input/Output Configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -211,3 +250,39 @@ pub enum InterruptMode {
     AsyncLowLevel = 6,
     AsyncHighLevel = 7,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ClockConfig3, RegisterBlock};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, clock_config_3), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, gpio_config), 0x100);
+        assert_eq!(offset_of!(RegisterBlock, gpio_input_value), 0x180);
+        assert_eq!(offset_of!(RegisterBlock, gpio_output_value), 0x188);
+        assert_eq!(offset_of!(RegisterBlock, gpio_output_enable), 0x190);
+        assert_eq!(offset_of!(RegisterBlock, gpio_interrupt_mask), 0x194);
+        assert_eq!(offset_of!(RegisterBlock, gpio_interrupt_state), 0x1a8);
+        assert_eq!(offset_of!(RegisterBlock, gpio_interrupt_clear), 0x1b0);
+        assert_eq!(offset_of!(RegisterBlock, gpio_interrupt_mode), 0x1c0);
+    }
+
+    #[test]
+    fn struct_clock_config_3_functions() {
+        let mut config = ClockConfig3(0x0);
+        config = config.set_i2c_clock_divide(0xff);
+        assert_eq!(config.0, 0x00ff0000);
+        assert_eq!(config.i2c_clock_divide(), 0xff);
+
+        config = ClockConfig3(0x0);
+        config = config.enable_i2c_clock();
+        assert_eq!(config.0, 0x01000000);
+        assert!(config.is_i2c_clock_enabled());
+
+        config = config.disable_i2c_clock();
+        assert_eq!(config.0, 0x00000000);
+        assert!(!config.is_i2c_clock_enabled());
+    }
+}
@@ -127,7 +127,7 @@ mod pad_v1;
 mod pad_v2;
 mod typestate;
 
-pub use convert::{IntoPad, IntoPadv2};
+pub use convert::{IntoPad, IntoPadv1, IntoPadv2};
 pub use gpio_group::Pads;
 pub use typestate::*;
 pub use {alternate::Alternate, disabled::Disabled, input::Input, output::Output};
@@ -27,6 +27,10 @@ pub trait IntoPadv2<'a, const N: usize> {
     fn into_spi<const I: usize>(self) -> Alternate<'a, N, typestate::Spi<I>>;
     /// Configures the pin to operate as a SDH pin.
     fn into_sdh(self) -> Alternate<'a, N, typestate::Sdh>;
+    /// Configures the pin to operate as a Display Bus Interface type B pin.
+    fn into_dbi_b(self) -> Alternate<'a, N, typestate::DbiB>;
+    /// Configures the pin to operate as a Display Bus Interface type C pin.
+    fn into_dbi_c(self) -> Alternate<'a, N, typestate::DbiC>;
     /// Configures the pin to operate as UART signal.
     fn into_uart(self) -> Alternate<'a, N, typestate::Uart>;
     /// Configures the pin to operate as multi-media cluster UART signal.
@@ -46,3 +50,9 @@ pub trait IntoPadv2<'a, const N: usize> {
     /// Configures the pin to operate as LP core JTAG.
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp>;
 }
+
+/// Trait for GLBv1 pad mode conversations.
+pub trait IntoPadv1<'a, const N: usize> {
+    /// Configures the pin to operate as an Inter-Integrated Circuit signal pin.
+    fn into_i2c<const I: usize>(self) -> Alternate<'a, N, typestate::I2c<I>>;
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "glb-v1")]
+use super::{alternate::Alternate, convert::IntoPadv1};
 #[cfg(any(doc, feature = "glb-v2"))]
 use super::{alternate::Alternate, convert::IntoPadv2};
 use super::{
@@ -39,6 +41,14 @@ impl<'a, const N: usize> IntoPad<'a, N> for Disabled<'a, N> {
     }
 }
 
+#[cfg(feature = "glb-v1")]
+impl<'a, const N: usize> IntoPadv1<'a, N> for Disabled<'a, N> {
+    #[inline]
+    fn into_i2c<const I: usize>(self) -> Alternate<'a, N, typestate::I2c<I>> {
+        self.inner.into_i2c().into()
+    }
+}
+
 #[cfg(any(doc, feature = "glb-v2"))]
 impl<'a, const N: usize> IntoPadv2<'a, N> for Disabled<'a, N> {
     #[inline]
@@ -50,6 +60,14 @@ impl<'a, const N: usize> IntoPadv2<'a, N> for Disabled<'a, N> {
         self.inner.into_sdh().into()
     }
     #[inline]
+    fn into_dbi_b(self) -> Alternate<'a, N, typestate::DbiB> {
+        self.inner.into_dbi_b().into()
+    }
+    #[inline]
+    fn into_dbi_c(self) -> Alternate<'a, N, typestate::DbiC> {
+        self.inner.into_dbi_c().into()
+    }
+    #[inline]
     fn into_uart(self) -> Alternate<'a, N, typestate::Uart> {
         self.inner.into_uart().into()
     }
@@ -1,4 +1,4 @@
-use super::typestate::{Floating, Input, Output, PullDown, PullUp};
+use super::typestate::{Floating, I2c, Input, Output, PullDown, PullUp};
 use crate::glb::{Drive, Pull, v1};
 use core::marker::PhantomData;
 use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
@@ -179,6 +179,25 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
     }
 }
 
+impl<'a, const N: usize, M> Padv1<'a, N, M> {
+    /// Configures the pin to operate as an Inter-Integrated Circuit signal pin.
+    #[inline]
+    pub fn into_i2c<const I: usize>(self) -> Padv1<'a, N, I2c<I>> {
+        let config = self.base.gpio_config[N >> 1]
+            .read()
+            .set_function(N & 0x1, I2c::<I>::FUNCTION_V1)
+            .enable_input(N & 0x1)
+            .enable_schmitt(N & 0x1)
+            .set_drive(N & 0x1, Drive::Drive0)
+            .set_pull(N & 0x1, Pull::Up);
+        unsafe { self.base.gpio_config[N >> 1].write(config) };
+        Padv1 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+}
+
 impl<'a, const N: usize, M> ErrorType for Padv1<'a, N, Input<M>> {
     type Error = core::convert::Infallible;
 }
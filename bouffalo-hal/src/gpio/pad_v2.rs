@@ -1,8 +1,8 @@
 use super::{
     Spi,
     typestate::{
-        Floating, I2c, Input, JtagD0, JtagLp, JtagM0, MmUart, Output, PullDown, PullUp, Pwm, Sdh,
-        Uart,
+        DbiB, DbiC, Floating, I2c, Input, JtagD0, JtagLp, JtagM0, MmUart, Output, PullDown, PullUp,
+        Pwm, Sdh, Uart,
     },
 };
 use crate::glb::{Drive, Pull, v2};
@@ -343,6 +343,44 @@ impl<'a, const N: usize, M> Padv2<'a, N, M> {
             self.base.gpio_config[N].write(config);
         }
 
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as a Display Bus Interface type B pin.
+    #[inline]
+    pub fn into_dbi_b(self) -> Padv2<'a, N, DbiB> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .disable_output()
+            .enable_schmitt()
+            .set_pull(Pull::Up)
+            .set_drive(Drive::Drive0)
+            .set_function(v2::Function::DbiB);
+        unsafe {
+            self.base.gpio_config[N].write(config);
+        }
+
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as a Display Bus Interface type C pin.
+    #[inline]
+    pub fn into_dbi_c(self) -> Padv2<'a, N, DbiC> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .disable_output()
+            .enable_schmitt()
+            .set_pull(Pull::Up)
+            .set_drive(Drive::Drive0)
+            .set_function(v2::Function::DbiC);
+        unsafe {
+            self.base.gpio_config[N].write(config);
+        }
+
         Padv2 {
             base: self.base,
             _mode: PhantomData,
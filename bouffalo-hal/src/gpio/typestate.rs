@@ -1,4 +1,4 @@
-use crate::glb::v2;
+use crate::glb::{v1, v2};
 use core::marker::PhantomData;
 
 /// Input mode (type state).
@@ -53,6 +53,12 @@ impl<const F: usize> Spi<F> {
 /// SD Host mode (type state).
 pub struct Sdh;
 
+/// Display Bus Interface type B mode (type state).
+pub struct DbiB;
+
+/// Display Bus Interface type C mode (type state).
+pub struct DbiC;
+
 /// Inter-Integrated Circuit mode (type state).
 pub struct I2c<const F: usize>;
 
@@ -65,6 +71,11 @@ impl<const F: usize> I2c<F> {
         3 => v2::Function::I2c3,
         _ => unreachable!(),
     };
+    /// I2C function constant in GLB v1 peripheral.
+    pub const FUNCTION_V1: v1::Function = match F {
+        0 => v1::Function::I2c,
+        _ => unreachable!(),
+    };
 }
 
 /// Pulse Width Modulation signal mode (type state).
@@ -4,6 +4,8 @@ use core::ops::Deref;
 
 use volatile_register::RW;
 
+use crate::dma::{Endpoint, PeripheralId};
+
 /// Generic DAC, ADC and ACOMP interface control peripheral registers.
 #[repr(C)]
 pub struct RegisterBlock {
@@ -42,10 +44,204 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct GpadcConfig(u32);
 
+impl GpadcConfig {
+    const DMA_ENABLE: u32 = 1 << 0;
+    const FIFO_CLEAR: u32 = 1 << 1;
+    const FIFO_NOT_EMPTY: u32 = 1 << 2;
+    const FIFO_FULL: u32 = 1 << 3;
+    const READY: u32 = 1 << 4;
+    const FIFO_OVERRUN: u32 = 1 << 5;
+    const FIFO_UNDERRUN: u32 = 1 << 6;
+    const READY_CLEAR: u32 = 1 << 8;
+    const FIFO_OVERRUN_CLEAR: u32 = 1 << 9;
+    const FIFO_UNDERRUN_CLEAR: u32 = 1 << 10;
+    const READY_MASK: u32 = 1 << 12;
+    const FIFO_OVERRUN_MASK: u32 = 1 << 13;
+    const FIFO_UNDERRUN_MASK: u32 = 1 << 14;
+    const FIFO_DATA_COUNT: u32 = 0x3f << 16;
+    const FIFO_THRESHOLD: u32 = 0x3 << 22;
+
+    /// Enable DMA transfer of conversion results.
+    #[inline]
+    pub const fn enable_dma(self) -> Self {
+        Self(self.0 | Self::DMA_ENABLE)
+    }
+    /// Disable DMA transfer of conversion results.
+    #[inline]
+    pub const fn disable_dma(self) -> Self {
+        Self(self.0 & !Self::DMA_ENABLE)
+    }
+    /// Check if DMA transfer of conversion results is enabled.
+    #[inline]
+    pub const fn is_dma_enabled(self) -> bool {
+        self.0 & Self::DMA_ENABLE != 0
+    }
+    /// Clear result FIFO.
+    #[inline]
+    pub const fn clear_fifo(self) -> Self {
+        Self(self.0 | Self::FIFO_CLEAR)
+    }
+    /// Check if result FIFO is not empty.
+    #[inline]
+    pub const fn is_fifo_not_empty(self) -> bool {
+        self.0 & Self::FIFO_NOT_EMPTY != 0
+    }
+    /// Check if result FIFO is full.
+    #[inline]
+    pub const fn is_fifo_full(self) -> bool {
+        self.0 & Self::FIFO_FULL != 0
+    }
+    /// Check if conversion result is ready.
+    #[inline]
+    pub const fn is_ready(self) -> bool {
+        self.0 & Self::READY != 0
+    }
+    /// Check if result FIFO has overrun.
+    #[inline]
+    pub const fn is_fifo_overrun(self) -> bool {
+        self.0 & Self::FIFO_OVERRUN != 0
+    }
+    /// Check if result FIFO has underrun.
+    #[inline]
+    pub const fn is_fifo_underrun(self) -> bool {
+        self.0 & Self::FIFO_UNDERRUN != 0
+    }
+    /// Clear result ready flag.
+    #[inline]
+    pub const fn clear_ready(self) -> Self {
+        Self(self.0 | Self::READY_CLEAR)
+    }
+    /// Clear result FIFO overrun flag.
+    #[inline]
+    pub const fn clear_fifo_overrun(self) -> Self {
+        Self(self.0 | Self::FIFO_OVERRUN_CLEAR)
+    }
+    /// Clear result FIFO underrun flag.
+    #[inline]
+    pub const fn clear_fifo_underrun(self) -> Self {
+        Self(self.0 | Self::FIFO_UNDERRUN_CLEAR)
+    }
+    /// Release all clear bits.
+    ///
+    /// Clear bits hold FIFO and flags cleared until released.
+    #[inline]
+    pub const fn release_clear(self) -> Self {
+        Self(
+            self.0
+                & !(Self::FIFO_CLEAR
+                    | Self::READY_CLEAR
+                    | Self::FIFO_OVERRUN_CLEAR
+                    | Self::FIFO_UNDERRUN_CLEAR),
+        )
+    }
+    /// Mask result ready interrupt.
+    #[inline]
+    pub const fn mask_ready(self) -> Self {
+        Self(self.0 | Self::READY_MASK)
+    }
+    /// Unmask result ready interrupt.
+    #[inline]
+    pub const fn unmask_ready(self) -> Self {
+        Self(self.0 & !Self::READY_MASK)
+    }
+    /// Check if result ready interrupt is masked.
+    #[inline]
+    pub const fn is_ready_masked(self) -> bool {
+        self.0 & Self::READY_MASK != 0
+    }
+    /// Mask result FIFO overrun interrupt.
+    #[inline]
+    pub const fn mask_fifo_overrun(self) -> Self {
+        Self(self.0 | Self::FIFO_OVERRUN_MASK)
+    }
+    /// Unmask result FIFO overrun interrupt.
+    #[inline]
+    pub const fn unmask_fifo_overrun(self) -> Self {
+        Self(self.0 & !Self::FIFO_OVERRUN_MASK)
+    }
+    /// Check if result FIFO overrun interrupt is masked.
+    #[inline]
+    pub const fn is_fifo_overrun_masked(self) -> bool {
+        self.0 & Self::FIFO_OVERRUN_MASK != 0
+    }
+    /// Mask result FIFO underrun interrupt.
+    #[inline]
+    pub const fn mask_fifo_underrun(self) -> Self {
+        Self(self.0 | Self::FIFO_UNDERRUN_MASK)
+    }
+    /// Unmask result FIFO underrun interrupt.
+    #[inline]
+    pub const fn unmask_fifo_underrun(self) -> Self {
+        Self(self.0 & !Self::FIFO_UNDERRUN_MASK)
+    }
+    /// Check if result FIFO underrun interrupt is masked.
+    #[inline]
+    pub const fn is_fifo_underrun_masked(self) -> bool {
+        self.0 & Self::FIFO_UNDERRUN_MASK != 0
+    }
+    /// Get number of results in FIFO.
+    #[inline]
+    pub const fn fifo_data_count(self) -> u8 {
+        ((self.0 & Self::FIFO_DATA_COUNT) >> 16) as u8
+    }
+    /// Set FIFO threshold for result ready and DMA request.
+    #[inline]
+    pub const fn set_fifo_threshold(self, val: FifoThreshold) -> Self {
+        Self((self.0 & !Self::FIFO_THRESHOLD) | ((val as u32) << 22))
+    }
+    /// Get FIFO threshold for result ready and DMA request.
+    #[inline]
+    pub const fn fifo_threshold(self) -> FifoThreshold {
+        match (self.0 & Self::FIFO_THRESHOLD) >> 22 {
+            0 => FifoThreshold::One,
+            1 => FifoThreshold::Four,
+            2 => FifoThreshold::Eight,
+            _ => FifoThreshold::Sixteen,
+        }
+    }
+}
+
+/// Result FIFO threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum FifoThreshold {
+    /// At least 1 result.
+    One = 0,
+    /// At least 4 results.
+    Four = 1,
+    /// At least 8 results.
+    Eight = 2,
+    /// At least 16 results.
+    Sixteen = 3,
+}
+
+/// Conversion result read register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcDmaRdata(u32);
 
+impl GpadcDmaRdata {
+    const VALUE: u32 = 0xffff;
+    const NEGATIVE_CHANNEL: u32 = 0x1f << 16;
+    const POSITIVE_CHANNEL: u32 = 0x1f << 21;
+
+    /// Get raw conversion value.
+    #[inline]
+    pub const fn value(self) -> u16 {
+        (self.0 & Self::VALUE) as u16
+    }
+    /// Get negative input channel of this result.
+    #[inline]
+    pub const fn negative_channel(self) -> u8 {
+        ((self.0 & Self::NEGATIVE_CHANNEL) >> 16) as u8
+    }
+    /// Get positive input channel of this result.
+    #[inline]
+    pub const fn positive_channel(self) -> u8 {
+        ((self.0 & Self::POSITIVE_CHANNEL) >> 21) as u8
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcPirTrain(u32);
@@ -56,6 +252,7 @@ pub struct GpadcCommand(u32);
 
 impl GpadcCommand {
     const GLOBAL_ENABLE: u32 = 1 << 0;
+    const CONVERSION_START: u32 = 1 << 1;
     const SOFTWARE_RESET: u32 = 1 << 2;
 
     /// Enable the Analog-to-Digital Converter.
@@ -73,6 +270,21 @@ impl GpadcCommand {
     pub const fn is_global_enabled(self) -> bool {
         self.0 & Self::GLOBAL_ENABLE != 0
     }
+    /// Start conversion.
+    #[inline]
+    pub const fn start_conversion(self) -> Self {
+        Self(self.0 | Self::CONVERSION_START)
+    }
+    /// Stop conversion.
+    #[inline]
+    pub const fn stop_conversion(self) -> Self {
+        Self(self.0 & !Self::CONVERSION_START)
+    }
+    /// Check if conversion is started.
+    #[inline]
+    pub const fn is_conversion_started(self) -> bool {
+        self.0 & Self::CONVERSION_START != 0
+    }
     /// Enable the ADC software reset signal.
     #[inline]
     pub const fn enable_software_reset(self) -> Self {
@@ -90,10 +302,31 @@ impl GpadcCommand {
     }
 }
 
+/// Generic Analog-to-Digital Converter configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcConfig1(u32);
 
+impl GpadcConfig1 {
+    const PWM_TRIGGER_ENABLE: u32 = 1 << 11;
+
+    /// Enable conversions triggered by PWM.
+    #[inline]
+    pub const fn enable_pwm_trigger(self) -> Self {
+        Self(self.0 | Self::PWM_TRIGGER_ENABLE)
+    }
+    /// Disable conversions triggered by PWM.
+    #[inline]
+    pub const fn disable_pwm_trigger(self) -> Self {
+        Self(self.0 & !Self::PWM_TRIGGER_ENABLE)
+    }
+    /// Check if conversions triggered by PWM are enabled.
+    #[inline]
+    pub const fn is_pwm_trigger_enabled(self) -> bool {
+        self.0 & Self::PWM_TRIGGER_ENABLE != 0
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcConfig2(u32);
@@ -181,6 +414,94 @@ impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
         Self { adc }
     }
 
+    /// Clear conversion results in FIFO and set its ready threshold.
+    ///
+    /// Results are read with `read_sample`; pending overrun and underrun flags are cleared.
+    #[inline]
+    pub fn configure_fifo(&mut self, threshold: FifoThreshold) {
+        self.pulse_clear(|v| {
+            v.clear_fifo()
+                .clear_fifo_overrun()
+                .clear_fifo_underrun()
+                .set_fifo_threshold(threshold)
+        })
+    }
+    /// Start conversions on triggers from PWM groups, see `Channels::set_adc_trigger`.
+    #[inline]
+    pub fn enable_pwm_trigger(&mut self) {
+        unsafe { self.adc.gpadc_config_1.modify(|v| v.enable_pwm_trigger()) }
+    }
+    /// Stop starting conversions on triggers from PWM groups.
+    #[inline]
+    pub fn disable_pwm_trigger(&mut self) {
+        unsafe { self.adc.gpadc_config_1.modify(|v| v.disable_pwm_trigger()) }
+    }
+    /// Hand over conversion results in FIFO to DMA.
+    ///
+    /// `request` must be the GPADC request of the DMA controller. Each result is moved as one
+    /// word laid out as `GpadcDmaRdata`, so transfers should use word width.
+    #[inline]
+    pub fn enable_dma<T: PeripheralId>(&mut self, request: T) -> Endpoint<T> {
+        unsafe { self.adc.gpadc_config.modify(|v| v.enable_dma()) };
+        Endpoint::from_register(request, &self.adc.gpadc_dma_rdata)
+    }
+    /// Stop handing over conversion results to DMA.
+    #[inline]
+    pub fn disable_dma(&mut self) {
+        unsafe { self.adc.gpadc_config.modify(|v| v.disable_dma()) }
+    }
+    /// Get number of conversion results waiting in FIFO.
+    #[inline]
+    pub fn fifo_count(&self) -> u8 {
+        self.adc.gpadc_config.read().fifo_data_count()
+    }
+    /// Read one conversion result from FIFO, if any.
+    ///
+    /// Returns `Err(Error::Overrun)` once when results were dropped since the last read.
+    #[inline]
+    pub fn read_sample(&mut self) -> nb::Result<Sample, Error> {
+        let config = self.adc.gpadc_config.read();
+        if config.is_fifo_overrun() {
+            self.pulse_clear(|v| v.clear_fifo_overrun());
+            return Err(nb::Error::Other(Error::Overrun));
+        }
+        if !config.is_fifo_not_empty() {
+            return Err(nb::Error::WouldBlock);
+        }
+        let data = self.adc.gpadc_dma_rdata.read();
+        Ok(Sample {
+            positive_channel: data.positive_channel(),
+            negative_channel: data.negative_channel(),
+            value: data.value(),
+        })
+    }
+    /// Check if results in FIFO reached threshold.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.adc.gpadc_config.read().is_ready()
+    }
+    /// Enable or disable interrupt when results in FIFO reach threshold.
+    #[inline]
+    pub fn set_ready_interrupt(&mut self, enable: bool) {
+        match enable {
+            true => self.pulse_clear(|v| v.clear_ready().unmask_ready()),
+            false => unsafe { self.adc.gpadc_config.modify(|v| v.mask_ready()) },
+        }
+    }
+    /// Clear result ready flag.
+    #[inline]
+    pub fn clear_ready(&mut self) {
+        self.pulse_clear(|v| v.clear_ready())
+    }
+    /// Apply `f` setting clear bits, then release them so flags are raised again.
+    #[inline]
+    fn pulse_clear(&mut self, f: impl FnOnce(GpadcConfig) -> GpadcConfig) {
+        unsafe {
+            self.adc.gpadc_config.modify(f);
+            self.adc.gpadc_config.modify(|v| v.release_clear());
+        }
+    }
+
     #[inline]
     pub fn free(self) -> ADC {
         unsafe {
@@ -190,10 +511,53 @@ impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
     }
 }
 
+/// Conversion result with its input channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Sample {
+    /// Positive input channel.
+    pub positive_channel: u8,
+    /// Negative input channel.
+    pub negative_channel: u8,
+    /// Raw conversion value.
+    pub value: u16,
+}
+
+/// Analog-to-Digital Converter error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Result FIFO overrun, conversion results were lost.
+    Overrun,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
-    use core::mem::offset_of;
+    use super::{
+        Adc, Error, FifoThreshold, GpadcCommand, GpadcConfig, GpadcConfig1, GpadcDmaRdata,
+        RegisterBlock, Sample,
+    };
+    use core::{cell::UnsafeCell, mem::offset_of, ops::Deref};
+
+    /// Register block in memory.
+    struct MockAdc(UnsafeCell<[u32; 0x940 / 4]>);
+
+    impl MockAdc {
+        fn new(gpadc_config: u32, gpadc_dma_rdata: u32) -> Adc<MockAdc> {
+            let mut regs = [0u32; 0x940 / 4];
+            regs[0] = gpadc_config;
+            regs[1] = gpadc_dma_rdata;
+            Adc {
+                adc: MockAdc(UnsafeCell::new(regs)),
+            }
+        }
+    }
+
+    impl Deref for MockAdc {
+        type Target = RegisterBlock;
+
+        fn deref(&self) -> &RegisterBlock {
+            unsafe { &*(self.0.get() as *const RegisterBlock) }
+        }
+    }
 
     #[test]
     fn struct_gpadc_config_functions() {
@@ -221,4 +585,134 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, gpadc_raw_result), 0x934);
         assert_eq!(offset_of!(RegisterBlock, gpadc_define), 0x938);
     }
+
+    #[test]
+    fn struct_gpadc_config_bit_functions() {
+        let mut val = GpadcConfig(0x0);
+        val = val.enable_dma();
+        assert!(val.is_dma_enabled());
+        assert_eq!(val.0, 0x00000001);
+        val = val.disable_dma();
+        assert!(!val.is_dma_enabled());
+        assert_eq!(val.0, 0x00000000);
+
+        assert_eq!(val.clear_fifo().0, 0x00000002);
+        assert_eq!(val.clear_ready().0, 0x00000100);
+        assert_eq!(val.clear_fifo_overrun().0, 0x00000200);
+        assert_eq!(val.clear_fifo_underrun().0, 0x00000400);
+        assert_eq!(GpadcConfig(0xffff_ffff).release_clear().0, 0xffff_f8fd);
+
+        val = val.mask_ready().mask_fifo_overrun().mask_fifo_underrun();
+        assert!(val.is_ready_masked());
+        assert!(val.is_fifo_overrun_masked());
+        assert!(val.is_fifo_underrun_masked());
+        assert_eq!(val.0, 0x00007000);
+        val = val
+            .unmask_ready()
+            .unmask_fifo_overrun()
+            .unmask_fifo_underrun();
+        assert!(!val.is_ready_masked());
+        assert!(!val.is_fifo_overrun_masked());
+        assert!(!val.is_fifo_underrun_masked());
+        assert_eq!(val.0, 0x00000000);
+
+        for (threshold, bits) in [
+            (FifoThreshold::One, 0x00000000),
+            (FifoThreshold::Four, 0x00400000),
+            (FifoThreshold::Eight, 0x00800000),
+            (FifoThreshold::Sixteen, 0x00c00000),
+        ] {
+            val = val.set_fifo_threshold(threshold);
+            assert_eq!(val.fifo_threshold(), threshold);
+            assert_eq!(val.0, bits);
+        }
+
+        let val = GpadcConfig(0x0025007c);
+        assert!(val.is_fifo_not_empty());
+        assert!(val.is_fifo_full());
+        assert!(val.is_ready());
+        assert!(val.is_fifo_overrun());
+        assert!(val.is_fifo_underrun());
+        assert_eq!(val.fifo_data_count(), 0x25);
+        assert_eq!(val.fifo_threshold(), FifoThreshold::One);
+    }
+
+    #[test]
+    fn struct_gpadc_dma_rdata_functions() {
+        let val = GpadcDmaRdata(0x0169_8abc);
+        assert_eq!(val.positive_channel(), 0x0b);
+        assert_eq!(val.negative_channel(), 0x09);
+        assert_eq!(val.value(), 0x8abc);
+    }
+
+    #[test]
+    fn struct_gpadc_config_1_functions() {
+        let mut val = GpadcConfig1(0x0);
+        val = val.enable_pwm_trigger();
+        assert!(val.is_pwm_trigger_enabled());
+        assert_eq!(val.0, 0x00000800);
+        val = val.disable_pwm_trigger();
+        assert!(!val.is_pwm_trigger_enabled());
+        assert_eq!(val.0, 0x00000000);
+    }
+
+    #[test]
+    fn struct_gpadc_command_functions() {
+        let mut val = GpadcCommand(0x0);
+        val = val.start_conversion();
+        assert!(val.is_conversion_started());
+        assert_eq!(val.0, 0x00000002);
+        val = val.stop_conversion();
+        assert!(!val.is_conversion_started());
+        assert_eq!(val.0, 0x00000000);
+    }
+
+    #[test]
+    fn function_adc_read_sample() {
+        let mut adc = MockAdc::new(0, 0x0169_8abc);
+        assert_eq!(adc.read_sample(), Err(nb::Error::WouldBlock));
+
+        unsafe { adc.adc.gpadc_config.write(GpadcConfig(1 << 2 | 1 << 16)) };
+        assert_eq!(adc.fifo_count(), 1);
+        assert_eq!(
+            adc.read_sample(),
+            Ok(Sample {
+                positive_channel: 0x0b,
+                negative_channel: 0x09,
+                value: 0x8abc,
+            })
+        );
+    }
+
+    #[test]
+    fn function_adc_read_sample_overrun() {
+        let mut adc = MockAdc::new(1 << 2 | 1 << 5, 0x0169_8abc);
+        // Overrun is reported before remaining results.
+        assert_eq!(adc.read_sample(), Err(nb::Error::Other(Error::Overrun)));
+        let config = adc.adc.gpadc_config.read();
+        assert_eq!(config.0 & (1 << 9), 0);
+        assert!(config.is_fifo_not_empty());
+
+        // Hardware drops the flag on the clear pulse.
+        unsafe {
+            adc.adc
+                .gpadc_config
+                .modify(|v| GpadcConfig(v.0 & !(1 << 5)))
+        };
+        assert_eq!(adc.read_sample().map(|s| s.value), Ok(0x8abc));
+    }
+
+    #[test]
+    fn function_adc_clear_bits_released() {
+        let mut adc = MockAdc::new(0, 0);
+        adc.configure_fifo(FifoThreshold::Eight);
+        adc.set_ready_interrupt(true);
+        adc.clear_ready();
+        let config = adc.adc.gpadc_config.read();
+        assert_eq!(config.0 & 0x0702, 0);
+        assert_eq!(config.fifo_threshold(), FifoThreshold::Eight);
+        assert!(!config.is_ready_masked());
+        adc.set_ready_interrupt(false);
+        assert!(adc.adc.gpadc_config.read().is_ready_masked());
+    }
 }
@@ -1,17 +1,36 @@
 //! Inter-Integrated Circuit bus.
-use core::ops::Deref;
+use as_slice::{AsMutSlice, AsSlice};
+use core::{
+    future::poll_fn,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicUsize, Ordering, compiler_fence},
+    task::Poll,
+};
+use embedded_hal::i2c::{NoAcknowledgeSource, Operation};
 
+#[cfg(not(feature = "glb-v1"))]
+use crate::glb::v2::I2cClockSource;
+#[cfg(any(doc, feature = "glb-v2"))]
+use crate::gpio::{IntoPad, IntoPadv2};
 use crate::{
-    glb::{self, v2::I2cClockSource},
+    clocks::Clocks,
+    dma::{
+        BurstSize, DmaChannelConfig, DmaMode, LliPool, PeripheralId, TransferWidth, TypedChannel,
+        clean_dcache, flush_dcache, invalidate_dcache,
+    },
+    glb,
     gpio::{self, Alternate},
 };
+#[cfg(any(doc, feature = "glb-v2"))]
+use embedded_hal::delay::DelayNs;
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
 /// Inter-integrated circuit registers.
 #[repr(C)]
 pub struct RegisterBlock {
     /// Function configuration register.
-    pub config: RW<Config>,
+    pub config: RW<FunctionConfig>,
     /// Interrupt state register.
     pub interrupt_state: RO<InterruptState>,
     /// Interrupt mask register.
@@ -44,9 +63,9 @@ pub struct RegisterBlock {
 /// Function configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
-pub struct Config(u32);
+pub struct FunctionConfig(u32);
 
-impl Config {
+impl FunctionConfig {
     const MASTER_ENABLE: u32 = 1 << 0;
     const PACKET_DIRECTION: u32 = 1 << 1;
     const DEGLITCH_ENABLE: u32 = 1 << 2;
@@ -500,54 +519,33 @@ pub struct I2c<I2C, PADS> {
     pads: PADS,
 }
 
+#[cfg(not(feature = "glb-v1"))]
 impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
     /// Create a new Inter-Integrated Circuit instance.
     #[inline]
-    pub fn new<const I: usize>(i2c: I2C, pads: (SCL, SDA), glb: &glb::v2::RegisterBlock) -> Self
+    pub fn new<const I: usize>(
+        i2c: I2C,
+        pads: (SCL, SDA),
+        config: Config,
+        glb: &glb::v2::RegisterBlock,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
     where
         SCL: SclPin<I>,
         SDA: SdaPin<I>,
     {
-        // TODO: support custom clock and frequency
-        // Enable clock
+        // Peripheral clock is divided from crystal oscillator clock.
+        let (divide, phases) = i2c_timing(clocks.xclk(), config.frequency)?;
         unsafe {
             glb.i2c_config.modify(|config| {
                 config
                     .enable_clock()
                     .set_clock_source(I2cClockSource::Xclk)
-                    .set_clock_divide(0xff)
+                    .set_clock_divide(divide)
             });
             glb.clock_config_1.modify(|config| config.enable_i2c());
-            i2c.period_start.write(
-                PeriodStart(0)
-                    .set_phase(0, 0xff)
-                    .set_phase(1, 0xff)
-                    .set_phase(2, 0xff)
-                    .set_phase(3, 0xff),
-            );
-            i2c.period_stop.write(
-                PeriodStop(0)
-                    .set_phase(0, 0xff)
-                    .set_phase(1, 0xff)
-                    .set_phase(2, 0xff)
-                    .set_phase(3, 0xff),
-            );
-            i2c.period_data.write(
-                PeriodData(0)
-                    .set_phase(0, 0xff)
-                    .set_phase(1, 0xff)
-                    .set_phase(2, 0xff)
-                    .set_phase(3, 0xff),
-            );
-            i2c.config.write(
-                Config(0)
-                    .disable_ten_bit_address()
-                    .disable_scl_sync()
-                    .disable_sub_address(),
-            );
         }
-
-        Self { i2c, pads }
+        Ok(Self::from_parts(i2c, pads, phases))
     }
 
     /// Release the I2C instance and return the pads.
@@ -560,6 +558,190 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
         (self.i2c, self.pads)
     }
 
+    /// Change bus frequency, reprogramming peripheral clock divide factor and bit phases.
+    #[inline]
+    pub fn set_frequency(
+        &mut self,
+        frequency: Hertz,
+        glb: &glb::v2::RegisterBlock,
+        clocks: &Clocks,
+    ) -> Result<(), ConfigError> {
+        let (divide, phases) = i2c_timing(clocks.xclk(), frequency)?;
+        unsafe {
+            glb.i2c_config
+                .modify(|config| config.set_clock_divide(divide))
+        };
+        self.write_phases(phases);
+        Ok(())
+    }
+
+    /// Create a new Inter-Integrated Circuit instance on multi-media subsystem.
+    ///
+    /// I2C2 and I2C3 of BL808 are clocked by multi-media global peripheral, which allows
+    /// using them from the DSP core without touching the MCU global configurations. Other
+    /// instances are rejected at compile time.
+    #[inline]
+    pub fn new_mm<const I: usize>(
+        i2c: I2C,
+        pads: (SCL, SDA),
+        config: Config,
+        mm_glb: &glb::mm::RegisterBlock,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
+    where
+        SCL: SclPin<I>,
+        SDA: SdaPin<I>,
+        gpio::I2c<I>: MmInstance,
+    {
+        // Peripheral clock is divided from crystal oscillator clock.
+        let (divide, phases) = i2c_timing(clocks.xclk(), config.frequency)?;
+        unsafe {
+            mm_glb
+                .cpu_config_0
+                .modify(|config| config.set_i2c_clock_source(glb::mm::I2cClockSource::Xclk));
+            gpio::I2c::<I>::clock_config(mm_glb).modify(|config| {
+                config
+                    .set_i2c_clock_divide(divide)
+                    .enable_i2c_clock_divide()
+                    .enable_i2c_clock()
+            });
+        }
+        Ok(Self::from_parts(i2c, pads, phases))
+    }
+
+    /// Release the multi-media I2C instance and return the pads.
+    #[inline]
+    pub fn free_mm<const I: usize>(self, mm_glb: &glb::mm::RegisterBlock) -> (I2C, (SCL, SDA))
+    where
+        SCL: SclPin<I>,
+        SDA: SdaPin<I>,
+        gpio::I2c<I>: MmInstance,
+    {
+        unsafe {
+            gpio::I2c::<I>::clock_config(mm_glb).modify(|config| config.disable_i2c_clock());
+        }
+        (self.i2c, self.pads)
+    }
+
+    /// Change bus frequency of multi-media I2C instance.
+    #[inline]
+    pub fn set_frequency_mm<const I: usize>(
+        &mut self,
+        frequency: Hertz,
+        mm_glb: &glb::mm::RegisterBlock,
+        clocks: &Clocks,
+    ) -> Result<(), ConfigError>
+    where
+        SCL: SclPin<I>,
+        SDA: SdaPin<I>,
+        gpio::I2c<I>: MmInstance,
+    {
+        let (divide, phases) = i2c_timing(clocks.xclk(), frequency)?;
+        unsafe {
+            gpio::I2c::<I>::clock_config(mm_glb)
+                .modify(|config| config.set_i2c_clock_divide(divide));
+        }
+        self.write_phases(phases);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "glb-v1")]
+impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
+    /// Create a new Inter-Integrated Circuit instance.
+    #[inline]
+    pub fn new<const I: usize>(
+        i2c: I2C,
+        pads: (SCL, SDA),
+        config: Config,
+        glb: &glb::v1::RegisterBlock,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
+    where
+        SCL: SclPin<I>,
+        SDA: SdaPin<I>,
+    {
+        // Peripheral clock is divided from bus clock.
+        let (divide, phases) = i2c_timing(clocks.bclk(), config.frequency)?;
+        unsafe {
+            glb.clock_config_3
+                .modify(|config| config.set_i2c_clock_divide(divide).enable_i2c_clock());
+        }
+        Ok(Self::from_parts(i2c, pads, phases))
+    }
+
+    /// Release the I2C instance and return the pads.
+    #[inline]
+    pub fn free(self, glb: &glb::v1::RegisterBlock) -> (I2C, (SCL, SDA)) {
+        unsafe {
+            glb.clock_config_3
+                .modify(|config| config.disable_i2c_clock());
+        }
+        (self.i2c, self.pads)
+    }
+
+    /// Change bus frequency, reprogramming peripheral clock divide factor and bit phases.
+    #[inline]
+    pub fn set_frequency(
+        &mut self,
+        frequency: Hertz,
+        glb: &glb::v1::RegisterBlock,
+        clocks: &Clocks,
+    ) -> Result<(), ConfigError> {
+        let (divide, phases) = i2c_timing(clocks.bclk(), frequency)?;
+        unsafe {
+            glb.clock_config_3
+                .modify(|config| config.set_i2c_clock_divide(divide))
+        };
+        self.write_phases(phases);
+        Ok(())
+    }
+}
+
+impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
+    #[inline]
+    fn from_parts(i2c: I2C, pads: (SCL, SDA), phases: [u8; 4]) -> Self {
+        let i2c = Self { i2c, pads };
+        i2c.write_phases(phases);
+        unsafe {
+            i2c.i2c.config.write(
+                FunctionConfig(0)
+                    .disable_ten_bit_address()
+                    .disable_scl_sync()
+                    .disable_sub_address(),
+            );
+        }
+        i2c
+    }
+
+    #[inline]
+    fn write_phases(&self, phases: [u8; 4]) {
+        let [p0, p1, p2, p3] = phases;
+        unsafe {
+            self.i2c.period_start.write(
+                PeriodStart(0)
+                    .set_phase(0, p0)
+                    .set_phase(1, p1)
+                    .set_phase(2, p2)
+                    .set_phase(3, p3),
+            );
+            self.i2c.period_stop.write(
+                PeriodStop(0)
+                    .set_phase(0, p0)
+                    .set_phase(1, p1)
+                    .set_phase(2, p2)
+                    .set_phase(3, p3),
+            );
+            self.i2c.period_data.write(
+                PeriodData(0)
+                    .set_phase(0, p0)
+                    .set_phase(1, p1)
+                    .set_phase(2, p2)
+                    .set_phase(3, p3),
+            );
+        }
+    }
+
     /// Enable sub-address.
     #[inline]
     pub fn enable_sub_address(&mut self, sub_address: u8) {
@@ -585,10 +767,143 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
     }
 }
 
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, 'b, I2C: Deref<Target = RegisterBlock>, const I: usize, const N1: usize, const N2: usize>
+    I2c<
+        I2C,
+        (
+            Alternate<'a, N1, gpio::I2c<I>>,
+            Alternate<'b, N2, gpio::I2c<I>>,
+        ),
+    >
+{
+    /// Recover the bus from a slave holding SDA low.
+    ///
+    /// Clocks out up to 9 SCL pulses in standard mode speed until the slave releases SDA,
+    /// then generates a stop condition. Returns `Error::Bus` if SDA is still held low.
+    #[inline]
+    pub fn recover_bus(self, delay: &mut impl DelayNs) -> (Self, Result<(), Error>) {
+        use embedded_hal::digital::{InputPin, OutputPin};
+        let Self {
+            i2c,
+            pads: (scl, sda),
+        } = self;
+        i2c_abort(&i2c);
+        let mut scl = scl.into_pull_up_output();
+        let mut sda = sda.into_pull_up_input();
+        for _ in 0..9 {
+            if sda.is_high().unwrap() {
+                break;
+            }
+            scl.set_low().ok();
+            delay.delay_us(5);
+            scl.set_high().ok();
+            delay.delay_us(5);
+        }
+        if sda.is_low().unwrap() {
+            let pads = (scl.into_i2c::<I>(), sda.into_i2c::<I>());
+            return (Self { i2c, pads }, Err(Error::Bus));
+        }
+        // Stop condition, SDA rises while SCL is high.
+        let mut sda = sda.into_pull_up_output();
+        scl.set_low().ok();
+        delay.delay_us(5);
+        sda.set_low().ok();
+        delay.delay_us(5);
+        scl.set_high().ok();
+        delay.delay_us(5);
+        sda.set_high().ok();
+        delay.delay_us(5);
+        let pads = (scl.into_i2c::<I>(), sda.into_i2c::<I>());
+        (Self { i2c, pads }, Ok(()))
+    }
+}
+
+/// Inter-Integrated Circuit bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Serial clock frequency.
+    pub frequency: Hertz,
+}
+
+impl Config {
+    /// Standard mode, 100 kHz.
+    pub const STANDARD: Self = Self {
+        frequency: Hertz(100_000),
+    };
+    /// Fast mode, 400 kHz.
+    pub const FAST: Self = Self {
+        frequency: Hertz(400_000),
+    };
+    /// Fast mode plus, 1 MHz.
+    pub const FAST_PLUS: Self = Self {
+        frequency: Hertz(1_000_000),
+    };
+
+    /// Set serial clock frequency.
+    #[inline]
+    pub const fn set_frequency(self, frequency: Hertz) -> Self {
+        Self { frequency }
+    }
+}
+
+impl Default for Config {
+    /// Bus configuration defaults to standard mode, 100 kHz.
+    #[inline]
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// Errors on bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Impossibly high frequency for current clock source.
+    FrequencyTooHigh,
+    /// Impossibly low frequency for current clock source.
+    FrequencyTooLow,
+}
+
+/// Compute peripheral clock divide factor and phase durations of one bit.
+///
+/// Each bit lasts four phases, each phase lasts 1 ~ 256 peripheral clocks.
+#[inline]
+fn i2c_timing(source: Hertz, frequency: Hertz) -> Result<(u8, [u8; 4]), ConfigError> {
+    if frequency.0 == 0 {
+        return Err(ConfigError::FrequencyTooLow);
+    }
+    for divide in 1..=256 {
+        let clock = source.0 / divide;
+        let total = (clock + frequency.0 / 2) / frequency.0;
+        if total < 4 {
+            return Err(ConfigError::FrequencyTooHigh);
+        }
+        if total > 4 * 256 {
+            continue;
+        }
+        let (quarter, rem) = (total / 4, total % 4);
+        let phase = |i: u32| (quarter + (rem > i) as u32 - 1) as u8;
+        return Ok(((divide - 1) as u8, [phase(0), phase(1), phase(2), phase(3)]));
+    }
+    Err(ConfigError::FrequencyTooLow)
+}
+
 /// I2C error.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Error {
+    /// Slave did not acknowledge address or data.
+    NoAcknowledge(NoAcknowledgeSource),
+    /// Arbitration lost to another master.
+    ArbitrationLoss,
+    /// Write into full FIFO queue.
+    FifoOverflow,
+    /// Read from empty FIFO queue.
+    FifoUnderflow,
+    /// Bus stays busy, e.g. a slave holds SDA low.
+    Bus,
+    /// Transaction shape cannot be sent by hardware.
+    Unsupported,
     Other,
 }
 
@@ -597,7 +912,11 @@ impl embedded_hal::i2c::Error for Error {
     fn kind(&self) -> embedded_hal::i2c::ErrorKind {
         use embedded_hal::i2c::ErrorKind;
         match self {
-            Error::Other => ErrorKind::Other,
+            Error::NoAcknowledge(source) => ErrorKind::NoAcknowledge(*source),
+            Error::ArbitrationLoss => ErrorKind::ArbitrationLoss,
+            Error::FifoOverflow => ErrorKind::Overrun,
+            Error::Bus => ErrorKind::Bus,
+            Error::FifoUnderflow | Error::Unsupported | Error::Other => ErrorKind::Other,
         }
     }
 }
@@ -607,53 +926,949 @@ impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::ErrorType for
 }
 
 impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::I2c for I2c<I2C, PADS> {
+    /// Perform transaction of consecutive operations.
+    ///
+    /// Zero-length operations are skipped, as hardware cannot send address-only frames. The
+    /// remaining operations must be adjacent writes, adjacent reads, or writes of 1 to 4 bytes
+    /// followed by reads; the writes are then sent as sub-address phase, and the reads follow a
+    /// repeated start. Other transactions return `Error::Unsupported`.
+    ///
+    /// Runs longer than 256 bytes are split into packets, each with its own start condition
+    /// and address. Packets of a read led by sub-address carry the sub-address advanced by the
+    /// bytes read so far, as EEPROM-style devices expect.
     #[inline]
     fn transaction(
         &mut self,
         address: u8,
-        operations: &mut [embedded_hal::i2c::Operation<'_>],
+        operations: &mut [Operation<'_>],
     ) -> Result<(), Self::Error> {
-        for op in operations {
-            match op {
-                embedded_hal::i2c::Operation::Write(_bytes) => {
-                    todo!()
-                }
-                embedded_hal::i2c::Operation::Read(bytes) => {
-                    let len = bytes.len() as u8;
-                    unsafe {
-                        self.i2c.config.modify(|config| {
-                            config
-                                .set_read_direction()
-                                .set_slave_address(address as u16)
-                                .set_packet_length(len - 1)
-                                .enable_master()
-                        })
-                    };
-
-                    let mut i = 0;
-                    let max_retry = len * 100;
-                    let mut retry = 0;
-                    while i < len {
-                        while self.i2c.fifo_config_1.read().receive_available_bytes() == 0 {
-                            retry += 1;
-                            if retry >= max_retry {
-                                unsafe { self.i2c.config.modify(|config| config.disable_master()) };
-                                return Err(Error::Other);
-                            }
-                        }
-                        let word = self.i2c.fifo_read.read();
-                        let bytes_to_read = core::cmp::min(len - i, 4);
-                        for j in 0..bytes_to_read {
-                            bytes[i as usize] = (word >> (j * 8)) as u8;
-                            i += 1;
-                        }
-                    }
+        let Some(frame) = plan_frame(operations)? else {
+            return Ok(());
+        };
+        let mut frame = CpuFrame::start(&self.i2c, address, frame);
+        loop {
+            i2c_wait(
+                &self.i2c,
+                |i2c| frame.nack_source(i2c),
+                |i2c| frame.is_ready(i2c),
+            )?;
+            if frame.step(operations) {
+                return Ok(());
+            }
+        }
+    }
+}
 
-                    unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+impl<I2C: Deref<Target = RegisterBlock>, PADS> I2c<I2C, PADS> {
+    /// Convert into an interrupt-driven async/await I2C peripheral.
+    ///
+    /// `state` must be notified with `I2cState::on_interrupt` in the I2C interrupt handler.
+    #[inline]
+    pub fn into_async(self, state: &I2cState) -> AsyncI2c<'_, I2C, PADS> {
+        state
+            .ref_to_i2c
+            .store(&*self.i2c as *const _ as usize, Ordering::Release);
+        unsafe {
+            self.i2c.interrupt_mask.modify(|val| {
+                ASYNC_INTERRUPTS
+                    .iter()
+                    .fold(val, |v, &i| v.mask_interrupt(i))
+            });
+            self.i2c.interrupt_enable.modify(|val| {
+                ASYNC_INTERRUPTS
+                    .iter()
+                    .fold(val, |v, &i| v.enable_interrupt(i))
+            });
+        }
+        AsyncI2c {
+            i2c: self.i2c,
+            pads: self.pads,
+            state,
+        }
+    }
+}
+
+/// Managed async/await Inter-Integrated Circuit peripheral.
+pub struct AsyncI2c<'a, I2C, PADS> {
+    i2c: I2C,
+    pads: PADS,
+    state: &'a I2cState,
+}
+
+impl<'a, I2C: Deref<Target = RegisterBlock>, PADS> AsyncI2c<'a, I2C, PADS> {
+    /// Convert back into blocking I2C peripheral, masking all I2C interrupts.
+    #[inline]
+    pub fn into_blocking(self) -> I2c<I2C, PADS> {
+        unsafe {
+            self.i2c.interrupt_mask.modify(|val| {
+                ASYNC_INTERRUPTS
+                    .iter()
+                    .fold(val, |v, &i| v.mask_interrupt(i))
+            });
+        }
+        I2c {
+            i2c: self.i2c,
+            pads: self.pads,
+        }
+    }
+}
+
+/// Waker as the state for an async/await I2C peripheral.
+#[derive(Debug)]
+pub struct I2cState {
+    waker: atomic_waker::AtomicWaker,
+    ref_to_i2c: AtomicUsize,
+}
+
+impl I2cState {
+    /// Creates the waker state for an I2C peripheral.
+    #[inline]
+    pub const fn new() -> I2cState {
+        I2cState {
+            waker: atomic_waker::AtomicWaker::new(),
+            ref_to_i2c: AtomicUsize::new(0),
+        }
+    }
+    /// Use this state to handle interrupt.
+    ///
+    /// Pending interrupts are masked and the waiting task is woken. Interrupt states are
+    /// left for the task to inspect and clear.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let i2c = unsafe { &*(self.ref_to_i2c.load(Ordering::Acquire) as *const RegisterBlock) };
+        let state = i2c.interrupt_state.read();
+        let mut mask = i2c.interrupt_mask.read();
+        for interrupt in ASYNC_INTERRUPTS {
+            if state.has_interrupt(interrupt) {
+                mask = mask.mask_interrupt(interrupt);
+            }
+        }
+        unsafe { i2c.interrupt_mask.write(mask) };
+        self.waker.wake();
+    }
+}
+
+impl Default for I2cState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::ErrorType
+    for AsyncI2c<'a, I2C, PADS>
+{
+    type Error = Error;
+}
+
+impl<'a, I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal_async::i2c::I2c
+    for AsyncI2c<'a, I2C, PADS>
+{
+    /// Perform transaction of consecutive operations.
+    ///
+    /// Supports the same transactions as the blocking `I2c`. The task sleeps until
+    /// end of transfer, FIFO ready, not-acknowledge or arbitration lost interrupts.
+    #[inline]
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let Some(frame) = plan_frame(operations)? else {
+            return Ok(());
+        };
+        let (i2c, state) = (&*self.i2c, self.state);
+        // Aborts the frame if this future is dropped before the frame ends.
+        let mut frame = CpuFrame::start(i2c, address, frame);
+        loop {
+            i2c_wait_async(
+                i2c,
+                state,
+                frame.interrupt(),
+                |i2c| frame.nack_source(i2c),
+                |i2c| frame.is_ready(i2c),
+            )
+            .await?;
+            if frame.step(operations) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Interrupts used by async/await I2C peripheral.
+const ASYNC_INTERRUPTS: [Interrupt; 6] = [
+    Interrupt::TransferEnd,
+    Interrupt::TransmitFifoReady,
+    Interrupt::ReceiveFifoReady,
+    Interrupt::NackReceived,
+    Interrupt::ArbitrationLost,
+    Interrupt::FifoError,
+];
+
+impl<I2C: Deref<Target = RegisterBlock>, PADS> I2c<I2C, PADS> {
+    /// Hand over data transfers to DMA channels.
+    ///
+    /// Each channel comes with the DMA peripheral request of this I2C on its DMA controller.
+    #[inline]
+    pub fn with_dma<'b, T: PeripheralId + Copy>(
+        self,
+        (mut tx_channel, tx_request): (TypedChannel<'b, T>, T),
+        (mut rx_channel, rx_request): (TypedChannel<'b, T>, T),
+    ) -> DmaI2c<'b, I2C, PADS, T> {
+        // Hardware FIFO queue is accessed in words.
+        tx_channel.configure(DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            src_req: None,
+            dst_req: Some(tx_request),
+            src_addr_inc: true,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::Word,
+            dst_transfer_width: TransferWidth::Word,
+        });
+        rx_channel.configure(DmaChannelConfig {
+            direction: DmaMode::Periph2Mem,
+            src_req: Some(rx_request),
+            dst_req: None,
+            src_addr_inc: false,
+            dst_addr_inc: true,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::Word,
+            dst_transfer_width: TransferWidth::Word,
+        });
+        unsafe {
+            self.i2c
+                .fifo_config_1
+                .modify(|val| val.set_transmit_threshold(0).set_receive_threshold(0));
+            self.i2c.fifo_config_0.modify(|val| {
+                val.enable_dma_transmit()
+                    .enable_dma_receive()
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo()
+            });
+        }
+        DmaI2c {
+            i2c: self.i2c,
+            pads: self.pads,
+            tx_channel,
+            rx_channel,
+        }
+    }
+}
+
+/// Managed Inter-Integrated Circuit peripheral with DMA transmit and receive.
+pub struct DmaI2c<'b, I2C, PADS, T> {
+    i2c: I2C,
+    pads: PADS,
+    tx_channel: TypedChannel<'b, T>,
+    rx_channel: TypedChannel<'b, T>,
+}
+
+impl<'b, I2C: Deref<Target = RegisterBlock>, PADS, T> DmaI2c<'b, I2C, PADS, T> {
+    /// Starts a DMA write of an owned buffer, optionally led by up to 4 sub-address bytes.
+    ///
+    /// Buffer must start at a word aligned address and its length must be a non-zero multiple
+    /// of 4 bytes; otherwise the buffer is returned back. Send short or unaligned payloads with
+    /// the blocking `I2c` instead.
+    #[inline]
+    pub fn write<B>(
+        &mut self,
+        address: u8,
+        sub_address: &[u8],
+        buffer: B,
+    ) -> Result<DmaTransfer<'_, 'b, I2C, PADS, T, B>, B>
+    where
+        B: Deref + 'static,
+        B::Target: AsSlice<Element = u8>,
+    {
+        let slice = buffer.as_slice();
+        let (ptr, len) = (slice.as_ptr() as usize, slice.len());
+        DmaTransfer::new(self, address, sub_address, false, buffer, ptr, len)
+    }
+
+    /// Starts a DMA read into an owned buffer, optionally led by up to 4 sub-address bytes.
+    ///
+    /// Buffer must start at a word aligned address and its length must be a non-zero multiple
+    /// of 4 bytes; otherwise the buffer is returned back.
+    #[inline]
+    pub fn read<B>(
+        &mut self,
+        address: u8,
+        sub_address: &[u8],
+        mut buffer: B,
+    ) -> Result<DmaTransfer<'_, 'b, I2C, PADS, T, B>, B>
+    where
+        B: DerefMut + 'static,
+        B::Target: AsMutSlice<Element = u8>,
+    {
+        let slice = buffer.as_mut_slice();
+        let (ptr, len) = (slice.as_mut_ptr() as usize, slice.len());
+        DmaTransfer::new(self, address, sub_address, true, buffer, ptr, len)
+    }
+
+    /// Release DMA channels and return the blocking I2C peripheral.
+    #[inline]
+    pub fn free(self) -> (I2c<I2C, PADS>, TypedChannel<'b, T>, TypedChannel<'b, T>) {
+        self.tx_channel.stop();
+        self.rx_channel.stop();
+        unsafe {
+            self.i2c
+                .fifo_config_0
+                .modify(|val| val.disable_dma_transmit().disable_dma_receive());
+        }
+        let i2c = I2c {
+            i2c: self.i2c,
+            pads: self.pads,
+        };
+        (i2c, self.tx_channel, self.rx_channel)
+    }
+}
+
+/// Ongoing DMA transfer on an owned buffer.
+///
+/// Payloads longer than 256 bytes are sent as several hardware packets; the CPU only restarts
+/// the peripheral between packets, when the transfer is polled. Each packet carries the
+/// sub-address advanced by the bytes transferred so far, as EEPROM-style devices expect.
+pub struct DmaTransfer<'s, 'b, I2C: Deref<Target = RegisterBlock>, PADS, T, B> {
+    i2c: &'s mut DmaI2c<'b, I2C, PADS, T>,
+    buffer: Option<B>,
+    address: u8,
+    read: bool,
+    ptr: usize,
+    len: usize,
+    offset: usize,
+    sub_address: Option<(u32, usize)>,
+    saved: Option<(FunctionConfig, u32)>,
+    result: Option<Result<(), Error>>,
+}
+
+impl<'s, 'b, I2C: Deref<Target = RegisterBlock>, PADS, T, B> DmaTransfer<'s, 'b, I2C, PADS, T, B> {
+    #[inline]
+    fn new(
+        i2c: &'s mut DmaI2c<'b, I2C, PADS, T>,
+        address: u8,
+        sub_address: &[u8],
+        read: bool,
+        buffer: B,
+        ptr: usize,
+        len: usize,
+    ) -> Result<Self, B> {
+        if len == 0
+            || !len.is_multiple_of(4)
+            || !ptr.is_multiple_of(4)
+            || sub_address.len() > SUB_ADDRESS_MAX_LENGTH
+        {
+            return Err(buffer);
+        }
+        let sub_address = match sub_address.len() {
+            0 => None,
+            count => Some((pack_word(sub_address.iter().copied()), count)),
+        };
+        let saved = sub_address.map(|_| (i2c.i2c.config.read(), i2c.i2c.sub_address.read()));
+        match read {
+            true => flush_dcache(ptr, len),
+            false => clean_dcache(ptr, len),
+        }
+        let mut transfer = DmaTransfer {
+            i2c,
+            buffer: Some(buffer),
+            address,
+            read,
+            ptr,
+            len,
+            offset: 0,
+            sub_address,
+            saved,
+            result: None,
+        };
+        transfer.start_packet();
+        Ok(transfer)
+    }
+
+    /// Checks whether the transfer has ended, starting the next packet if needed.
+    #[inline]
+    pub fn is_done(&mut self) -> bool {
+        self.poll().is_some()
+    }
+
+    /// Waits for the transfer to end and returns its result with the buffer.
+    #[inline]
+    pub fn wait(mut self) -> (Result<(), Error>, B) {
+        let ans = loop {
+            match self.poll() {
+                Some(ans) => break ans,
+                None => core::hint::spin_loop(),
+            }
+        };
+        (ans, self.buffer.take().unwrap())
+    }
+
+    #[inline]
+    fn start_packet(&mut self) {
+        let chunk = core::cmp::min(self.len - self.offset, PACKET_MAX_LENGTH);
+        let sub_address = self
+            .sub_address
+            .map(|sub_address| advance_sub_address(sub_address, self.offset));
+        let i2c = &*self.i2c.i2c;
+        i2c_start(i2c, self.address, sub_address, self.read, chunk);
+        let (channel, src, dst) = if self.read {
+            let dst = (self.ptr + self.offset) as u32;
+            (&self.i2c.rx_channel, &i2c.fifo_read as *const _ as u32, dst)
+        } else {
+            let src = (self.ptr + self.offset) as u32;
+            (
+                &self.i2c.tx_channel,
+                src,
+                &i2c.fifo_write as *const _ as u32,
+            )
+        };
+        // One packet of at most 64 words fits into a single linked list item.
+        let mut lli = [LliPool::new()];
+        channel.lli_config(&mut lli, 1, src, dst, 0, (chunk / 4) as u32);
+        compiler_fence(Ordering::Release);
+        channel.lli_load(&lli[0]);
+        channel.start();
+    }
+
+    #[inline]
+    fn poll(&mut self) -> Option<Result<(), Error>> {
+        if self.result.is_some() {
+            return self.result;
+        }
+        let i2c = &*self.i2c.i2c;
+        let first_packet = self.offset == 0;
+        // Words moved by transmit DMA in current packet tell address from data not-acknowledge.
+        let pushed = (self.i2c.tx_channel.source_address() as usize)
+            .wrapping_sub(self.ptr + self.offset)
+            / 4;
+        let nack = |i2c: &RegisterBlock| match (self.read, self.sub_address) {
+            (true, Some(_)) => NoAcknowledgeSource::Unknown,
+            (true, None) => NoAcknowledgeSource::Address,
+            (false, _) => write_nack_source(i2c, first_packet, pushed),
+        };
+        if let Err(e) = i2c_check(i2c, nack) {
+            self.finish(Err(e));
+            return self.result;
+        }
+        let channel = if self.read {
+            &self.i2c.rx_channel
+        } else {
+            &self.i2c.tx_channel
+        };
+        // Receive DMA may still be moving the last word after the packet ends.
+        if !i2c
+            .interrupt_state
+            .read()
+            .has_interrupt(Interrupt::TransferEnd)
+            || channel.is_busy()
+        {
+            return None;
+        }
+        unsafe {
+            i2c.interrupt_clear
+                .write(InterruptClear(0).clear_interrupt(Interrupt::TransferEnd));
+            i2c.config.modify(|config| config.disable_master());
+        }
+        self.offset += core::cmp::min(self.len - self.offset, PACKET_MAX_LENGTH);
+        if self.offset < self.len {
+            self.start_packet();
+            None
+        } else {
+            compiler_fence(Ordering::Acquire);
+            self.finish(Ok(()));
+            self.result
+        }
+    }
+
+    #[inline]
+    fn finish(&mut self, result: Result<(), Error>) {
+        if result.is_err() {
+            self.i2c.tx_channel.stop();
+            self.i2c.rx_channel.stop();
+            i2c_abort(&self.i2c.i2c);
+        }
+        if self.read {
+            // Buffer was flushed before the transfer and only DMA wrote it since.
+            unsafe { invalidate_dcache(self.ptr, self.len) };
+        }
+        if let Some(saved) = self.saved {
+            i2c_restore_sub_address(&self.i2c.i2c, saved);
+        }
+        self.result = Some(result);
+    }
+}
+
+impl<'s, 'b, I2C: Deref<Target = RegisterBlock>, PADS, T, B> Drop
+    for DmaTransfer<'s, 'b, I2C, PADS, T, B>
+{
+    /// Stops the DMA channels and the peripheral before the buffer could be released.
+    #[inline]
+    fn drop(&mut self) {
+        if self.result.is_none() {
+            self.finish(Err(Error::Other));
+        }
+    }
+}
+
+/// Maximum bytes in one hardware packet.
+const PACKET_MAX_LENGTH: usize = 256;
+/// Maximum bytes sent in hardware sub-address phase.
+const SUB_ADDRESS_MAX_LENGTH: usize = 4;
+/// Words in transmit or receive FIFO queue.
+const FIFO_DEPTH: usize = 2;
+/// Polls on a hardware state before the transfer is considered stalled.
+const MAX_RETRY: usize = 100_000;
+
+/// Transfer of a transaction, sent as one or more hardware packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    /// Write of given length.
+    Write(usize),
+    /// Read of given length, led by an optional sub-address `(value, byte count)`.
+    Read(Option<(u32, usize)>, usize),
+}
+
+/// Plan the transfer of a transaction, or `None` if there are no bytes to move.
+#[inline]
+fn plan_frame(operations: &[Operation<'_>]) -> Result<Option<Frame>, Error> {
+    // Runs of non-empty operations as `(is read, total length)`.
+    let mut runs = [(false, 0); 2];
+    let mut count = 0;
+    for op in operations.iter() {
+        let (read, len) = match op {
+            Operation::Read(bytes) => (true, bytes.len()),
+            Operation::Write(bytes) => (false, bytes.len()),
+        };
+        if len == 0 {
+            continue;
+        }
+        match count {
+            1 | 2 if runs[count - 1].0 == read => runs[count - 1].1 += len,
+            2 => return Err(Error::Unsupported),
+            _ => {
+                runs[count] = (read, len);
+                count += 1;
+            }
+        }
+    }
+    match runs[..count] {
+        [] => Ok(None),
+        [(false, len)] => Ok(Some(Frame::Write(len))),
+        [(true, len)] => Ok(Some(Frame::Read(None, len))),
+        [(false, sub_len), (true, len)] if sub_len <= SUB_ADDRESS_MAX_LENGTH => {
+            let sub_address = pack_word(write_bytes(operations));
+            Ok(Some(Frame::Read(Some((sub_address, sub_len)), len)))
+        }
+        _ => Err(Error::Unsupported),
+    }
+}
+
+/// Sub-address `(value, byte count)` advanced by `offset`, wrapping within its byte count.
+///
+/// Sub-address bytes are sent from the lowest byte of `value`, so the first byte is the most
+/// significant one of the advanced address.
+#[inline]
+fn advance_sub_address((value, count): (u32, usize), offset: usize) -> (u32, usize) {
+    let bits = count as u32 * 8;
+    let address = value.swap_bytes() >> (32 - bits);
+    let address = address.wrapping_add(offset as u32) & (u32::MAX >> (32 - bits));
+    ((address << (32 - bits)).swap_bytes(), count)
+}
+
+/// Little-endian FIFO or sub-address word of up to 4 bytes.
+#[inline]
+fn pack_word(bytes: impl Iterator<Item = u8>) -> u32 {
+    bytes
+        .enumerate()
+        .fold(0, |acc, (i, byte)| acc | (byte as u32) << (i * 8))
+}
+
+#[inline]
+fn write_bytes<'o>(operations: &'o [Operation<'_>]) -> impl Iterator<Item = u8> + 'o {
+    operations
+        .iter()
+        .flat_map(|op| match op {
+            Operation::Write(bytes) => bytes.iter(),
+            Operation::Read(_) => [].iter(),
+        })
+        .copied()
+}
+
+/// Configure and start one hardware packet.
+#[inline]
+fn i2c_start(
+    i2c: &RegisterBlock,
+    address: u8,
+    sub_address: Option<(u32, usize)>,
+    read: bool,
+    len: usize,
+) {
+    unsafe {
+        i2c.fifo_config_0
+            .modify(|val| val.clear_transmit_fifo().clear_receive_fifo());
+        i2c.interrupt_clear.write(
+            InterruptClear(0)
+                .clear_interrupt(Interrupt::TransferEnd)
+                .clear_interrupt(Interrupt::NackReceived),
+        );
+        if let Some((value, count)) = sub_address {
+            i2c.sub_address.write(value);
+            i2c.config.modify(|config| {
+                config
+                    .enable_sub_address()
+                    .set_sub_address_byte_count(SUB_ADDRESS_BYTE_COUNTS[count - 1])
+            });
+        }
+        i2c.config.modify(|config| {
+            let config = if read {
+                config.set_read_direction()
+            } else {
+                config.set_write_direction()
+            };
+            config
+                .set_slave_address(address as u16)
+                .set_packet_length((len - 1) as u8)
+                .enable_master()
+        });
+    }
+}
+
+const SUB_ADDRESS_BYTE_COUNTS: [SubAddressByteCount; 4] = [
+    SubAddressByteCount::One,
+    SubAddressByteCount::Two,
+    SubAddressByteCount::Three,
+    SubAddressByteCount::Four,
+];
+
+/// Poll until `f` holds, aborting the packet on bus error or timeout.
+///
+/// `nack` tells which part of the packet a not-acknowledge comes from.
+#[inline]
+fn i2c_wait(
+    i2c: &RegisterBlock,
+    nack: impl Fn(&RegisterBlock) -> NoAcknowledgeSource,
+    f: impl Fn(&RegisterBlock) -> bool,
+) -> Result<(), Error> {
+    for _ in 0..MAX_RETRY {
+        if let Err(e) = i2c_check(i2c, &nack) {
+            i2c_abort(i2c);
+            return Err(e);
+        }
+        if f(i2c) {
+            return Ok(());
+        }
+    }
+    i2c_abort(i2c);
+    Err(Error::Bus)
+}
+
+/// Check error states raised during current packet.
+#[inline]
+fn i2c_check(
+    i2c: &RegisterBlock,
+    nack: impl Fn(&RegisterBlock) -> NoAcknowledgeSource,
+) -> Result<(), Error> {
+    let state = i2c.interrupt_state.read();
+    if state.has_interrupt(Interrupt::ArbitrationLost) {
+        return Err(Error::ArbitrationLoss);
+    }
+    if state.has_interrupt(Interrupt::NackReceived) {
+        return Err(Error::NoAcknowledge(nack(i2c)));
+    }
+    if state.has_interrupt(Interrupt::FifoError) {
+        let fifo = i2c.fifo_config_0.read();
+        if fifo.is_transmit_fifo_overflow() || fifo.is_receive_fifo_overflow() {
+            return Err(Error::FifoOverflow);
+        }
+        return Err(Error::FifoUnderflow);
+    }
+    Ok(())
+}
+
+/// Stop master and bring peripheral back to idle state.
+#[inline]
+fn i2c_abort(i2c: &RegisterBlock) {
+    unsafe {
+        i2c.config.modify(|config| config.disable_master());
+        i2c.fifo_config_0
+            .modify(|val| val.clear_transmit_fifo().clear_receive_fifo());
+        i2c.interrupt_clear.write(
+            InterruptClear(0)
+                .clear_interrupt(Interrupt::TransferEnd)
+                .clear_interrupt(Interrupt::NackReceived)
+                .clear_interrupt(Interrupt::ArbitrationLost)
+                .clear_interrupt(Interrupt::FifoError),
+        );
+        i2c.bus_busy.modify(|val| val.clear_bus_busy());
+    }
+}
+
+/// Not-acknowledge before any word leaves transmit FIFO comes from address phase.
+#[inline]
+fn write_nack_source(
+    i2c: &RegisterBlock,
+    first_packet: bool,
+    pushed: usize,
+) -> NoAcknowledgeSource {
+    let queued = FIFO_DEPTH - i2c.fifo_config_1.read().transmit_available_bytes() as usize;
+    if first_packet && queued >= pushed {
+        NoAcknowledgeSource::Address
+    } else {
+        NoAcknowledgeSource::Data
+    }
+}
+
+#[inline]
+fn i2c_restore_sub_address(i2c: &RegisterBlock, (config, value): (FunctionConfig, u32)) {
+    unsafe {
+        i2c.sub_address.write(value);
+        i2c.config.modify(|val| {
+            let val = if config.is_sub_address_enabled() {
+                val.enable_sub_address()
+            } else {
+                val.disable_sub_address()
+            };
+            val.set_sub_address_byte_count(config.get_sub_address_byte_count())
+        });
+    }
+}
+
+/// Transfer of a transaction, moved word by word by the CPU.
+///
+/// Blocking and async transactions share it and only differ in how they wait between steps.
+/// Dropping an unfinished frame aborts it, e.g. when an async transaction is cancelled.
+struct CpuFrame<'r> {
+    i2c: &'r RegisterBlock,
+    address: u8,
+    frame: Frame,
+    len: usize,
+    moved: usize,
+    /// Bytes moved when current packet ends.
+    packet_end: usize,
+    /// Words pushed in current packet.
+    pushed: usize,
+    /// Operation and byte index of the next byte to move.
+    cursor: (usize, usize),
+    saved: Option<(FunctionConfig, u32)>,
+    ended: bool,
+}
+
+impl<'r> CpuFrame<'r> {
+    #[inline]
+    fn start(i2c: &'r RegisterBlock, address: u8, frame: Frame) -> Self {
+        let (sub_address, len) = match frame {
+            Frame::Write(len) | Frame::Read(None, len) => (None, len),
+            Frame::Read(Some(sub_address), len) => (Some(sub_address), len),
+        };
+        // Keep sub-address set by `I2c::enable_sub_address` for later transfers.
+        let saved = sub_address.map(|_| (i2c.config.read(), i2c.sub_address.read()));
+        let mut frame = CpuFrame {
+            i2c,
+            address,
+            frame,
+            len,
+            moved: 0,
+            packet_end: 0,
+            pushed: 0,
+            cursor: (0, 0),
+            saved,
+            ended: false,
+        };
+        frame.start_packet();
+        frame
+    }
+
+    /// Start the packet from `moved` bytes on.
+    #[inline]
+    fn start_packet(&mut self) {
+        let (sub_address, read) = match self.frame {
+            Frame::Write(_) => (None, false),
+            Frame::Read(sub_address, _) => (
+                sub_address.map(|sub_address| advance_sub_address(sub_address, self.moved)),
+                true,
+            ),
+        };
+        let chunk = core::cmp::min(self.len - self.moved, PACKET_MAX_LENGTH);
+        i2c_start(self.i2c, self.address, sub_address, read, chunk);
+        self.packet_end = self.moved + chunk;
+        self.pushed = 0;
+    }
+
+    /// Interrupt raised once the next step can be taken.
+    #[inline]
+    fn interrupt(&self) -> Interrupt {
+        match self.frame {
+            _ if self.moved == self.packet_end => Interrupt::TransferEnd,
+            Frame::Write(_) => Interrupt::TransmitFifoReady,
+            Frame::Read(..) => Interrupt::ReceiveFifoReady,
+        }
+    }
+
+    #[inline]
+    fn is_ready(&self, i2c: &RegisterBlock) -> bool {
+        match self.interrupt() {
+            Interrupt::TransmitFifoReady => i2c.fifo_config_1.read().transmit_available_bytes() > 0,
+            Interrupt::ReceiveFifoReady => i2c.fifo_config_1.read().receive_available_bytes() > 0,
+            _ => i2c
+                .interrupt_state
+                .read()
+                .has_interrupt(Interrupt::TransferEnd),
+        }
+    }
+
+    #[inline]
+    fn nack_source(&self, i2c: &RegisterBlock) -> NoAcknowledgeSource {
+        match self.frame {
+            Frame::Write(_) => {
+                write_nack_source(i2c, self.packet_end <= PACKET_MAX_LENGTH, self.pushed)
+            }
+            // Master acknowledges read data, so not-acknowledge comes from address or sub-address.
+            Frame::Read(Some(_), _) => NoAcknowledgeSource::Unknown,
+            Frame::Read(None, _) => NoAcknowledgeSource::Address,
+        }
+    }
+
+    /// Move the next word through FIFO, or stop master once a packet has ended and start
+    /// the next one.
+    ///
+    /// Returns `true` when the last packet has ended.
+    #[inline]
+    fn step(&mut self, operations: &mut [Operation<'_>]) -> bool {
+        let i2c = self.i2c;
+        if self.moved == self.packet_end {
+            unsafe {
+                i2c.interrupt_clear
+                    .write(InterruptClear(0).clear_interrupt(Interrupt::TransferEnd));
+                i2c.config.modify(|config| config.disable_master());
+            }
+            if self.moved < self.len {
+                self.start_packet();
+                return false;
+            }
+            self.ended = true;
+            return true;
+        }
+        let count = core::cmp::min(self.packet_end - self.moved, 4);
+        let mut word = match self.frame {
+            Frame::Write(_) => 0,
+            Frame::Read(..) => i2c.fifo_read.read(),
+        };
+        for i in 0..count {
+            // Skip exhausted operations, and the sub-address writes of a read.
+            let (op, byte) = loop {
+                let (op, byte) = self.cursor;
+                match (&operations[op], self.frame) {
+                    (Operation::Write(bytes), Frame::Write(_)) if byte < bytes.len() => {
+                        break (op, byte);
+                    }
+                    (Operation::Read(bytes), Frame::Read(..)) if byte < bytes.len() => {
+                        break (op, byte);
+                    }
+                    _ => {}
                 }
+                self.cursor = (op + 1, 0);
+            };
+            match &mut operations[op] {
+                Operation::Write(bytes) => word |= (bytes[byte] as u32) << (i * 8),
+                Operation::Read(bytes) => bytes[byte] = (word >> (i * 8)) as u8,
             }
+            self.cursor = (op, byte + 1);
         }
-        Ok(())
+        if let Frame::Write(_) = self.frame {
+            unsafe { i2c.fifo_write.write(word) };
+            self.pushed += 1;
+        }
+        self.moved += count;
+        false
+    }
+}
+
+impl Drop for CpuFrame<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.ended {
+            unsafe {
+                self.i2c.interrupt_mask.modify(|val| {
+                    ASYNC_INTERRUPTS
+                        .iter()
+                        .fold(val, |v, &i| v.mask_interrupt(i))
+                })
+            };
+            i2c_abort(self.i2c);
+        }
+        if let Some(saved) = self.saved {
+            i2c_restore_sub_address(self.i2c, saved);
+        }
+    }
+}
+
+/// Wait until `f` holds, sleeping on `interrupt` and error interrupts.
+///
+/// Packet is aborted on bus error. Unlike blocking `i2c_wait` there is no retry limit,
+/// a stalled bus keeps the task pending.
+#[inline]
+async fn i2c_wait_async(
+    i2c: &RegisterBlock,
+    state: &I2cState,
+    interrupt: Interrupt,
+    nack: impl Fn(&RegisterBlock) -> NoAcknowledgeSource,
+    f: impl Fn(&RegisterBlock) -> bool,
+) -> Result<(), Error> {
+    poll_fn(|cx| {
+        state.waker.register(cx.waker());
+        let ans = match i2c_check(i2c, &nack) {
+            Err(e) => {
+                i2c_abort(i2c);
+                Err(e)
+            }
+            Ok(()) if f(i2c) => Ok(()),
+            Ok(()) => {
+                unsafe {
+                    i2c.interrupt_mask.modify(|val| {
+                        val.unmask_interrupt(interrupt)
+                            .unmask_interrupt(Interrupt::NackReceived)
+                            .unmask_interrupt(Interrupt::ArbitrationLost)
+                            .unmask_interrupt(Interrupt::FifoError)
+                    })
+                };
+                return Poll::Pending;
+            }
+        };
+        unsafe {
+            i2c.interrupt_mask.modify(|val| {
+                ASYNC_INTERRUPTS
+                    .iter()
+                    .fold(val, |v, &i| v.mask_interrupt(i))
+            })
+        };
+        Poll::Ready(ans)
+    })
+    .await
+}
+
+/// I2C instance clocked by multi-media global peripheral, i.e. I2C2 or I2C3 of BL808.
+#[cfg(not(feature = "glb-v1"))]
+pub trait MmInstance {
+    /// Clock configuration register of this instance.
+    fn clock_config(mm_glb: &glb::mm::RegisterBlock) -> &RW<glb::mm::PeripheralConfig>;
+}
+
+#[cfg(not(feature = "glb-v1"))]
+impl MmInstance for gpio::I2c<2> {
+    #[inline]
+    fn clock_config(mm_glb: &glb::mm::RegisterBlock) -> &RW<glb::mm::PeripheralConfig> {
+        &mm_glb.peripheral_config_0
+    }
+}
+
+#[cfg(not(feature = "glb-v1"))]
+impl MmInstance for gpio::I2c<3> {
+    #[inline]
+    fn clock_config(mm_glb: &glb::mm::RegisterBlock) -> &RW<glb::mm::PeripheralConfig> {
+        &mm_glb.peripheral_config_3
     }
 }
 
@@ -667,7 +1882,6 @@ mod i2c_impls {
 
     // 0, 2, 4, ..., 2n: SCL
     // 1, 3, 5, ..., 2n+1: SDA
-    // TODO: support other pads if needed
     impl<'a, const I: usize> SclPin<I> for Alternate<'a, 0, gpio::I2c<I>> {}
     impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 1, gpio::I2c<I>> {}
     impl<'a, const I: usize> SclPin<I> for Alternate<'a, 2, gpio::I2c<I>> {}
@@ -691,23 +1905,97 @@ mod i2c_impls {
     impl<'a, const I: usize> SclPin<I> for Alternate<'a, 20, gpio::I2c<I>> {}
     impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 21, gpio::I2c<I>> {}
     impl<'a, const I: usize> SclPin<I> for Alternate<'a, 22, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
     impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 23, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
     impl<'a, const I: usize> SclPin<I> for Alternate<'a, 24, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
     impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 25, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
     impl<'a, const I: usize> SclPin<I> for Alternate<'a, 26, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
     impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 27, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
     impl<'a, const I: usize> SclPin<I> for Alternate<'a, 28, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
     impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 29, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 30, gpio::I2c<I>> {}
+    #[cfg(not(feature = "bl602"))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 31, gpio::I2c<I>> {}
+    #[cfg(not(feature = "glb-v1"))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 32, gpio::I2c<I>> {}
+    #[cfg(not(feature = "glb-v1"))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 33, gpio::I2c<I>> {}
+    #[cfg(not(feature = "glb-v1"))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 34, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 35, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 36, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 37, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 38, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 39, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 40, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 41, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 42, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 43, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SclPin<I> for Alternate<'a, 44, gpio::I2c<I>> {}
+    #[cfg(not(any(feature = "glb-v1", feature = "bl616")))]
+    impl<'a, const I: usize> SdaPin<I> for Alternate<'a, 45, gpio::I2c<I>> {}
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        BusBusy, Config, FifoConfig0, FifoConfig1, Interrupt, InterruptClear, InterruptEnable,
-        InterruptMask, InterruptState, PeriodData, PeriodStart, PeriodStop, RegisterBlock,
-        SubAddressByteCount,
+        BusBusy, ConfigError, DmaI2c, Error, FifoConfig0, FifoConfig1, FunctionConfig, I2c,
+        I2cState, Interrupt, InterruptClear, InterruptEnable, InterruptMask, InterruptState,
+        PeriodData, PeriodStart, PeriodStop, RegisterBlock, SubAddressByteCount,
+        advance_sub_address,
     };
-    use core::mem::offset_of;
+    use crate::dma::mock::{MockDma, MockRequest};
+    use core::ptr::addr_of_mut;
+    use core::{
+        cell::UnsafeCell, future::Future, mem::offset_of, ops::Deref, sync::atomic::Ordering,
+    };
+    use embedded_hal::i2c::{I2c as _, NoAcknowledgeSource, Operation};
+    use embedded_hal_async::i2c::I2c as _;
+
+    /// Register block in memory, with every transfer completing immediately.
+    struct MockI2c(UnsafeCell<[u32; 0x24]>);
+
+    impl MockI2c {
+        fn new(fifo_read: u32) -> I2c<MockI2c, ((), ())> {
+            let mut regs = [0u32; 0x24];
+            // Interrupt state is the lowest byte of word at 0x04.
+            regs[1] = 1 << (Interrupt::TransferEnd as u32);
+            regs[0x84 / 4] = 0x0000_0202;
+            regs[0x8c / 4] = fifo_read;
+            I2c {
+                i2c: MockI2c(UnsafeCell::new(regs)),
+                pads: ((), ()),
+            }
+        }
+        fn set_interrupt_state(&self, val: Interrupt) {
+            unsafe { (*self.0.get())[1] |= 1 << (val as u32) };
+        }
+    }
+
+    impl Deref for MockI2c {
+        type Target = RegisterBlock;
+
+        fn deref(&self) -> &RegisterBlock {
+            unsafe { &*(self.0.get() as *const RegisterBlock) }
+        }
+    }
 
     #[test]
     fn struct_register_block_offset() {
@@ -728,8 +2016,8 @@ mod tests {
     }
 
     #[test]
-    fn struct_config_functions() {
-        let mut config = Config(0x0);
+    fn struct_function_config_functions() {
+        let mut config = FunctionConfig(0x0);
 
         config = config.enable_master();
         assert_eq!(config.0, 0x00000001);
@@ -738,7 +2026,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_master_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_read_direction();
         assert_eq!(config.0, 0x00000002);
         assert!(config.is_read_direction());
@@ -748,7 +2036,7 @@ mod tests {
         assert!(!config.is_read_direction());
         assert!(config.is_write_direction());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_deglitch();
         assert_eq!(config.0, 0x00000004);
         assert!(config.is_deglitch_enabled());
@@ -756,7 +2044,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_deglitch_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_scl_sync();
         assert_eq!(config.0, 0x00000008);
         assert!(config.is_scl_sync_enabled());
@@ -764,7 +2052,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_scl_sync_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_sub_address();
         assert_eq!(config.0, 0x00000010);
         assert!(config.is_sub_address_enabled());
@@ -772,7 +2060,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_sub_address_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_sub_address_byte_count(SubAddressByteCount::One);
         assert_eq!(config.0, 0x00000000);
         assert_eq!(
@@ -780,7 +2068,7 @@ mod tests {
             SubAddressByteCount::One
         );
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_sub_address_byte_count(SubAddressByteCount::Two);
         assert_eq!(config.0, 0x00000020);
         assert_eq!(
@@ -788,7 +2076,7 @@ mod tests {
             SubAddressByteCount::Two
         );
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_sub_address_byte_count(SubAddressByteCount::Three);
         assert_eq!(config.0, 0x00000040);
         assert_eq!(
@@ -796,7 +2084,7 @@ mod tests {
             SubAddressByteCount::Three
         );
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_sub_address_byte_count(SubAddressByteCount::Four);
         assert_eq!(config.0, 0x00000060);
         assert_eq!(
@@ -804,7 +2092,7 @@ mod tests {
             SubAddressByteCount::Four
         );
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_ten_bit_address();
         assert_eq!(config.0, 0x00000080);
         assert!(config.is_ten_bit_address_enabled());
@@ -812,17 +2100,17 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_ten_bit_address_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_slave_address(0x17ff);
         assert_eq!(config.0, 0x0003ff00);
         assert_eq!(config.get_slave_address(), 0x3ff);
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_packet_length(0x66);
         assert_eq!(config.0, 0x06600000);
         assert_eq!(config.get_packet_length(), 0x66);
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_deglitch_cycle_count(0x01);
         assert_eq!(config.0, 0x10000000);
         assert_eq!(config.get_deglitch_cycle_count(), 0x01);
@@ -959,4 +2247,485 @@ mod tests {
         fifo_config = FifoConfig1(0x0);
         assert_eq!(fifo_config.receive_threshold(), 0x00);
     }
+
+    #[test]
+    fn function_i2c_timing() {
+        use super::i2c_timing;
+        use embedded_time::rate::Hertz;
+
+        let xtal = Hertz(40_000_000);
+        assert_eq!(i2c_timing(xtal, Hertz(100_000)), Ok((0, [99, 99, 99, 99])));
+        assert_eq!(i2c_timing(xtal, Hertz(400_000)), Ok((0, [24, 24, 24, 24])));
+        assert_eq!(i2c_timing(xtal, Hertz(1_000_000)), Ok((0, [9, 9, 9, 9])));
+        assert_eq!(i2c_timing(xtal, Hertz(3_000_000)), Ok((0, [3, 2, 2, 2])));
+        assert_eq!(
+            i2c_timing(xtal, Hertz(1_000)),
+            Ok((39, [249, 249, 249, 249]))
+        );
+        assert_eq!(
+            i2c_timing(xtal, Hertz(20_000_000)),
+            Err(ConfigError::FrequencyTooHigh)
+        );
+        assert_eq!(
+            i2c_timing(xtal, Hertz(100)),
+            Err(ConfigError::FrequencyTooLow)
+        );
+        assert_eq!(
+            i2c_timing(xtal, Hertz(0)),
+            Err(ConfigError::FrequencyTooLow)
+        );
+    }
+
+    #[test]
+    fn i2c_write() {
+        let mut i2c = MockI2c::new(0);
+        i2c.write(0x15, &[0x11, 0x22, 0x33]).unwrap();
+        let config = i2c.i2c.config.read();
+        assert!(config.is_write_direction());
+        assert!(!config.is_master_enabled());
+        assert!(!config.is_sub_address_enabled());
+        assert_eq!(config.get_slave_address(), 0x15);
+        assert_eq!(config.get_packet_length(), 2);
+        assert_eq!(unsafe { (*i2c.i2c.0.get())[0x88 / 4] }, 0x0033_2211);
+    }
+
+    #[test]
+    fn i2c_read() {
+        let mut i2c = MockI2c::new(0x4433_2211);
+        let mut buf = [0u8; 6];
+        i2c.read(0x15, &mut buf).unwrap();
+        assert_eq!(buf, [0x11, 0x22, 0x33, 0x44, 0x11, 0x22]);
+        let config = i2c.i2c.config.read();
+        assert!(config.is_read_direction());
+        assert!(!config.is_master_enabled());
+        assert_eq!(config.get_packet_length(), 5);
+    }
+
+    #[test]
+    fn i2c_write_read_sub_address() {
+        let mut i2c = MockI2c::new(0x4433_2211);
+        let mut buf = [0u8; 2];
+        i2c.write_read(0x15, &[0x01, 0x02], &mut buf).unwrap();
+        assert_eq!(buf, [0x11, 0x22]);
+        let config = i2c.i2c.config.read();
+        assert!(config.is_read_direction());
+        assert_eq!(config.get_packet_length(), 1);
+        // Sub-address phase does not outlive the transaction.
+        assert!(!config.is_sub_address_enabled());
+
+        i2c.enable_sub_address(0x5a);
+        i2c.write_read(0x15, &[0x01, 0x02, 0x03], &mut buf).unwrap();
+        let config = i2c.i2c.config.read();
+        assert!(config.is_sub_address_enabled());
+        assert_eq!(
+            config.get_sub_address_byte_count(),
+            SubAddressByteCount::One
+        );
+        assert_eq!(i2c.i2c.sub_address.read(), 0x5a);
+    }
+
+    #[test]
+    fn i2c_write_read_long_write() {
+        let mut i2c = MockI2c::new(0x4433_2211);
+        let mut buf = [0u8; 1];
+        assert_eq!(
+            i2c.write_read(0x15, &[0x01, 0x02, 0x03, 0x04, 0x05], &mut buf),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(i2c.i2c.config.read(), FunctionConfig(0));
+    }
+
+    #[test]
+    fn i2c_packet_length_limit() {
+        let mut i2c = MockI2c::new(0x4433_2211);
+        i2c.write(0x15, &[0xaa; 256]).unwrap();
+        assert_eq!(i2c.i2c.config.read().get_packet_length(), 255);
+        assert_eq!(unsafe { (*i2c.i2c.0.get())[0x88 / 4] }, 0xaaaa_aaaa);
+
+        let mut buf = [0u8; 256];
+        i2c.write_read(0x15, &[0x01], &mut buf).unwrap();
+        assert_eq!(i2c.i2c.config.read().get_packet_length(), 255);
+        for (i, byte) in buf.iter().enumerate() {
+            assert_eq!(*byte, 0x11 * (i % 4 + 1) as u8);
+        }
+
+        // Longer runs are split into packets.
+        let mut bytes = [0u8; 302];
+        bytes[296..].copy_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        i2c.write(0x15, &bytes).unwrap();
+        assert_eq!(i2c.i2c.config.read().get_packet_length(), 45);
+        assert_eq!(unsafe { (*i2c.i2c.0.get())[0x88 / 4] }, 0x0000_0605);
+        assert!(!i2c.i2c.config.read().is_master_enabled());
+
+        let mut buf = [0u8; 257];
+        i2c.read(0x15, &mut buf).unwrap();
+        assert_eq!(i2c.i2c.config.read().get_packet_length(), 0);
+        assert_eq!(buf[256], 0x11);
+
+        let mut buf = [0u8; 300];
+        i2c.write_read(0x15, &[0x00, 0x10], &mut buf).unwrap();
+        let config = i2c.i2c.config.read();
+        assert!(config.is_read_direction());
+        assert_eq!(config.get_packet_length(), 43);
+        assert!(!config.is_sub_address_enabled());
+        for (i, byte) in buf.iter().enumerate() {
+            assert_eq!(*byte, 0x11 * (i % 4 + 1) as u8);
+        }
+    }
+
+    #[test]
+    fn function_advance_sub_address() {
+        assert_eq!(advance_sub_address((0x40, 1), 0), (0x40, 1));
+        // One-byte sub-address wraps around every packet.
+        assert_eq!(advance_sub_address((0x40, 1), 256), (0x40, 1));
+        assert_eq!(advance_sub_address((0x40, 1), 4), (0x44, 1));
+        // First byte is the most significant one.
+        assert_eq!(advance_sub_address((0x1000, 2), 256), (0x1001, 2));
+        assert_eq!(advance_sub_address((0xff10, 2), 512), (0xff12, 2));
+        assert_eq!(advance_sub_address((0xffff, 2), 256), (0xff00, 2));
+        assert_eq!(
+            advance_sub_address((0x0030_2010, 3), 0x10),
+            (0x0040_2010, 3)
+        );
+        assert_eq!(advance_sub_address((0xffff_ffff, 4), 1), (0x0000_0000, 4));
+        assert_eq!(advance_sub_address((0x0000_0001, 4), 256), (0x0001_0001, 4));
+    }
+
+    #[test]
+    fn i2c_merged_operations() {
+        let mut i2c = MockI2c::new(0x4433_2211);
+        let (mut a, mut b) = ([0u8; 3], [0u8; 3]);
+        i2c.transaction(
+            0x15,
+            &mut [Operation::Read(&mut a), Operation::Read(&mut b)],
+        )
+        .unwrap();
+        assert_eq!(a, [0x11, 0x22, 0x33]);
+        assert_eq!(b, [0x44, 0x11, 0x22]);
+        assert_eq!(i2c.i2c.config.read().get_packet_length(), 5);
+
+        i2c.transaction(
+            0x15,
+            &mut [Operation::Write(&[0x01]), Operation::Write(&[0x02, 0x03])],
+        )
+        .unwrap();
+        assert_eq!(i2c.i2c.config.read().get_packet_length(), 2);
+        assert_eq!(unsafe { (*i2c.i2c.0.get())[0x88 / 4] }, 0x0003_0201);
+    }
+
+    #[test]
+    fn i2c_unsupported_transactions() {
+        let mut i2c = MockI2c::new(0x4433_2211);
+        // Only a write may lead a read in one frame.
+        let mut buf = [0u8; 1];
+        assert_eq!(
+            i2c.transaction(
+                0x15,
+                &mut [Operation::Read(&mut buf), Operation::Write(&[0x01])],
+            ),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            i2c.transaction(
+                0x15,
+                &mut [
+                    Operation::Write(&[0x01]),
+                    Operation::Read(&mut buf),
+                    Operation::Write(&[0x02]),
+                ],
+            ),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(i2c.i2c.config.read(), FunctionConfig(0));
+        // Empty transaction does nothing.
+        i2c.transaction(0x15, &mut []).unwrap();
+        assert_eq!(i2c.i2c.config.read(), FunctionConfig(0));
+    }
+
+    #[test]
+    fn i2c_zero_length_operations() {
+        let mut i2c = MockI2c::new(0x4433_2211);
+        // Hardware cannot send address-only frames, so empty operations are skipped.
+        i2c.write(0x15, &[]).unwrap();
+        i2c.read(0x15, &mut []).unwrap();
+        assert_eq!(i2c.i2c.config.read(), FunctionConfig(0));
+
+        let mut buf = [0u8; 2];
+        i2c.write_read(0x15, &[], &mut buf).unwrap();
+        assert_eq!(buf, [0x11, 0x22]);
+        assert!(!i2c.i2c.config.read().is_sub_address_enabled());
+
+        i2c.write_read(0x15, &[0x01], &mut []).unwrap();
+        assert!(!i2c.i2c.config.read().is_read_direction());
+        assert_eq!(unsafe { (*i2c.i2c.0.get())[0x88 / 4] }, 0x0000_0001);
+
+        // Empty operations do not split runs.
+        let (mut a, mut b) = ([0u8; 1], [0u8; 1]);
+        i2c.transaction(
+            0x15,
+            &mut [
+                Operation::Read(&mut []),
+                Operation::Write(&[0x02]),
+                Operation::Write(&[]),
+                Operation::Read(&mut a),
+                Operation::Write(&[]),
+                Operation::Read(&mut b),
+            ],
+        )
+        .unwrap();
+        assert_eq!((a, b), ([0x11], [0x22]));
+        assert_eq!(i2c.i2c.config.read().get_packet_length(), 1);
+    }
+
+    #[test]
+    fn i2c_nack() {
+        let mut i2c = MockI2c::new(0);
+        i2c.i2c.set_interrupt_state(Interrupt::NackReceived);
+        assert_eq!(
+            i2c.write(0x15, &[0x11]),
+            Err(Error::NoAcknowledge(NoAcknowledgeSource::Address))
+        );
+        assert!(!i2c.i2c.config.read().is_master_enabled());
+        assert_eq!(
+            i2c.read(0x15, &mut [0u8; 1]),
+            Err(Error::NoAcknowledge(NoAcknowledgeSource::Address))
+        );
+        assert_eq!(
+            i2c.write_read(0x15, &[0x01], &mut [0u8; 1]),
+            Err(Error::NoAcknowledge(NoAcknowledgeSource::Unknown))
+        );
+    }
+
+    #[test]
+    fn i2c_bus_errors() {
+        let mut i2c = MockI2c::new(0);
+        i2c.i2c.set_interrupt_state(Interrupt::ArbitrationLost);
+        assert_eq!(i2c.write(0x15, &[0x11]), Err(Error::ArbitrationLoss));
+
+        let mut i2c = MockI2c::new(0);
+        i2c.i2c.set_interrupt_state(Interrupt::FifoError);
+        unsafe { (*i2c.i2c.0.get())[0x80 / 4] = 1 << 6 };
+        assert_eq!(i2c.read(0x15, &mut [0u8; 1]), Err(Error::FifoOverflow));
+        unsafe { (*i2c.i2c.0.get())[0x80 / 4] = 1 << 5 };
+        assert_eq!(i2c.write(0x15, &[0x11]), Err(Error::FifoUnderflow));
+
+        // Transfer never ends.
+        let mut i2c = MockI2c::new(0);
+        unsafe { (*i2c.i2c.0.get())[1] = 0 };
+        assert_eq!(i2c.write(0x15, &[0x11]), Err(Error::Bus));
+        assert!(!i2c.i2c.config.read().is_master_enabled());
+    }
+
+    fn dma_i2c<'b>(dma: &'b MockDma, fifo_read: u32) -> DmaI2c<'b, MockI2c, ((), ()), MockRequest> {
+        let tx = MockRequest {
+            id: 22,
+            address: 0x2000_a388,
+        };
+        let rx = MockRequest {
+            id: 23,
+            address: 0x2000_a38c,
+        };
+        MockI2c::new(fifo_read).with_dma((dma.channel(0), tx), (dma.channel(1), rx))
+    }
+
+    #[test]
+    fn struct_dma_i2c_buffer_alignment() {
+        static mut BUFFER: [u32; 4] = [0; 4];
+        let dma = MockDma::new();
+        let mut i2c = dma_i2c(&dma, 0);
+        assert!(i2c.i2c.fifo_config_0.read().is_dma_transmit_enabled());
+        let bytes = || unsafe { &mut *(addr_of_mut!(BUFFER) as *mut [u8; 16]) };
+        // Unaligned start, length not a multiple of 4, empty buffer and long sub-address.
+        assert!(i2c.write(0x15, &[], &bytes()[1..13]).is_err());
+        assert!(i2c.read(0x15, &[], &mut bytes()[..6]).is_err());
+        assert!(i2c.write(0x15, &[], &bytes()[..0]).is_err());
+        assert!(i2c.read(0x15, &[0; 5], &mut bytes()[..8]).is_err());
+        // Nothing was started.
+        assert!(!i2c.i2c.config.read().is_master_enabled());
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 0);
+    }
+
+    #[test]
+    fn struct_dma_i2c_chunked_write() {
+        static mut BUFFER: [u32; 65] = [0; 65];
+        let dma = MockDma::new();
+        let mut i2c = dma_i2c(&dma, 0);
+        let buffer = unsafe { &*(addr_of_mut!(BUFFER) as *const [u8; 260]) };
+        let ptr = buffer.as_ptr() as u32;
+        let mut transfer = i2c.write(0x15, &[0x01, 0x02], buffer).ok().unwrap();
+        // First packet carries sub-address and 256 bytes in 64 words.
+        let config = transfer.i2c.i2c.config.read();
+        assert!(config.is_master_enabled() && config.is_write_direction());
+        assert!(config.is_sub_address_enabled());
+        assert_eq!(
+            config.get_sub_address_byte_count(),
+            SubAddressByteCount::Two
+        );
+        assert_eq!(transfer.i2c.i2c.sub_address.read(), 0x0201);
+        assert_eq!(config.get_packet_length(), 255);
+        assert_eq!(dma.channel_reg(0, 0x00), ptr);
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 64);
+        assert!(!transfer.is_done());
+
+        // Second packet continues where DMA stopped, at sub-address 0x0102 + 256.
+        dma.complete(0);
+        assert!(!transfer.is_done());
+        let config = transfer.i2c.i2c.config.read();
+        assert!(config.is_master_enabled());
+        assert!(config.is_sub_address_enabled());
+        assert_eq!(
+            config.get_sub_address_byte_count(),
+            SubAddressByteCount::Two
+        );
+        assert_eq!(transfer.i2c.i2c.sub_address.read(), 0x0202);
+        assert_eq!(config.get_packet_length(), 3);
+        assert_eq!(dma.channel_reg(0, 0x00), ptr + 256);
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 1);
+
+        dma.complete(0);
+        let (ans, returned) = transfer.wait();
+        assert_eq!(ans, Ok(()));
+        assert_eq!(returned.as_ptr() as u32, ptr);
+        let config = i2c.i2c.config.read();
+        assert!(!config.is_master_enabled());
+        assert!(!config.is_sub_address_enabled());
+
+        let (i2c, _, _) = i2c.free();
+        assert!(!i2c.i2c.fifo_config_0.read().is_dma_transmit_enabled());
+    }
+
+    #[test]
+    fn struct_dma_i2c_drop() {
+        static mut BUFFER: [u32; 2] = [0; 2];
+        let dma = MockDma::new();
+        let mut i2c = dma_i2c(&dma, 0);
+        unsafe {
+            i2c.i2c.config.modify(|config| config.enable_sub_address());
+            i2c.i2c.sub_address.write(0x5a);
+        }
+        let buffer = unsafe { &mut *(addr_of_mut!(BUFFER) as *mut [u8; 8]) };
+        let transfer = i2c.read(0x15, &[0x01], buffer).ok().unwrap();
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 1);
+        assert_eq!(dma.channel_reg(1, 0x0c) & 0xfff, 2);
+        drop(transfer);
+        // Dropped transfer stops DMA and master before the buffer is released.
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 0);
+        let config = i2c.i2c.config.read();
+        assert!(!config.is_master_enabled());
+        assert!(config.is_sub_address_enabled());
+        assert_eq!(i2c.i2c.sub_address.read(), 0x5a);
+    }
+
+    #[test]
+    fn struct_dma_i2c_nack() {
+        static mut BUFFER: [u32; 1] = [0; 1];
+        let dma = MockDma::new();
+        let mut i2c = dma_i2c(&dma, 0);
+        let buffer = unsafe { &mut *(addr_of_mut!(BUFFER) as *mut [u8; 4]) };
+        let mut transfer = i2c.read(0x15, &[], buffer).ok().unwrap();
+        transfer
+            .i2c
+            .i2c
+            .set_interrupt_state(Interrupt::NackReceived);
+        assert!(transfer.is_done());
+        let (ans, _) = transfer.wait();
+        assert_eq!(ans, Err(Error::NoAcknowledge(NoAcknowledgeSource::Address)));
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 0);
+        assert!(!i2c.i2c.config.read().is_master_enabled());
+    }
+
+    /// Poll a future which is expected to complete without waiting on interrupts.
+    fn poll_once<F: core::future::Future>(f: F) -> F::Output {
+        let mut f = core::pin::pin!(f);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        match f.as_mut().poll(&mut cx) {
+            core::task::Poll::Ready(ans) => ans,
+            core::task::Poll::Pending => panic!("future is pending"),
+        }
+    }
+
+    #[test]
+    fn async_i2c_transaction() {
+        let state = I2cState::new();
+        let mut i2c = MockI2c::new(0x4433_2211).into_async(&state);
+        let mut buf = [0u8; 2];
+        poll_once(i2c.write_read(0x15, &[0x01], &mut buf)).unwrap();
+        assert_eq!(buf, [0x11, 0x22]);
+        poll_once(i2c.write(0x15, &[0x11, 0x22, 0x33])).unwrap();
+        let config = i2c.i2c.config.read();
+        assert!(!config.is_read_direction());
+        assert_eq!(config.get_packet_length(), 2);
+        // Interrupts are masked again once the transaction completes.
+        let mask = i2c.i2c.interrupt_mask.read();
+        assert!(mask.is_interrupt_masked(Interrupt::TransferEnd));
+        assert!(mask.is_interrupt_masked(Interrupt::NackReceived));
+
+        i2c.i2c.set_interrupt_state(Interrupt::NackReceived);
+        assert_eq!(
+            poll_once(i2c.read(0x15, &mut buf)),
+            Err(Error::NoAcknowledge(NoAcknowledgeSource::Address))
+        );
+    }
+
+    #[test]
+    fn async_i2c_cancel() {
+        let state = I2cState::new();
+        let mut i2c = MockI2c::new(0);
+        // Transfer has not ended yet.
+        unsafe { (*i2c.i2c.0.get())[1] = 0 };
+        i2c.enable_sub_address(0x5a);
+        let mut i2c = i2c.into_async(&state);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut buf = [0u8; 1];
+        {
+            let mut read = core::pin::pin!(i2c.write_read(0x15, &[0x01, 0x02], &mut buf));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+        }
+        // Dropped transaction stops the master and puts sub-address back.
+        let config = i2c.i2c.config.read();
+        assert!(!config.is_master_enabled());
+        assert!(config.is_sub_address_enabled());
+        assert_eq!(
+            config.get_sub_address_byte_count(),
+            SubAddressByteCount::One
+        );
+        assert_eq!(i2c.i2c.sub_address.read(), 0x5a);
+        let mask = i2c.i2c.interrupt_mask.read();
+        assert!(mask.is_interrupt_masked(Interrupt::TransferEnd));
+        assert!(mask.is_interrupt_masked(Interrupt::NackReceived));
+    }
+
+    #[test]
+    fn async_i2c_on_interrupt() {
+        let state = I2cState::new();
+        let i2c = MockI2c::new(0);
+        // Transfer has not ended yet.
+        unsafe { (*i2c.i2c.0.get())[1] = 0 };
+        let mut i2c = i2c.into_async(&state);
+        // Mock registers move together with the peripheral, point the state to the new place.
+        let regs = i2c.i2c.0.get() as *mut u32;
+        state.ref_to_i2c.store(regs as usize, Ordering::Release);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut buf = [0u8; 1];
+        let mut read = core::pin::pin!(i2c.read(0x15, &mut buf));
+        assert!(read.as_mut().poll(&mut cx).is_pending());
+        // Waiting on end of transfer unmasks it together with error interrupts.
+        let i2c = unsafe { &*(regs as *const RegisterBlock) };
+        let mask = i2c.interrupt_mask.read();
+        assert!(!mask.is_interrupt_masked(Interrupt::TransferEnd));
+        assert!(!mask.is_interrupt_masked(Interrupt::ArbitrationLost));
+        assert!(mask.is_interrupt_masked(Interrupt::TransmitFifoReady));
+        unsafe { *regs.add(1) |= 1 << (Interrupt::ArbitrationLost as u32) };
+        state.on_interrupt();
+        assert!(
+            i2c.interrupt_mask
+                .read()
+                .is_interrupt_masked(Interrupt::ArbitrationLost)
+        );
+        assert_eq!(
+            read.as_mut().poll(&mut cx),
+            core::task::Poll::Ready(Err(Error::ArbitrationLoss))
+        );
+    }
 }
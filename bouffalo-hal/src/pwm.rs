@@ -5,8 +5,12 @@ use crate::glb::{
     v2::{PwmSignal0, PwmSignal1},
 };
 use crate::gpio::{self, Alternate};
+use core::cmp::max;
+use core::future::poll_fn;
 use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::Poll;
 use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
@@ -136,6 +140,7 @@ impl GroupConfig {
             6 => AdcTriggerSource::Channel3LowThreashold,
             7 => AdcTriggerSource::Channel3HighThreashold,
             8 => AdcTriggerSource::PeriodEnd,
+            15 => AdcTriggerSource::Disabled,
             _ => unreachable!(),
         }
     }
@@ -249,6 +254,32 @@ pub enum AdcTriggerSource {
     Channel3LowThreashold = 6,
     Channel3HighThreashold = 7,
     PeriodEnd = 8,
+    Disabled = 15,
+}
+
+impl AdcTriggerSource {
+    /// ADC trigger source on low threshold of channel `idx`.
+    #[inline]
+    pub const fn low_threshold(idx: usize) -> Self {
+        match idx {
+            0 => AdcTriggerSource::Channel0LowThreashold,
+            1 => AdcTriggerSource::Channel1LowThreashold,
+            2 => AdcTriggerSource::Channel2LowThreashold,
+            3 => AdcTriggerSource::Channel3LowThreashold,
+            _ => panic!("PWM group has 4 channels"),
+        }
+    }
+    /// ADC trigger source on high threshold of channel `idx`.
+    #[inline]
+    pub const fn high_threshold(idx: usize) -> Self {
+        match idx {
+            0 => AdcTriggerSource::Channel0HighThreashold,
+            1 => AdcTriggerSource::Channel1HighThreashold,
+            2 => AdcTriggerSource::Channel2HighThreashold,
+            3 => AdcTriggerSource::Channel3HighThreashold,
+            _ => panic!("PWM group has 4 channels"),
+        }
+    }
 }
 
 /// Polarity.
@@ -535,6 +566,31 @@ pub enum Interrupt {
     RepeatCount = 10,
 }
 
+impl Interrupt {
+    /// Interrupt on low threshold of channel `idx`.
+    #[inline]
+    pub const fn low_threshold(idx: usize) -> Self {
+        match idx {
+            0 => Interrupt::Channel0LowThreashold,
+            1 => Interrupt::Channel1LowThreashold,
+            2 => Interrupt::Channel2LowThreashold,
+            3 => Interrupt::Channel3LowThreashold,
+            _ => panic!("PWM group has 4 channels"),
+        }
+    }
+    /// Interrupt on high threshold of channel `idx`.
+    #[inline]
+    pub const fn high_threshold(idx: usize) -> Self {
+        match idx {
+            0 => Interrupt::Channel0HighThreashold,
+            1 => Interrupt::Channel1HighThreashold,
+            2 => Interrupt::Channel2HighThreashold,
+            3 => Interrupt::Channel3HighThreashold,
+            _ => panic!("PWM group has 4 channels"),
+        }
+    }
+}
+
 /// Interrupt state register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -705,15 +761,21 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
     ///
     /// Clock settings would affect all the channels in the PWM group.
     #[inline]
-    pub fn set_clock(&mut self, frequency: Hertz, source: ClockSource, clocks: &Clocks) {
-        let source_freq = match source {
-            ClockSource::Xclk => clocks.xclk(),
-            ClockSource::Bclk => todo!(),
-            ClockSource::F32kClk => todo!(),
-        };
-        let clock_divisor = source_freq.0 / frequency.0;
-        if !(1..=65535).contains(&clock_divisor) {
-            panic!("impossible frequency");
+    pub fn set_clock(
+        &mut self,
+        frequency: Hertz,
+        source: ClockSource,
+        clocks: &Clocks,
+    ) -> Result<(), ConfigError> {
+        if frequency.0 == 0 {
+            return Err(ConfigError::FrequencyTooLow);
+        }
+        let clock_divisor = source_frequency(source, clocks).0 / frequency.0;
+        if clock_divisor == 0 {
+            return Err(ConfigError::FrequencyTooHigh);
+        }
+        if clock_divisor > 65535 {
+            return Err(ConfigError::FrequencyTooLow);
         }
         unsafe {
             self.pwm.group[I].group_config.modify(|val| {
@@ -721,6 +783,66 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
                     .set_clock_divide(clock_divisor as u16)
             })
         };
+        Ok(())
+    }
+    /// Configure output frequency for current PWM group.
+    ///
+    /// Picks the clock source, clock divide and period nearest to the target frequency, with
+    /// maximum duty cycle of at least `resolution`. Among equally accurate settings, the one
+    /// with finest duty cycle steps is used. Returns the actual output frequency.
+    ///
+    /// Maximum duty cycle of all the channels in the PWM group is overwritten.
+    #[inline]
+    pub fn set_frequency(
+        &mut self,
+        frequency: Hertz,
+        resolution: u16,
+        clocks: &Clocks,
+    ) -> Result<Hertz, ConfigError> {
+        let mut best: Option<(ClockSource, Timing)> = None;
+        let mut error = None;
+        for source in [ClockSource::Xclk, ClockSource::Bclk, ClockSource::F32kClk] {
+            match pwm_timing(source_frequency(source, clocks), frequency, resolution) {
+                Ok(timing) => match best {
+                    Some((_, prev)) if !timing.is_better_than(&prev, frequency) => {}
+                    _ => best = Some((source, timing)),
+                },
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        match best {
+            Some((source, timing)) => Ok(self.apply_timing(source, timing)),
+            None => Err(error.unwrap()),
+        }
+    }
+    /// Configure output frequency for current PWM group from given clock source.
+    ///
+    /// Works like `set_frequency`, but keeps to one clock source; e.g. the 32-kHz clock
+    /// keeps running in low power modes for LED dimming.
+    #[inline]
+    pub fn set_frequency_from(
+        &mut self,
+        frequency: Hertz,
+        resolution: u16,
+        source: ClockSource,
+        clocks: &Clocks,
+    ) -> Result<Hertz, ConfigError> {
+        let timing = pwm_timing(source_frequency(source, clocks), frequency, resolution)?;
+        Ok(self.apply_timing(source, timing))
+    }
+    #[inline]
+    fn apply_timing(&mut self, source: ClockSource, timing: Timing) -> Hertz {
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.set_clock_source(source).set_clock_divide(timing.divide));
+            self.pwm.group[I]
+                .period_config
+                .modify(|val| val.set_period(timing.period));
+        }
+        timing.output
     }
     /// Configure maximum duty cycle for this PWM group.
     #[inline]
@@ -755,6 +877,318 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
             core::hint::spin_loop();
         }
     }
+    /// Enable external break input for current PWM group.
+    ///
+    /// While the break input is at `polarity` level, all the channels of this group
+    /// drive their break state.
+    #[inline]
+    pub fn enable_external_break(&mut self, polarity: Polarity) {
+        unsafe {
+            self.pwm.group[I].group_config.modify(|val| {
+                val.set_external_break_polarity(polarity)
+                    .enable_external_break()
+            })
+        }
+    }
+    /// Disable external break input for current PWM group.
+    #[inline]
+    pub fn disable_external_break(&mut self) {
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.disable_external_break())
+        }
+    }
+    /// Force all the channels of current PWM group into break state.
+    #[inline]
+    pub fn software_break(&mut self) {
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.enable_software_break())
+        }
+    }
+    /// Release software break of current PWM group.
+    #[inline]
+    pub fn release_software_break(&mut self) {
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.disable_software_break())
+        }
+    }
+    /// Check if software break of current PWM group is active.
+    #[inline]
+    pub fn is_software_break(&self) -> bool {
+        self.pwm.group[I]
+            .group_config
+            .read()
+            .is_software_break_enabled()
+    }
+    /// Enable and unmask interrupt event of current PWM group.
+    #[inline]
+    pub fn enable_interrupt(&mut self, interrupt: Interrupt) {
+        unsafe {
+            self.pwm.group[I]
+                .interrupt_enable
+                .modify(|val| val.enable_interrupt(interrupt));
+            self.pwm.group[I]
+                .interrupt_mask
+                .modify(|val| val.unmask_interrupt(interrupt));
+        }
+    }
+    /// Disable and mask interrupt event of current PWM group.
+    #[inline]
+    pub fn disable_interrupt(&mut self, interrupt: Interrupt) {
+        unsafe {
+            self.pwm.group[I]
+                .interrupt_mask
+                .modify(|val| val.mask_interrupt(interrupt));
+            self.pwm.group[I]
+                .interrupt_enable
+                .modify(|val| val.disable_interrupt(interrupt));
+        }
+    }
+    /// Check if interrupt event of current PWM group has happened.
+    ///
+    /// `ExternalBreak` signals a fault on the external break input.
+    #[inline]
+    pub fn has_interrupt(&self, interrupt: Interrupt) -> bool {
+        self.pwm.group[I]
+            .interrupt_state
+            .read()
+            .has_interrupt(interrupt)
+    }
+    /// Clear interrupt event flag of current PWM group.
+    #[inline]
+    pub fn clear_interrupt(&mut self, interrupt: Interrupt) {
+        unsafe {
+            self.pwm.group[I]
+                .interrupt_clear
+                .write(InterruptClear(0).clear_interrupt(interrupt))
+        }
+    }
+    /// Trigger ADC conversion on a PWM event of current group.
+    ///
+    /// Use a threshold of a channel to sample at a chosen edge, e.g. in the middle of a
+    /// half-bridge on-time for synchronous current sensing.
+    /// The ADC accepts these triggers after `Adc::enable_pwm_trigger`.
+    #[inline]
+    pub fn set_adc_trigger(&mut self, source: AdcTriggerSource) {
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.set_adc_trigger_source(source))
+        }
+    }
+    /// Stop triggering ADC conversions from current group.
+    #[inline]
+    pub fn disable_adc_trigger(&mut self) {
+        self.set_adc_trigger(AdcTriggerSource::Disabled)
+    }
+    /// Wait for interrupt event of current PWM group asynchronously.
+    ///
+    /// `state` must be notified with `PwmState::on_interrupt` in the PWM interrupt handler.
+    /// The event flag is cleared when this function returns.
+    #[inline]
+    pub async fn wait_for_interrupt(&mut self, state: &PwmState, interrupt: Interrupt) {
+        pwm_wait_async(&self.pwm.group[I], state, interrupt).await
+    }
+    /// Wait for end of current period asynchronously.
+    #[inline]
+    pub async fn wait_period_end(&mut self, state: &PwmState) {
+        self.wait_for_interrupt(state, Interrupt::PeriodEnd).await
+    }
+}
+
+/// Waker as the state for an async/await PWM group.
+///
+/// Each PWM group waiting for interrupts needs its own state.
+#[derive(Debug)]
+pub struct PwmState {
+    waker: atomic_waker::AtomicWaker,
+    ref_to_group: AtomicUsize,
+}
+
+impl PwmState {
+    /// Creates the waker state for a PWM group.
+    #[inline]
+    pub const fn new() -> PwmState {
+        PwmState {
+            waker: atomic_waker::AtomicWaker::new(),
+            ref_to_group: AtomicUsize::new(0),
+        }
+    }
+    /// Use this state to handle interrupt.
+    ///
+    /// Pending interrupts are masked and the waiting task is woken.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let group = self.ref_to_group.load(Ordering::Acquire) as *const Group;
+        if group.is_null() {
+            return;
+        }
+        let group = unsafe { &*group };
+        let state = group.interrupt_state.read();
+        let mask = group.interrupt_mask.read();
+        // Interrupt state and mask registers share the same bit layout.
+        unsafe { group.interrupt_mask.write(InterruptMask(mask.0 | state.0)) };
+        self.waker.wake();
+    }
+}
+
+impl Default for PwmState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wait until `interrupt` happens on `group`, then clear its flag.
+#[inline]
+async fn pwm_wait_async(group: &Group, state: &PwmState, interrupt: Interrupt) {
+    state
+        .ref_to_group
+        .store(group as *const _ as usize, Ordering::Release);
+    unsafe {
+        group
+            .interrupt_clear
+            .write(InterruptClear(0).clear_interrupt(interrupt));
+        group
+            .interrupt_enable
+            .modify(|val| val.enable_interrupt(interrupt));
+    }
+    let _guard = DisableOnDrop(group, interrupt);
+    poll_fn(|cx| {
+        state.waker.register(cx.waker());
+        if group.interrupt_state.read().has_interrupt(interrupt) {
+            unsafe {
+                group
+                    .interrupt_mask
+                    .modify(|val| val.mask_interrupt(interrupt));
+                group
+                    .interrupt_clear
+                    .write(InterruptClear(0).clear_interrupt(interrupt));
+            }
+            Poll::Ready(())
+        } else {
+            unsafe {
+                group
+                    .interrupt_mask
+                    .modify(|val| val.unmask_interrupt(interrupt))
+            };
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// Disables a PWM group interrupt when a wait on it ends or is abandoned.
+struct DisableOnDrop<'a>(&'a Group, Interrupt);
+
+impl Drop for DisableOnDrop<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            self.0
+                .interrupt_mask
+                .modify(|val| val.mask_interrupt(self.1));
+            self.0
+                .interrupt_enable
+                .modify(|val| val.disable_interrupt(self.1));
+        }
+    }
+}
+
+/// Errors on PWM configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Impossibly high frequency for current clock source.
+    FrequencyTooHigh,
+    /// Impossibly low frequency for current clock source.
+    FrequencyTooLow,
+    /// Requested duty cycle resolution does not fit in one period of this frequency.
+    ResolutionTooHigh,
+    /// Dead time longer than 255 ticks of the PWM group clock.
+    DeadTimeTooLong,
+}
+
+/// Clock divide and period of one PWM group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Timing {
+    divide: u16,
+    period: u16,
+    /// Source clock frequency.
+    source: Hertz,
+    /// Resulting output frequency, rounded down.
+    output: Hertz,
+}
+
+impl Timing {
+    /// Checks if this timing is nearer to the target frequency, or equally near with
+    /// finer duty cycle steps.
+    #[inline]
+    fn is_better_than(&self, other: &Timing, target: Hertz) -> bool {
+        // Compare |source / ticks - target| across timings without division.
+        let ticks = |t: &Timing| t.divide as u128 * t.period as u128;
+        let error = |t: &Timing| (t.source.0 as u128).abs_diff(target.0 as u128 * ticks(t));
+        let (lhs, rhs) = (error(self) * ticks(other), error(other) * ticks(self));
+        lhs < rhs || (lhs == rhs && self.period > other.period)
+    }
+}
+
+#[inline]
+fn source_frequency(source: ClockSource, clocks: &Clocks) -> Hertz {
+    match source {
+        ClockSource::Xclk => clocks.xclk(),
+        ClockSource::Bclk => clocks.bclk(),
+        ClockSource::F32kClk => clocks.f32k_clk(),
+    }
+}
+
+/// Compute clock divide and period for the output frequency.
+///
+/// Clock divide is kept as small as possible, so the period, i.e. maximum duty cycle,
+/// is as large as possible.
+#[inline]
+fn pwm_timing(source: Hertz, frequency: Hertz, resolution: u16) -> Result<Timing, ConfigError> {
+    if frequency.0 == 0 {
+        return Err(ConfigError::FrequencyTooLow);
+    }
+    // Source clock ticks in one output period, rounded to nearest.
+    let total = (source.0 as u64 + frequency.0 as u64 / 2) / frequency.0 as u64;
+    if total == 0 {
+        return Err(ConfigError::FrequencyTooHigh);
+    }
+    let divide = total.div_ceil(65535);
+    if divide > 65535 {
+        return Err(ConfigError::FrequencyTooLow);
+    }
+    let period = (total + divide / 2) / divide;
+    if period < max(resolution, 1) as u64 {
+        return Err(ConfigError::ResolutionTooHigh);
+    }
+    Ok(Timing {
+        divide: divide as u16,
+        period: period as u16,
+        source,
+        output: Hertz((source.0 as u64 / (divide * period)) as u32),
+    })
+}
+
+/// Compute dead time in ticks of the PWM group counter clock, rounded up.
+#[inline]
+fn dead_time_ticks(nanoseconds: u32, counter: Hertz) -> Result<u8, ConfigError> {
+    let ticks = (nanoseconds as u64 * counter.0 as u64).div_ceil(1_000_000_000);
+    u8::try_from(ticks).map_err(|_| ConfigError::DeadTimeTooLong)
+}
+
+/// Counter clock frequency of a PWM group from its clock source and divide.
+#[inline]
+fn counter_frequency(group: &Group, clocks: &Clocks) -> Hertz {
+    let config = group.group_config.read();
+    let source = source_frequency(config.clock_source(), clocks);
+    Hertz(source.0 / max(config.clock_divide(), 1) as u32)
 }
 
 /// Pulse Width Modulation channel.
@@ -800,6 +1234,60 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize> Chan
             _polarity: PhantomData,
         }
     }
+    /// Set dead time inserted between positive and negative signals, in nanoseconds.
+    ///
+    /// Dead time is counted in PWM group clock ticks; set it again after changing group clock.
+    #[inline]
+    pub fn set_dead_time(&mut self, nanoseconds: u32, clocks: &Clocks) -> Result<(), ConfigError> {
+        let ticks = dead_time_ticks(nanoseconds, counter_frequency(&self.pwm.group[I], clocks))?;
+        unsafe {
+            self.pwm.group[I]
+                .dead_time
+                .modify(|val| val.set_channel(J, ticks))
+        };
+        Ok(())
+    }
+    /// Wrap current channel as a complementary half-bridge pair with high and low side pins.
+    ///
+    /// Both outputs stay at their inactive level until `enable_output` is called, and return
+    /// to it on break. This function statically checks that the pins are the positive and
+    /// negative signals of current channel.
+    #[inline]
+    pub fn half_bridge_pins<'a, 'b, const N1: usize, const N2: usize, const F: usize>(
+        mut self,
+        high_side: Alternate<'a, N1, gpio::Pwm<F>>,
+        low_side: Alternate<'b, N2, gpio::Pwm<F>>,
+        config: HalfBridgeConfig,
+        clocks: &Clocks,
+    ) -> Result<
+        HalfBridge<Self, Alternate<'a, N1, gpio::Pwm<F>>, Alternate<'b, N2, gpio::Pwm<F>>>,
+        ConfigError,
+    >
+    where
+        Alternate<'a, N1, gpio::Pwm<F>>: HasPwmSignal<S, I, J, Positive>,
+        Alternate<'b, N2, gpio::Pwm<F>>: HasPwmSignal<S, I, J, Negative>,
+    {
+        self.set_dead_time(config.dead_time, clocks)?;
+        let high_off = inactive_level(config.high_side_polarity);
+        let low_off = inactive_level(config.low_side_polarity);
+        unsafe {
+            self.pwm.group[I].channel_config.modify(|val| {
+                val.disable_positive_output(J)
+                    .disable_negative_output(J)
+                    .set_positive_polarity(J, config.high_side_polarity)
+                    .set_negative_polarity(J, config.low_side_polarity)
+                    .set_positive_idle_state(J, high_off)
+                    .set_negative_idle_state(J, low_off)
+                    .set_positive_break_state(J, high_off)
+                    .set_negative_break_state(J, low_off)
+            })
+        };
+        Ok(HalfBridge {
+            channel: self,
+            high_side,
+            low_side,
+        })
+    }
 }
 
 /// Pulse Width Modulation external break signal.
@@ -870,6 +1358,128 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize>
     }
 }
 
+/// Half-bridge pair configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalfBridgeConfig {
+    /// Dead time between switching off one side and switching on the other, in nanoseconds.
+    pub dead_time: u32,
+    /// Level that switches on the high side transistor driver.
+    pub high_side_polarity: Polarity,
+    /// Level that switches on the low side transistor driver.
+    pub low_side_polarity: Polarity,
+}
+
+impl HalfBridgeConfig {
+    /// Create a half-bridge configuration with dead time and active high drivers.
+    #[inline]
+    pub const fn new(dead_time: u32) -> Self {
+        Self {
+            dead_time,
+            high_side_polarity: Polarity::ActiveHigh,
+            low_side_polarity: Polarity::ActiveHigh,
+        }
+    }
+    /// Set dead time in nanoseconds.
+    #[inline]
+    pub const fn set_dead_time(self, dead_time: u32) -> Self {
+        Self { dead_time, ..self }
+    }
+    /// Set active levels of high side and low side drivers.
+    #[inline]
+    pub const fn set_polarity(self, high_side: Polarity, low_side: Polarity) -> Self {
+        Self {
+            high_side_polarity: high_side,
+            low_side_polarity: low_side,
+            ..self
+        }
+    }
+}
+
+#[inline]
+const fn inactive_level(polarity: Polarity) -> ElectricLevel {
+    match polarity {
+        Polarity::ActiveHigh => ElectricLevel::Low,
+        Polarity::ActiveLow => ElectricLevel::High,
+    }
+}
+
+/// Complementary half-bridge pair on one PWM channel.
+///
+/// The high side follows the duty cycle, and the low side is its complement with dead time
+/// inserted on both edges.
+pub struct HalfBridge<CHANNEL, HIGH, LOW> {
+    channel: CHANNEL,
+    high_side: HIGH,
+    low_side: LOW,
+}
+
+impl<CHANNEL, HIGH, LOW> HalfBridge<CHANNEL, HIGH, LOW> {
+    /// Release the channel and the pins.
+    #[inline]
+    pub fn free(self) -> (CHANNEL, HIGH, LOW) {
+        (self.channel, self.high_side, self.low_side)
+    }
+}
+
+impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize, HIGH, LOW>
+    HalfBridge<Channel<PWM, S, I, J>, HIGH, LOW>
+{
+    /// Start switching both sides of the pair.
+    #[inline]
+    pub fn enable_output(&mut self) {
+        unsafe {
+            self.channel.pwm.group[I]
+                .channel_config
+                .modify(|val| val.enable_positive_output(J).enable_negative_output(J))
+        }
+    }
+    /// Switch off both sides of the pair.
+    #[inline]
+    pub fn disable_output(&mut self) {
+        unsafe {
+            self.channel.pwm.group[I]
+                .channel_config
+                .modify(|val| val.disable_positive_output(J).disable_negative_output(J))
+        }
+    }
+}
+
+impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize, HIGH, LOW>
+    embedded_hal::pwm::ErrorType for HalfBridge<Channel<PWM, S, I, J>, HIGH, LOW>
+{
+    type Error = core::convert::Infallible;
+}
+
+impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize, HIGH, LOW>
+    embedded_hal::pwm::SetDutyCycle for HalfBridge<Channel<PWM, S, I, J>, HIGH, LOW>
+{
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.channel.max_duty_cycle()
+    }
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.channel.set_duty_cycle(duty)
+    }
+}
+
+impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize, HIGH, LOW> Deref
+    for HalfBridge<Channel<PWM, S, I, J>, HIGH, LOW>
+{
+    type Target = Channel<PWM, S, I, J>;
+    fn deref(&self) -> &Self::Target {
+        &self.channel
+    }
+}
+
+impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize, HIGH, LOW> DerefMut
+    for HalfBridge<Channel<PWM, S, I, J>, HIGH, LOW>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.channel
+    }
+}
+
 /// Wrapped GPIO pin with PWM channel feature.
 ///
 /// `PwmPin` implements both `pwm::SetDutyCycle` and `digital::OutputPin` traits.
@@ -1185,11 +1795,12 @@ mod gpio_impls {
 #[cfg(test)]
 mod tests {
     use super::{
-        AdcTriggerSource, ChannelConfig, ClockSource, DeadTime, ElectricLevel, Group, GroupConfig,
-        Interrupt, InterruptClear, InterruptConfig, InterruptEnable, InterruptMask, InterruptState,
-        PeriodConfig, Polarity, RegisterBlock, StopMode, Threshold,
+        AdcTriggerSource, ChannelConfig, ClockSource, ConfigError, DeadTime, ElectricLevel, Group,
+        GroupConfig, Interrupt, InterruptClear, InterruptConfig, InterruptEnable, InterruptMask,
+        InterruptState, PeriodConfig, Polarity, RegisterBlock, StopMode, Threshold, pwm_timing,
     };
     use core::mem::offset_of;
+    use embedded_time::rate::Hertz;
 
     #[test]
     fn struct_register_block_offset() {
@@ -1300,6 +1911,9 @@ mod tests {
         val = val.set_adc_trigger_source(AdcTriggerSource::PeriodEnd);
         assert_eq!(val.adc_trigger_source(), AdcTriggerSource::PeriodEnd);
         assert_eq!(AdcTriggerSource::PeriodEnd as u32, 0x00000008);
+        val = val.set_adc_trigger_source(AdcTriggerSource::Disabled);
+        assert_eq!(val.adc_trigger_source(), AdcTriggerSource::Disabled);
+        assert_eq!(AdcTriggerSource::Disabled as u32, 0x0000000F);
 
         val = GroupConfig(0x0);
         val = val.enable_software_break();
@@ -1524,4 +2138,140 @@ mod tests {
             assert_eq!(val.0, 0x00000000 << idx);
         }
     }
+
+    #[test]
+    fn function_pwm_timing() {
+        let xclk = Hertz(40_000_000);
+        let timing = pwm_timing(xclk, Hertz(1_000), 100).unwrap();
+        assert_eq!((timing.divide, timing.period), (1, 40000));
+        assert_eq!(timing.output, Hertz(1_000));
+
+        let timing = pwm_timing(xclk, Hertz(10), 100).unwrap();
+        assert_eq!((timing.divide, timing.period), (62, 64516));
+        assert_eq!(timing.output, Hertz(10));
+
+        let timing = pwm_timing(Hertz(32_000), Hertz(100), 255).unwrap();
+        assert_eq!((timing.divide, timing.period), (1, 320));
+        assert_eq!(timing.output, Hertz(100));
+
+        let timing = pwm_timing(xclk, Hertz(30_000_000), 0).unwrap();
+        assert_eq!((timing.divide, timing.period), (1, 1));
+        assert_eq!(
+            pwm_timing(xclk, Hertz(30_000_000), 2),
+            Err(ConfigError::ResolutionTooHigh)
+        );
+        assert_eq!(
+            pwm_timing(Hertz(32_000), Hertz(1_000), 255),
+            Err(ConfigError::ResolutionTooHigh)
+        );
+        assert_eq!(
+            pwm_timing(xclk, Hertz(100_000_000), 1),
+            Err(ConfigError::FrequencyTooHigh)
+        );
+        assert_eq!(
+            pwm_timing(xclk, Hertz(0), 1),
+            Err(ConfigError::FrequencyTooLow)
+        );
+    }
+
+    #[test]
+    fn function_timing_is_better_than() {
+        let target = Hertz(1_000);
+        let xclk = pwm_timing(Hertz(40_000_000), target, 1).unwrap();
+        let f32k = pwm_timing(Hertz(32_000), target, 1).unwrap();
+        assert!(xclk.is_better_than(&f32k, target));
+        assert!(!f32k.is_better_than(&xclk, target));
+
+        let target = Hertz(3_000_000);
+        let xclk = pwm_timing(Hertz(40_000_000), target, 1).unwrap();
+        let bclk = pwm_timing(Hertz(80_000_000), target, 1).unwrap();
+        assert_eq!(xclk.period, 13);
+        assert_eq!(bclk.period, 27);
+        assert!(bclk.is_better_than(&xclk, target));
+    }
+
+    #[test]
+    fn function_dead_time_ticks() {
+        use super::{ConfigError, dead_time_ticks};
+        use embedded_time::rate::Hertz;
+
+        assert_eq!(dead_time_ticks(0, Hertz(40_000_000)), Ok(0));
+        assert_eq!(dead_time_ticks(100, Hertz(40_000_000)), Ok(4));
+        assert_eq!(dead_time_ticks(110, Hertz(40_000_000)), Ok(5));
+        assert_eq!(dead_time_ticks(3187, Hertz(80_000_000)), Ok(255));
+        assert_eq!(
+            dead_time_ticks(3200, Hertz(80_000_000)),
+            Err(ConfigError::DeadTimeTooLong)
+        );
+        assert_eq!(
+            dead_time_ticks(u32::MAX, Hertz(80_000_000)),
+            Err(ConfigError::DeadTimeTooLong)
+        );
+    }
+
+    #[test]
+    fn function_pwm_wait_async() {
+        use super::{Group, Interrupt, PwmState, pwm_wait_async};
+        use core::cell::UnsafeCell;
+        use core::future::Future;
+        use core::sync::atomic::Ordering;
+
+        let regs = UnsafeCell::new([0u32; 0x40 / 4]);
+        let ptr = regs.get() as *mut u32;
+        let group = unsafe { &*(ptr as *const Group) };
+        let state = PwmState::new();
+        // Unregistered state ignores interrupts.
+        state.on_interrupt();
+
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut wait = core::pin::pin!(pwm_wait_async(group, &state, Interrupt::PeriodEnd));
+        assert!(wait.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(state.ref_to_group.load(Ordering::Acquire), ptr as usize);
+        assert!(
+            group
+                .interrupt_enable
+                .read()
+                .is_interrupt_enabled(Interrupt::PeriodEnd)
+        );
+        assert!(
+            !group
+                .interrupt_mask
+                .read()
+                .is_interrupt_masked(Interrupt::PeriodEnd)
+        );
+        // Period end and channel 1 low threshold events happen.
+        unsafe { *ptr.add(0x20 / 4) = (1 << 8) | (1 << 2) };
+        state.on_interrupt();
+        let mask = group.interrupt_mask.read();
+        assert!(mask.is_interrupt_masked(Interrupt::PeriodEnd));
+        assert!(mask.is_interrupt_masked(Interrupt::low_threshold(1)));
+        assert!(!mask.is_interrupt_masked(Interrupt::high_threshold(1)));
+        assert!(wait.as_mut().poll(&mut cx).is_ready());
+        // Interrupt is disabled once the wait ends.
+        let enable = group.interrupt_enable.read();
+        assert!(!enable.is_interrupt_enabled(Interrupt::PeriodEnd));
+    }
+
+    #[test]
+    fn function_pwm_wait_async_cancel() {
+        use super::{Group, Interrupt, PwmState, pwm_wait_async};
+        use core::cell::UnsafeCell;
+        use core::future::Future;
+
+        let regs = UnsafeCell::new([0u32; 0x40 / 4]);
+        let group = unsafe { &*(regs.get() as *const Group) };
+        let state = PwmState::new();
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        {
+            let mut wait = core::pin::pin!(pwm_wait_async(group, &state, Interrupt::PeriodEnd));
+            assert!(wait.as_mut().poll(&mut cx).is_pending());
+            let enable = group.interrupt_enable.read();
+            assert!(enable.is_interrupt_enabled(Interrupt::PeriodEnd));
+        }
+        // Abandoned wait disables and masks its interrupt.
+        let enable = group.interrupt_enable.read();
+        assert!(!enable.is_interrupt_enabled(Interrupt::PeriodEnd));
+        let mask = group.interrupt_mask.read();
+        assert!(mask.is_interrupt_masked(Interrupt::PeriodEnd));
+    }
 }
@@ -4,7 +4,9 @@ use super::pad::Pads;
 use super::register::{
     AutoCMDMode, BusVoltage, ClkGenMode, CmdType, DataTransferMode, DmaMode, RegisterBlock,
 };
-use crate::dma::{LliPool, LliTransfer, UntypedChannel};
+use crate::dma::{
+    LliPool, LliTransfer, UntypedChannel, clean_dcache, flush_dcache, invalidate_dcache,
+};
 use crate::glb;
 use core::ops::Deref;
 use core::sync::atomic::{Ordering, fence};
@@ -145,6 +147,7 @@ impl<'a, SDH: Deref<Target = RegisterBlock>, PADS, CH: Deref<Target = UntypedCha
             }];
 
             self.dma_channel.lli_reload(rx_lli_pool, 1, rx_transfer, 1);
+            flush_dcache(val.as_ptr() as usize, val.len());
             self.dma_channel.start();
 
             while self.dma_channel.is_busy() {
@@ -155,6 +158,7 @@ impl<'a, SDH: Deref<Target = RegisterBlock>, PADS, CH: Deref<Target = UntypedCha
 
             // FIXME modify to a proper fence
             fence(Ordering::SeqCst);
+            unsafe { invalidate_dcache(val.as_ptr() as usize, val.len()) };
 
             block[j * 4 + 0] = val[0];
             block[j * 4 + 1] = val[1];
@@ -214,6 +218,7 @@ impl<'a, SDH: Deref<Target = RegisterBlock>, PADS, CH: Deref<Target = UntypedCha
             }];
 
             self.dma_channel.lli_reload(tx_lli_pool, 1, tx_transfer, 1);
+            clean_dcache(val.as_ptr() as usize, val.len());
             self.dma_channel.start();
 
             while self.dma_channel.is_busy() {
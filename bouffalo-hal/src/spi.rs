@@ -1,17 +1,31 @@
 //! Serial Peripheral Interface peripheral.
 
-use crate::glb::{self, v2::SpiMode};
+use crate::clocks::Clocks;
+use crate::dma::{
+    BurstSize, DmaChannelConfig, DmaMode, LliPool, PeripheralId, TransferWidth, TypedChannel,
+    clean_dcache, flush_dcache, invalidate_dcache,
+};
+use crate::glb::{
+    self,
+    v2::{SpiClockSource, SpiMode},
+};
 use crate::gpio::{self, Alternate};
+use as_slice::{AsMutSlice, AsSlice};
 use core::cmp::max;
-use core::ops::Deref;
-use embedded_hal::spi::Mode;
+use core::future::poll_fn;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering, compiler_fence};
+use core::task::Poll;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::spi::{Mode, Operation};
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
 /// Serial Peripheral Interface registers.
 #[repr(C)]
 pub struct RegisterBlock {
     /// Peripheral configuration register.
-    pub config: RW<Config>,
+    pub config: RW<FunctionConfig>,
     /// Interrupt configuration and state register.
     pub interrupt_config: RW<InterruptConfig>,
     /// Bus busy state indication register.
@@ -31,18 +45,17 @@ pub struct RegisterBlock {
     /// First-in first-out queue configuration register 1.
     pub fifo_config_1: RW<FifoConfig1>,
     /// First-in first-out queue write data register.
-    pub fifo_write: WO<u8>,
-    _reserved2: [u8; 0x3],
+    pub fifo_write: WO<u32>,
     /// First-in first-out queue read data register.
-    pub fifo_read: RO<u8>,
+    pub fifo_read: RO<u32>,
 }
 
 /// Peripheral configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
-pub struct Config(u32);
+pub struct FunctionConfig(u32);
 
-impl Config {
+impl FunctionConfig {
     const MASTER_ENABLE: u32 = 1 << 0;
     const SLAVE_ENABLE: u32 = 1 << 1;
     const FRAME_SIZE: u32 = 0x3 << 2;
@@ -625,43 +638,152 @@ pub struct Spi<SPI, PADS, const I: usize> {
     pads: PADS,
 }
 
-impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I> {
+impl<SPI: Deref<Target = RegisterBlock>, PADS> Spi<SPI, PADS, 0> {
     /// Create a new Serial Peripheral Interface instance.
+    ///
+    /// SPI0 is clocked by the global peripheral. Use `new_mm` for multi-media SPI1 of BL808.
+    #[inline]
+    pub fn new<GLB>(
+        spi: SPI,
+        pads: PADS,
+        config: Config,
+        glb: &GLB,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<0>,
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+    {
+        let (divide, phases) = spi_timing(clocks.mux_pll_160m(), config.frequency)?;
+        unsafe {
+            glb.spi_config.modify(|c| {
+                c.enable_clock()
+                    .set_clock_source(SpiClockSource::MuxPll160M)
+                    .set_clock_divide(divide)
+            });
+            glb.param_config
+                .modify(|c| c.set_spi_mode::<0>(SpiMode::Master));
+        }
+        Ok(Self::from_parts(spi, pads, config, phases))
+    }
+
+    /// Change serial clock frequency.
+    ///
+    /// Use this to run a slow initialization sequence, e.g. SD card at 400 kHz,
+    /// before switching to the full operating speed.
     #[inline]
-    pub fn new<GLB>(spi: SPI, pads: PADS, mode: Mode, glb: &GLB) -> Self
+    pub fn set_frequency<GLB>(
+        &mut self,
+        frequency: Hertz,
+        glb: &GLB,
+        clocks: &Clocks,
+    ) -> Result<(), ConfigError>
+    where
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+    {
+        let (divide, phases) = spi_timing(clocks.mux_pll_160m(), frequency)?;
+        unsafe { glb.spi_config.modify(|c| c.set_clock_divide(divide)) };
+        self.write_phases(phases);
+        Ok(())
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS> Spi<SPI, PADS, 1> {
+    /// Create a new Serial Peripheral Interface instance on multi-media subsystem.
+    ///
+    /// SPI1 of BL808 is clocked by multi-media global peripheral; its master mode is still
+    /// selected in the global peripheral.
+    #[inline]
+    pub fn new_mm<GLB, MMGLB>(
+        spi: SPI,
+        pads: PADS,
+        config: Config,
+        glb: &GLB,
+        mm_glb: &MMGLB,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
     where
-        PADS: Pads<I>,
+        PADS: Pads<1>,
         GLB: Deref<Target = glb::v2::RegisterBlock>,
+        MMGLB: Deref<Target = glb::mm::RegisterBlock>,
+    {
+        let (divide, phases) = spi_timing(clocks.mux_pll_160m(), config.frequency)?;
+        unsafe {
+            mm_glb
+                .cpu_config_0
+                .modify(|c| c.set_spi_clock_source(glb::mm::SpiClockSource::MuxPll160M));
+            mm_glb
+                .peripheral_config_0
+                .modify(|c| c.set_spi_clock_divide(divide).enable_spi_clock_divide());
+            glb.param_config
+                .modify(|c| c.set_spi_mode::<1>(SpiMode::Master));
+        }
+        Ok(Self::from_parts(spi, pads, config, phases))
+    }
+
+    /// Change serial clock frequency of multi-media SPI instance.
+    #[inline]
+    pub fn set_frequency_mm<MMGLB>(
+        &mut self,
+        frequency: Hertz,
+        mm_glb: &MMGLB,
+        clocks: &Clocks,
+    ) -> Result<(), ConfigError>
+    where
+        MMGLB: Deref<Target = glb::mm::RegisterBlock>,
     {
-        let mut config = Config(0)
+        let (divide, phases) = spi_timing(clocks.mux_pll_160m(), frequency)?;
+        unsafe {
+            mm_glb
+                .peripheral_config_0
+                .modify(|c| c.set_spi_clock_divide(divide))
+        };
+        self.write_phases(phases);
+        Ok(())
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I> {
+    #[inline]
+    fn from_parts(spi: SPI, pads: PADS, config: Config, phases: [u8; 2]) -> Self {
+        let mut function_config = FunctionConfig(0)
             .disable_deglitch()
             .disable_slave_three_pin()
             .enable_master_continuous()
-            .disable_byte_inverse()
-            .disable_bit_inverse()
             .set_frame_size(FrameSize::Eight)
             .disable_master();
 
-        config = match mode.phase {
+        function_config = match config.bit_order {
+            BitOrder::MsbFirst => function_config.disable_bit_inverse(),
+            BitOrder::LsbFirst => function_config.enable_bit_inverse(),
+        };
+
+        function_config = match config.byte_order {
+            ByteOrder::BigEndian => function_config.disable_byte_inverse(),
+            ByteOrder::LittleEndian => function_config.enable_byte_inverse(),
+        };
+
+        function_config = match config.mode.phase {
             embedded_hal::spi::Phase::CaptureOnFirstTransition => {
-                config.set_clock_phase(Phase::CaptureOnFirstTransition)
+                function_config.set_clock_phase(Phase::CaptureOnFirstTransition)
             }
 
             embedded_hal::spi::Phase::CaptureOnSecondTransition => {
-                config.set_clock_phase(Phase::CaptureOnSecondTransition)
+                function_config.set_clock_phase(Phase::CaptureOnSecondTransition)
             }
         };
 
-        config = match mode.polarity {
-            embedded_hal::spi::Polarity::IdleHigh => config.set_clock_polarity(Polarity::IdleHigh),
-            embedded_hal::spi::Polarity::IdleLow => config.set_clock_polarity(Polarity::IdleLow),
+        function_config = match config.mode.polarity {
+            embedded_hal::spi::Polarity::IdleHigh => {
+                function_config.set_clock_polarity(Polarity::IdleHigh)
+            }
+            embedded_hal::spi::Polarity::IdleLow => {
+                function_config.set_clock_polarity(Polarity::IdleLow)
+            }
         };
 
         unsafe {
-            glb.param_config
-                .modify(|c| c.set_spi_mode::<I>(SpiMode::Master));
-
-            spi.config.write(config);
+            spi.config.write(function_config);
             spi.fifo_config_0
                 .write(FifoConfig0(0).disable_dma_receive().disable_dma_transmit());
             spi.fifo_config_1.write(
@@ -669,17 +791,26 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
                     .set_receive_threshold(0)
                     .set_transmit_threshold(0),
             );
-            spi.period_signal.write(
+        }
+        let spi = Spi { spi, pads };
+        spi.write_phases(phases);
+        spi
+    }
+
+    #[inline]
+    fn write_phases(&self, [phase_0, phase_1]: [u8; 2]) {
+        unsafe {
+            self.spi.period_signal.write(
                 PeriodSignal(0)
-                    .set_data_phase_0(4)
-                    .set_data_phase_1(4)
-                    .set_start_condition(4)
-                    .set_stop_condition(4),
+                    .set_data_phase_0(phase_0)
+                    .set_data_phase_1(phase_1)
+                    .set_start_condition(phase_0)
+                    .set_stop_condition(phase_1),
             );
-            spi.period_interval
-                .write(PeriodInterval(0).set_frame_interval(4));
+            self.spi
+                .period_interval
+                .write(PeriodInterval(0).set_frame_interval(phase_1));
         }
-        Spi { spi, pads }
     }
 
     /// Release the SPI instance and return the pads.
@@ -689,10 +820,178 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
     }
 }
 
+/// SPI bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Serial clock frequency.
+    pub frequency: Hertz,
+    /// Clock polarity and phase.
+    pub mode: Mode,
+    /// Order of the bits in each byte.
+    pub bit_order: BitOrder,
+    /// Order of the bytes in each multi-byte word.
+    pub byte_order: ByteOrder,
+}
+
+impl Config {
+    /// Create a configuration with serial clock frequency and mode.
+    #[inline]
+    pub const fn new(frequency: Hertz, mode: Mode) -> Self {
+        Self {
+            frequency,
+            mode,
+            bit_order: BitOrder::MsbFirst,
+            byte_order: ByteOrder::BigEndian,
+        }
+    }
+    /// Set serial clock frequency.
+    #[inline]
+    pub const fn set_frequency(self, frequency: Hertz) -> Self {
+        Self { frequency, ..self }
+    }
+    /// Set clock polarity and phase.
+    #[inline]
+    pub const fn set_mode(self, mode: Mode) -> Self {
+        Self { mode, ..self }
+    }
+    /// Set order of the bits in each byte.
+    #[inline]
+    pub const fn set_bit_order(self, bit_order: BitOrder) -> Self {
+        Self { bit_order, ..self }
+    }
+    /// Set order of the bytes in each multi-byte word.
+    #[inline]
+    pub const fn set_byte_order(self, byte_order: ByteOrder) -> Self {
+        Self { byte_order, ..self }
+    }
+}
+
+/// Order of the bits transmitted and received on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Each byte is sent out MSB-first.
+    MsbFirst,
+    /// Each byte is sent out LSB-first.
+    LsbFirst,
+}
+
+/// Order of the bytes of `u16` and `u32` words on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Most significant byte is sent out first.
+    BigEndian,
+    /// Least significant byte is sent out first.
+    LittleEndian,
+}
+
+/// Word transferred in one SPI data frame.
+pub trait Word: Copy + 'static {
+    /// Data frame size of this word.
+    const FRAME_SIZE: FrameSize;
+    /// Pack the word into a FIFO queue entry.
+    fn into_fifo(self) -> u32;
+    /// Unpack the word from a FIFO queue entry.
+    fn from_fifo(val: u32) -> Self;
+}
+
+// Hardware sends the lowest byte of a queue entry first; bytes are swapped so that words
+// go out big endian unless byte inverse is enabled.
+impl Word for u8 {
+    const FRAME_SIZE: FrameSize = FrameSize::Eight;
+    #[inline]
+    fn into_fifo(self) -> u32 {
+        self as u32
+    }
+    #[inline]
+    fn from_fifo(val: u32) -> Self {
+        val as u8
+    }
+}
+
+impl Word for u16 {
+    const FRAME_SIZE: FrameSize = FrameSize::Sixteen;
+    #[inline]
+    fn into_fifo(self) -> u32 {
+        self.swap_bytes() as u32
+    }
+    #[inline]
+    fn from_fifo(val: u32) -> Self {
+        (val as u16).swap_bytes()
+    }
+}
+
+impl Word for u32 {
+    const FRAME_SIZE: FrameSize = FrameSize::ThirtyTwo;
+    #[inline]
+    fn into_fifo(self) -> u32 {
+        self.swap_bytes()
+    }
+    #[inline]
+    fn from_fifo(val: u32) -> Self {
+        val.swap_bytes()
+    }
+}
+
+impl Default for Config {
+    /// Bus configuration defaults to 1 MHz in SPI mode 0.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            frequency: Hertz(1_000_000),
+            mode: embedded_hal::spi::MODE_0,
+            bit_order: BitOrder::MsbFirst,
+            byte_order: ByteOrder::BigEndian,
+        }
+    }
+}
+
+/// Errors on bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Impossibly high frequency for current clock source.
+    FrequencyTooHigh,
+    /// Impossibly low frequency for current clock source.
+    FrequencyTooLow,
+}
+
+/// Compute peripheral clock divide factor and phase durations of one bit.
+///
+/// Each bit lasts two phases, each phase lasts 1 ~ 256 peripheral clocks. The
+/// resulting frequency never exceeds the requested one.
+#[inline]
+fn spi_timing(source: Hertz, frequency: Hertz) -> Result<(u8, [u8; 2]), ConfigError> {
+    if frequency.0 == 0 {
+        return Err(ConfigError::FrequencyTooLow);
+    }
+    for divide in 1..=256 {
+        let clock = source.0 / divide;
+        let total = clock.div_ceil(frequency.0);
+        if total < 2 {
+            return Err(ConfigError::FrequencyTooHigh);
+        }
+        if total > 2 * 256 {
+            continue;
+        }
+        let phase_0 = total.div_ceil(2);
+        let phase_1 = total - phase_0;
+        return Ok((
+            (divide - 1) as u8,
+            [(phase_0 - 1) as u8, (phase_1 - 1) as u8],
+        ));
+    }
+    Err(ConfigError::FrequencyTooLow)
+}
+
 /// SPI error.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Error {
+    /// Receive queue overflowed before words were read, slave mode only.
+    Overrun,
+    /// Transmit queue ran empty while the host was clocking, slave mode only.
+    Underrun,
+    /// Transaction cannot keep hardware chip select asserted, see `HardwareCsDevice`.
+    Unsupported,
     Other,
 }
 
@@ -701,7 +1000,8 @@ impl embedded_hal::spi::Error for Error {
     fn kind(&self) -> embedded_hal::spi::ErrorKind {
         use embedded_hal::spi::ErrorKind;
         match self {
-            Error::Other => ErrorKind::Other,
+            Error::Overrun => ErrorKind::Overrun,
+            Error::Underrun | Error::Unsupported | Error::Other => ErrorKind::Other,
         }
     }
 }
@@ -712,145 +1012,231 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
     type Error = Error;
 }
 
-impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi::SpiBus
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, W: Word> embedded_hal::spi::SpiBus<W>
     for Spi<SPI, PADS, I>
 {
     #[inline]
-    fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
-
-        buf.iter_mut().for_each(|slot| {
-            while self.spi.fifo_config_1.read().receive_available_bytes() == 0 {
-                core::hint::spin_loop();
-            }
-            *slot = self.spi.fifo_read.read()
-        });
-
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+    fn read(&mut self, buf: &mut [W]) -> Result<(), Self::Error> {
+        spi_begin::<W>(&self.spi);
+        spi_read(&self.spi, buf);
+        spi_end(&self.spi);
         Ok(())
     }
     #[inline]
-    fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
-
-        buf.iter().for_each(|&word| {
-            while self.spi.fifo_config_1.read().transmit_available_bytes() == 0 {
-                core::hint::spin_loop();
-            }
-            unsafe { self.spi.fifo_write.write(word) }
-            _ = self.spi.fifo_read.read();
-        });
-
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+    fn write(&mut self, buf: &[W]) -> Result<(), Self::Error> {
+        spi_begin::<W>(&self.spi);
+        spi_write(&self.spi, buf);
+        spi_end(&self.spi);
         Ok(())
     }
     #[inline]
-    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
-        const MAX_RETRY: usize = 1000;
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
-
-        let (mut tx, mut rx) = (0, 0);
-        let mut fifo_config = self.spi.fifo_config_1.read();
-        let mut retry = 0;
-        while tx < write.len() || rx < read.len() {
-            while fifo_config.receive_available_bytes() == 0
-                && fifo_config.transmit_available_bytes() == 0
-            {
-                fifo_config = self.spi.fifo_config_1.read();
-            }
-            if fifo_config.transmit_available_bytes() != 0 && tx < write.len() {
-                unsafe { self.spi.fifo_write.write(write[tx]) }
-                tx += 1;
-            }
-            if fifo_config.receive_available_bytes() != 0 && rx < read.len() {
-                read[rx] = self.spi.fifo_read.read();
-                rx += 1;
-            }
-            retry += 1;
-            if retry > MAX_RETRY * max(write.len(), read.len()) {
-                return Err(Error::Other);
-            }
-        }
-
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
-        Ok(())
+    fn transfer(&mut self, read: &mut [W], write: &[W]) -> Result<(), Self::Error> {
+        spi_begin::<W>(&self.spi);
+        let ans = spi_transfer(&self.spi, read, write);
+        spi_end(&self.spi);
+        ans
     }
     #[inline]
-    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-        const MAX_RETRY: usize = 1000;
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
-
-        let (mut tx, mut rx) = (0, 0);
-        let mut fifo_config = self.spi.fifo_config_1.read();
-        let mut retry = 0;
-        while tx < words.len() || rx < words.len() {
-            while fifo_config.receive_available_bytes() == 0
-                && fifo_config.transmit_available_bytes() == 0
-            {
-                fifo_config = self.spi.fifo_config_1.read();
-            }
-            if fifo_config.transmit_available_bytes() != 0 && tx < words.len() {
-                unsafe { self.spi.fifo_write.write(words[tx]) }
-                tx += 1;
-            }
-            if fifo_config.receive_available_bytes() != 0 && rx < tx {
-                words[rx] = self.spi.fifo_read.read();
-                rx += 1;
-            }
-            retry += 1;
-            if retry > MAX_RETRY * words.len() {
-                return Err(Error::Other);
-            }
-        }
-
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
-        Ok(())
+    fn transfer_in_place(&mut self, words: &mut [W]) -> Result<(), Self::Error> {
+        spi_begin::<W>(&self.spi);
+        let ans = spi_transfer_in_place(&self.spi, words);
+        spi_end(&self.spi);
+        ans
     }
+    /// Wait until the bus is idle.
+    ///
+    /// Queue depth depends on frame size, so bus busy state is checked instead of queue counts.
     #[inline]
     fn flush(&mut self) -> Result<(), Self::Error> {
-        while self.spi.fifo_config_1.read().transmit_available_bytes() != 32 {
+        spi_flush(&self.spi);
+        Ok(())
+    }
+}
+
+/// Enable master with frame size of `W`; hardware chip select is asserted from now on.
+#[inline]
+fn spi_begin<W: Word>(spi: &RegisterBlock) {
+    unsafe {
+        spi.config
+            .modify(|config| config.set_frame_size(W::FRAME_SIZE).enable_master())
+    };
+}
+
+/// Disable master, releasing hardware chip select.
+#[inline]
+fn spi_end(spi: &RegisterBlock) {
+    unsafe { spi.config.modify(|config| config.disable_master()) };
+}
+
+#[inline]
+fn spi_read<W: Word>(spi: &RegisterBlock, buf: &mut [W]) {
+    buf.iter_mut().for_each(|slot| {
+        while spi.fifo_config_1.read().receive_available_bytes() == 0 {
             core::hint::spin_loop();
         }
-        while self.spi.fifo_config_1.read().receive_available_bytes() != 32 {
+        *slot = W::from_fifo(spi.fifo_read.read())
+    });
+}
+
+#[inline]
+fn spi_write<W: Word>(spi: &RegisterBlock, buf: &[W]) {
+    buf.iter().for_each(|&word| {
+        while spi.fifo_config_1.read().transmit_available_bytes() == 0 {
             core::hint::spin_loop();
         }
-        Ok(())
+        unsafe { spi.fifo_write.write(word.into_fifo()) }
+        _ = spi.fifo_read.read();
+    });
+}
+
+#[inline]
+fn spi_transfer<W: Word>(spi: &RegisterBlock, read: &mut [W], write: &[W]) -> Result<(), Error> {
+    const MAX_RETRY: usize = 1000;
+    let (mut tx, mut rx) = (0, 0);
+    let mut fifo_config = spi.fifo_config_1.read();
+    let mut retry = 0;
+    while tx < write.len() || rx < read.len() {
+        while fifo_config.receive_available_bytes() == 0
+            && fifo_config.transmit_available_bytes() == 0
+        {
+            fifo_config = spi.fifo_config_1.read();
+        }
+        if fifo_config.transmit_available_bytes() != 0 && tx < write.len() {
+            unsafe { spi.fifo_write.write(write[tx].into_fifo()) }
+            tx += 1;
+        }
+        if fifo_config.receive_available_bytes() != 0 && rx < read.len() {
+            read[rx] = W::from_fifo(spi.fifo_read.read());
+            rx += 1;
+        }
+        retry += 1;
+        if retry > MAX_RETRY * max(write.len(), read.len()) {
+            return Err(Error::Other);
+        }
     }
+    Ok(())
 }
 
-impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi::SpiDevice
-    for Spi<SPI, PADS, I>
+#[inline]
+fn spi_transfer_in_place<W: Word>(spi: &RegisterBlock, words: &mut [W]) -> Result<(), Error> {
+    const MAX_RETRY: usize = 1000;
+    let (mut tx, mut rx) = (0, 0);
+    let mut fifo_config = spi.fifo_config_1.read();
+    let mut retry = 0;
+    while tx < words.len() || rx < words.len() {
+        while fifo_config.receive_available_bytes() == 0
+            && fifo_config.transmit_available_bytes() == 0
+        {
+            fifo_config = spi.fifo_config_1.read();
+        }
+        if fifo_config.transmit_available_bytes() != 0 && tx < words.len() {
+            unsafe { spi.fifo_write.write(words[tx].into_fifo()) }
+            tx += 1;
+        }
+        if fifo_config.receive_available_bytes() != 0 && rx < tx {
+            words[rx] = W::from_fifo(spi.fifo_read.read());
+            rx += 1;
+        }
+        retry += 1;
+        if retry > MAX_RETRY * words.len() {
+            return Err(Error::Other);
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+fn spi_flush(spi: &RegisterBlock) {
+    while spi.bus_busy.read().is_bus_busy() {
+        core::hint::spin_loop();
+    }
+}
+
+/// SPI device selected by hardware chip select pad of the peripheral.
+///
+/// Hardware releases chip select whenever the transmit queue runs empty, so only transactions
+/// of one read, write or transfer operation are supported; others, including delays, return
+/// `Error::Unsupported`. Words are fed by the CPU, so an interrupt taken mid-operation may
+/// still release chip select. Use `ExclusiveDevice` or `RefCellDevice` with a GPIO output if
+/// the device needs chip select held across operations.
+pub struct HardwareCsDevice<SPI, PADS, const I: usize, D> {
+    spi: Spi<SPI, PADS, I>,
+    delay: D,
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, D>
+    HardwareCsDevice<SPI, PADS, I, D>
 {
-    fn transaction(
-        &mut self,
-        operations: &mut [embedded_hal::spi::Operation<'_, u8>],
-    ) -> Result<(), Self::Error> {
-        for op in operations {
-            match op {
-                embedded_hal::spi::Operation::Read(buf) => {
-                    embedded_hal::spi::SpiBus::read(self, buf)?
-                }
-                embedded_hal::spi::Operation::Write(buf) => {
-                    embedded_hal::spi::SpiBus::write(self, buf)?
-                }
-                embedded_hal::spi::Operation::Transfer(read, write) => {
-                    embedded_hal::spi::SpiBus::transfer(self, read, write)?
-                }
-                embedded_hal::spi::Operation::TransferInPlace(buf) => {
-                    embedded_hal::spi::SpiBus::transfer_in_place(self, buf)?
-                }
-                embedded_hal::spi::Operation::DelayNs(_delay) => {
-                    for _ in 0..*_delay {
-                        // TODO: more accurate delay
-                        core::hint::spin_loop();
-                    }
-                }
+    /// Create an SPI device from the bus and a delay provider, e.g. a timer.
+    #[inline]
+    pub fn new(spi: Spi<SPI, PADS, I>, delay: D) -> Self {
+        Self { spi, delay }
+    }
+    /// Release the bus and the delay provider.
+    #[inline]
+    pub fn free(self) -> (Spi<SPI, PADS, I>, D) {
+        (self.spi, self.delay)
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, D> embedded_hal::spi::ErrorType
+    for HardwareCsDevice<SPI, PADS, I, D>
+{
+    type Error = Error;
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, D: DelayNs, W: Word>
+    embedded_hal::spi::SpiDevice<W> for HardwareCsDevice<SPI, PADS, I, D>
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, W>]) -> Result<(), Self::Error> {
+        let spi = &*self.spi.spi;
+        let mut operations = operations.iter_mut().filter(|op| !is_empty_operation(op));
+        let op = match (operations.next(), operations.next()) {
+            (None, _) => return Ok(()),
+            (Some(Operation::DelayNs(_)), _) | (Some(_), Some(_)) => {
+                return Err(Error::Unsupported);
             }
-        }
-        Ok(())
+            (Some(op), None) => op,
+        };
+        spi_begin::<W>(spi);
+        let ans = match op {
+            Operation::Read(buf) => {
+                spi_read(spi, buf);
+                Ok(())
+            }
+            Operation::Write(buf) => {
+                spi_write(spi, buf);
+                Ok(())
+            }
+            Operation::Transfer(read, write) => spi_transfer(spi, read, write),
+            Operation::TransferInPlace(buf) => spi_transfer_in_place(spi, buf),
+            Operation::DelayNs(_) => unreachable!(),
+        };
+        spi_flush(spi);
+        spi_end(spi);
+        ans
+    }
+}
+
+/// Operation which moves no words on the bus.
+#[inline]
+fn is_empty_operation<W>(op: &Operation<'_, W>) -> bool {
+    match op {
+        Operation::Read(buf) => buf.is_empty(),
+        Operation::Write(buf) => buf.is_empty(),
+        Operation::Transfer(read, write) => read.is_empty() && write.is_empty(),
+        Operation::TransferInPlace(buf) => buf.is_empty(),
+        Operation::DelayNs(_) => false,
     }
 }
 
+/// SPI device with exclusive access to the bus, selected by a GPIO output.
+pub use embedded_hal_bus::spi::ExclusiveDevice;
+
+/// SPI device sharing the bus with other devices, selected by a GPIO output.
+pub use embedded_hal_bus::spi::RefCellDevice;
+
 // This part of implementation using `embedded_hal_027` is designed for backward compatibility of
 // ecosystem crates, as some of them depends on embedded-hal v0.2.7 traits.
 // We encourage ecosystem developers to use embedded-hal v1.0.0 traits; after that, this part of code
@@ -861,7 +1247,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize>
     type Error = Error;
     #[inline]
     fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-        <Self as embedded_hal::spi::SpiBus>::write(self, words)?;
+        <Self as embedded_hal::spi::SpiBus<u8>>::write(self, words)?;
         Ok(())
     }
 }
@@ -872,111 +1258,1069 @@ impl<SPI: Deref<Target = RegisterBlock>, PINS, const I: usize>
     type Error = Error;
     #[inline]
     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
-        <Self as embedded_hal::spi::SpiBus>::transfer_in_place(self, words)?;
+        <Self as embedded_hal::spi::SpiBus<u8>>::transfer_in_place(self, words)?;
         Ok(words)
     }
 }
 
-/// Valid SPI pads.
-pub trait Pads<const I: usize> {}
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I> {
+    /// Convert into an interrupt-driven async/await SPI peripheral.
+    ///
+    /// `state` must be notified with `SpiState::on_interrupt` in the SPI interrupt handler.
+    #[inline]
+    pub fn into_async(self, state: &SpiState) -> AsyncSpi<'_, SPI, PADS, I> {
+        state
+            .ref_to_spi
+            .store(&*self.spi as *const _ as usize, Ordering::Release);
+        unsafe {
+            self.spi.interrupt_config.modify(|val| {
+                ASYNC_INTERRUPTS
+                    .iter()
+                    .fold(val, |v, &i| v.mask_interrupt(i).enable_interrupt(i))
+            });
+        }
+        AsyncSpi {
+            spi: self.spi,
+            pads: self.pads,
+            state,
+        }
+    }
 
-impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> Pads<1>
-    for (
-        Alternate<'a, N1, gpio::Spi<1>>,
-        Alternate<'b, N2, gpio::Spi<1>>,
-        Alternate<'c, N3, gpio::Spi<1>>,
-    )
-where
-    Alternate<'a, N1, gpio::Spi<1>>: HasClkSignal,
-    Alternate<'b, N2, gpio::Spi<1>>: HasMosiSignal,
-    Alternate<'c, N3, gpio::Spi<1>>: HasCsSignal,
-{
+    /// Hand over data transfers to DMA channels.
+    ///
+    /// Each channel comes with the DMA peripheral request of this SPI on its DMA controller,
+    /// e.g. `Spi0Tx` and `Spi0Rx` for SPI0.
+    #[inline]
+    pub fn with_dma<'b, T: PeripheralId + Copy>(
+        self,
+        (tx_channel, tx_request): (TypedChannel<'b, T>, T),
+        (rx_channel, rx_request): (TypedChannel<'b, T>, T),
+    ) -> DmaSpi<'b, SPI, PADS, I, T> {
+        unsafe {
+            self.spi
+                .fifo_config_1
+                .modify(|val| val.set_transmit_threshold(0).set_receive_threshold(0));
+            self.spi.fifo_config_0.modify(|val| {
+                val.enable_dma_transmit()
+                    .enable_dma_receive()
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo()
+            });
+        }
+        DmaSpi {
+            spi: self.spi,
+            pads: self.pads,
+            tx_channel,
+            rx_channel,
+            tx_request,
+            rx_request,
+        }
+    }
 }
 
-impl<'a, 'b, 'c, 'd, const N1: usize, const N2: usize, const N3: usize, const N4: usize> Pads<1>
-    for (
-        Alternate<'a, N1, gpio::Spi<1>>,
-        Alternate<'b, N2, gpio::Spi<1>>,
-        Alternate<'c, N3, gpio::Spi<1>>,
-        Alternate<'d, N4, gpio::Spi<1>>,
-    )
-where
-    Alternate<'a, N1, gpio::Spi<1>>: HasClkSignal,
-    Alternate<'b, N2, gpio::Spi<1>>: HasMosiSignal,
-    Alternate<'c, N3, gpio::Spi<1>>: HasMisoSignal,
-    Alternate<'d, N4, gpio::Spi<1>>: HasCsSignal,
-{
+/// Managed async/await Serial Peripheral Interface peripheral.
+pub struct AsyncSpi<'a, SPI, PADS, const I: usize> {
+    spi: SPI,
+    pads: PADS,
+    state: &'a SpiState,
 }
 
-/// Check if target gpio `Pin` is internally connected to SPI clock signal.
-pub trait HasClkSignal {}
-
-impl<'a> HasClkSignal for Alternate<'a, 3, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 7, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 11, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 15, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 19, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 23, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 27, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 31, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 35, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 39, gpio::Spi<1>> {}
-impl<'a> HasClkSignal for Alternate<'a, 43, gpio::Spi<1>> {}
-
-/// Check if target gpio `Pin` is internally connected to SPI MISO signal.
-pub trait HasMisoSignal {}
-
-impl<'a> HasMisoSignal for Alternate<'a, 2, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 6, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 10, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 14, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 18, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 22, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 26, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 30, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 34, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 38, gpio::Spi<1>> {}
-impl<'a> HasMisoSignal for Alternate<'a, 42, gpio::Spi<1>> {}
+impl<'a, SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> AsyncSpi<'a, SPI, PADS, I> {
+    /// Convert back into blocking SPI peripheral, masking all SPI interrupts.
+    #[inline]
+    pub fn into_blocking(self) -> Spi<SPI, PADS, I> {
+        unsafe {
+            self.spi.interrupt_config.modify(|val| {
+                ASYNC_INTERRUPTS
+                    .iter()
+                    .fold(val, |v, &i| v.mask_interrupt(i))
+            });
+        }
+        Spi {
+            spi: self.spi,
+            pads: self.pads,
+        }
+    }
+}
 
-/// Check if target gpio `Pin` is internally connected to SPI MOSI signal.
-pub trait HasMosiSignal {}
+/// Waker as the state for an async/await SPI peripheral.
+#[derive(Debug)]
+pub struct SpiState {
+    waker: atomic_waker::AtomicWaker,
+    ref_to_spi: AtomicUsize,
+}
 
-impl<'a> HasMosiSignal for Alternate<'a, 1, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 5, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 9, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 13, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 17, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 21, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 25, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 29, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 33, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 37, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 41, gpio::Spi<1>> {}
-impl<'a> HasMosiSignal for Alternate<'a, 45, gpio::Spi<1>> {}
+impl SpiState {
+    /// Creates the waker state for an SPI peripheral.
+    #[inline]
+    pub const fn new() -> SpiState {
+        SpiState {
+            waker: atomic_waker::AtomicWaker::new(),
+            ref_to_spi: AtomicUsize::new(0),
+        }
+    }
+    /// Use this state to handle interrupt.
+    ///
+    /// Pending interrupts are masked and the waiting task is woken.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let spi = unsafe { &*(self.ref_to_spi.load(Ordering::Acquire) as *const RegisterBlock) };
+        let config = spi.interrupt_config.read();
+        let mut ans = config;
+        for interrupt in ASYNC_INTERRUPTS {
+            if config.has_interrupt(interrupt) {
+                ans = ans.mask_interrupt(interrupt);
+            }
+        }
+        unsafe { spi.interrupt_config.write(ans) };
+        self.waker.wake();
+    }
+}
 
-/// Check if target gpio `Pin` is internally connected to SPI CS signal.
-pub trait HasCsSignal {}
+impl Default for SpiState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-impl<'a> HasCsSignal for Alternate<'a, 0, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 4, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 8, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 12, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 16, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 20, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 24, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 28, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 32, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 36, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 40, gpio::Spi<1>> {}
-impl<'a> HasCsSignal for Alternate<'a, 44, gpio::Spi<1>> {}
+impl<'a, SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi::ErrorType
+    for AsyncSpi<'a, SPI, PADS, I>
+{
+    type Error = Error;
+}
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        BusBusy, Config, FifoConfig0, FifoConfig1, FrameSize, Interrupt, InterruptConfig,
-        PeriodInterval, PeriodSignal, Phase, Polarity, ReceiveIgnore, RegisterBlock, SlaveTimeout,
-    };
-    use core::mem::offset_of;
+impl<'a, SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal_async::spi::SpiBus
+    for AsyncSpi<'a, SPI, PADS, I>
+{
+    #[inline]
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        spi_transfer_async(&self.spi, self.state, Buffers::Read(words)).await;
+        Ok(())
+    }
+    #[inline]
+    async fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        spi_transfer_async(&self.spi, self.state, Buffers::Write(words)).await;
+        Ok(())
+    }
+    #[inline]
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        spi_transfer_async(&self.spi, self.state, Buffers::Transfer(read, write)).await;
+        Ok(())
+    }
+    #[inline]
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        spi_transfer_async(&self.spi, self.state, Buffers::InPlace(words)).await;
+        Ok(())
+    }
+    /// Wait until the bus is idle.
+    #[inline]
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        let (spi, state) = (&*self.spi, self.state);
+        unsafe {
+            spi.interrupt_config
+                .modify(|val| val.clear_interrupt(Interrupt::TransferEnd))
+        };
+        spi_wait_async(spi, state, Interrupt::TransferEnd, |spi| {
+            !spi.bus_busy.read().is_bus_busy()
+        })
+        .await;
+        Ok(())
+    }
+}
+
+/// Interrupts used by async/await SPI peripheral.
+const ASYNC_INTERRUPTS: [Interrupt; 2] = [Interrupt::TransferEnd, Interrupt::ReceiveFifoReady];
+
+/// Bytes in transmit or receive FIFO queue.
+const FIFO_DEPTH: usize = 32;
+
+/// Word sources and destinations of one bus transfer.
+enum Buffers<'a> {
+    Read(&'a mut [u8]),
+    Write(&'a [u8]),
+    Transfer(&'a mut [u8], &'a [u8]),
+    InPlace(&'a mut [u8]),
+}
+
+impl Buffers<'_> {
+    /// Words clocked on the bus.
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            Buffers::Read(read) => read.len(),
+            Buffers::Write(write) => write.len(),
+            Buffers::Transfer(read, write) => max(read.len(), write.len()),
+            Buffers::InPlace(words) => words.len(),
+        }
+    }
+    /// Word to send at `idx`, zero when there is nothing to write.
+    #[inline]
+    fn send(&self, idx: usize) -> u8 {
+        match self {
+            Buffers::Read(_) => 0,
+            Buffers::Write(write) | Buffers::Transfer(_, write) => {
+                write.get(idx).copied().unwrap_or(0)
+            }
+            Buffers::InPlace(words) => words[idx],
+        }
+    }
+    /// Store word received at `idx`, discarded when there is nowhere to read.
+    #[inline]
+    fn receive(&mut self, idx: usize, word: u8) {
+        match self {
+            Buffers::Read(read) | Buffers::Transfer(read, _) => {
+                if let Some(slot) = read.get_mut(idx) {
+                    *slot = word;
+                }
+            }
+            Buffers::Write(_) => {}
+            Buffers::InPlace(words) => words[idx] = word,
+        }
+    }
+}
+
+/// Clock every word of `buffers` through the bus.
+///
+/// At most a FIFO depth of words is in flight, so receive queue never overflows; the task
+/// sleeps on receive FIFO ready interrupt while the bus is shifting.
+#[inline]
+async fn spi_transfer_async(spi: &RegisterBlock, state: &SpiState, mut buffers: Buffers<'_>) {
+    let len = buffers.len();
+    unsafe {
+        spi.config
+            .modify(|config| config.set_frame_size(FrameSize::Eight).enable_master())
+    };
+    // Aborts the transfer if this future is dropped before all words are received.
+    let guard = AbortOnDrop(spi);
+    let (mut tx, mut rx) = (0, 0);
+    while rx < len {
+        let fifo = spi.fifo_config_1.read();
+        let mut space = fifo.transmit_available_bytes() as usize;
+        while tx < len && space > 0 && tx - rx < FIFO_DEPTH {
+            unsafe { spi.fifo_write.write(buffers.send(tx) as u32) };
+            tx += 1;
+            space -= 1;
+        }
+        let mut available = fifo.receive_available_bytes() as usize;
+        if available == 0 && rx < tx {
+            spi_wait_async(spi, state, Interrupt::ReceiveFifoReady, |spi| {
+                spi.fifo_config_1.read().receive_available_bytes() != 0
+            })
+            .await;
+            available = spi.fifo_config_1.read().receive_available_bytes() as usize;
+        }
+        while available > 0 && rx < tx {
+            buffers.receive(rx, spi.fifo_read.read() as u8);
+            rx += 1;
+            available -= 1;
+        }
+    }
+    core::mem::forget(guard);
+    unsafe { spi.config.modify(|config| config.disable_master()) };
+}
+
+/// Stops master, masks receive FIFO ready interrupt and drops queued words on drop.
+struct AbortOnDrop<'a>(&'a RegisterBlock);
+
+impl Drop for AbortOnDrop<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            self.0.config.modify(|config| config.disable_master());
+            self.0
+                .interrupt_config
+                .modify(|val| val.mask_interrupt(Interrupt::ReceiveFifoReady));
+        }
+        spi_clear_fifo(self.0);
+    }
+}
+
+/// Wait until `f` holds, sleeping on `interrupt` in between.
+#[inline]
+async fn spi_wait_async(
+    spi: &RegisterBlock,
+    state: &SpiState,
+    interrupt: Interrupt,
+    f: impl Fn(&RegisterBlock) -> bool,
+) {
+    poll_fn(|cx| {
+        state.waker.register(cx.waker());
+        if f(spi) {
+            unsafe {
+                spi.interrupt_config
+                    .modify(|val| val.mask_interrupt(interrupt))
+            };
+            Poll::Ready(())
+        } else {
+            unsafe {
+                spi.interrupt_config
+                    .modify(|val| val.unmask_interrupt(interrupt))
+            };
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// Managed Serial Peripheral Interface peripheral with DMA transmit and receive.
+pub struct DmaSpi<'b, SPI, PADS, const I: usize, T> {
+    spi: SPI,
+    pads: PADS,
+    tx_channel: TypedChannel<'b, T>,
+    rx_channel: TypedChannel<'b, T>,
+    tx_request: T,
+    rx_request: T,
+}
+
+impl<'b, SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, T: PeripheralId + Copy>
+    DmaSpi<'b, SPI, PADS, I, T>
+{
+    /// Starts a DMA write of an owned buffer; received words are discarded.
+    ///
+    /// An empty buffer is returned back.
+    #[inline]
+    pub fn write<B>(&mut self, buffer: B) -> Result<DmaTransfer<'_, 'b, SPI, PADS, I, T, B>, B>
+    where
+        B: Deref + 'static,
+        B::Target: AsSlice<Element = u8>,
+    {
+        let slice = buffer.as_slice();
+        let (ptr, len) = (slice.as_ptr() as usize, slice.len());
+        DmaTransfer::new(self, buffer, ptr, len, true, false)
+    }
+
+    /// Starts a DMA read into an owned buffer, sending zeros.
+    ///
+    /// An empty buffer is returned back.
+    #[inline]
+    pub fn read<B>(&mut self, mut buffer: B) -> Result<DmaTransfer<'_, 'b, SPI, PADS, I, T, B>, B>
+    where
+        B: DerefMut + 'static,
+        B::Target: AsMutSlice<Element = u8>,
+    {
+        let slice = buffer.as_mut_slice();
+        let (ptr, len) = (slice.as_mut_ptr() as usize, slice.len());
+        DmaTransfer::new(self, buffer, ptr, len, false, true)
+    }
+
+    /// Starts a DMA transfer sending an owned buffer and replacing it with received words.
+    ///
+    /// An empty buffer is returned back.
+    #[inline]
+    pub fn transfer_in_place<B>(
+        &mut self,
+        mut buffer: B,
+    ) -> Result<DmaTransfer<'_, 'b, SPI, PADS, I, T, B>, B>
+    where
+        B: DerefMut + 'static,
+        B::Target: AsMutSlice<Element = u8>,
+    {
+        let slice = buffer.as_mut_slice();
+        let (ptr, len) = (slice.as_mut_ptr() as usize, slice.len());
+        DmaTransfer::new(self, buffer, ptr, len, true, true)
+    }
+
+    /// Release DMA channels and return the blocking SPI peripheral.
+    #[inline]
+    pub fn free(self) -> (Spi<SPI, PADS, I>, TypedChannel<'b, T>, TypedChannel<'b, T>) {
+        self.tx_channel.stop();
+        self.rx_channel.stop();
+        unsafe {
+            self.spi
+                .fifo_config_0
+                .modify(|val| val.disable_dma_transmit().disable_dma_receive());
+        }
+        let spi = Spi {
+            spi: self.spi,
+            pads: self.pads,
+        };
+        (spi, self.tx_channel, self.rx_channel)
+    }
+
+    #[inline]
+    fn configure(&mut self, tx_increment: bool, rx_increment: bool) {
+        self.tx_channel.configure(DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            src_req: None,
+            dst_req: Some(self.tx_request),
+            src_addr_inc: tx_increment,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::Byte,
+            dst_transfer_width: TransferWidth::Byte,
+        });
+        self.rx_channel.configure(DmaChannelConfig {
+            direction: DmaMode::Periph2Mem,
+            src_req: Some(self.rx_request),
+            dst_req: None,
+            src_addr_inc: false,
+            dst_addr_inc: rx_increment,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::Byte,
+            dst_transfer_width: TransferWidth::Byte,
+        });
+    }
+}
+
+/// Source of words sent on DMA reads.
+static DMA_ZERO: u8 = 0;
+/// Destination of words received on DMA writes.
+static DMA_SINK: AtomicU8 = AtomicU8::new(0);
+/// Maximum bytes moved by one linked list item.
+const DMA_CHUNK_LENGTH: usize = 4064;
+
+/// Ongoing DMA transfer on an owned buffer.
+///
+/// Both channels run on every transfer, so the receive queue never overflows. Buffers longer
+/// than 4064 bytes are moved in several chunks; the CPU only restarts the channels between
+/// chunks, when the transfer is polled.
+pub struct DmaTransfer<
+    's,
+    'b,
+    SPI: Deref<Target = RegisterBlock>,
+    PADS,
+    const I: usize,
+    T: PeripheralId + Copy,
+    B,
+> {
+    spi: &'s mut DmaSpi<'b, SPI, PADS, I, T>,
+    buffer: Option<B>,
+    ptr: usize,
+    len: usize,
+    offset: usize,
+    tx_increment: bool,
+    rx_increment: bool,
+    done: bool,
+}
+
+impl<'s, 'b, SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, T: PeripheralId + Copy, B>
+    DmaTransfer<'s, 'b, SPI, PADS, I, T, B>
+{
+    #[inline]
+    fn new(
+        spi: &'s mut DmaSpi<'b, SPI, PADS, I, T>,
+        buffer: B,
+        ptr: usize,
+        len: usize,
+        tx_increment: bool,
+        rx_increment: bool,
+    ) -> Result<Self, B> {
+        if len == 0 {
+            return Err(buffer);
+        }
+        spi.configure(tx_increment, rx_increment);
+        match rx_increment {
+            true => flush_dcache(ptr, len),
+            false => clean_dcache(ptr, len),
+        }
+        unsafe {
+            spi.spi
+                .config
+                .modify(|config| config.set_frame_size(FrameSize::Eight).enable_master())
+        };
+        let mut transfer = DmaTransfer {
+            spi,
+            buffer: Some(buffer),
+            ptr,
+            len,
+            offset: 0,
+            tx_increment,
+            rx_increment,
+            done: false,
+        };
+        transfer.start_chunk();
+        Ok(transfer)
+    }
+
+    /// Checks whether the transfer has ended, starting the next chunk if needed.
+    #[inline]
+    pub fn is_done(&mut self) -> bool {
+        self.poll()
+    }
+
+    /// Waits for the transfer to end and returns the buffer.
+    #[inline]
+    pub fn wait(mut self) -> B {
+        while !self.poll() {
+            core::hint::spin_loop();
+        }
+        self.buffer.take().unwrap()
+    }
+
+    #[inline]
+    fn start_chunk(&mut self) {
+        let chunk = core::cmp::min(self.len - self.offset, DMA_CHUNK_LENGTH);
+        let spi = &*self.spi.spi;
+        let src = match self.tx_increment {
+            true => (self.ptr + self.offset) as u32,
+            false => &DMA_ZERO as *const u8 as u32,
+        };
+        let dst = match self.rx_increment {
+            true => (self.ptr + self.offset) as u32,
+            false => DMA_SINK.as_ptr() as u32,
+        };
+        let mut tx_lli = [LliPool::new()];
+        let mut rx_lli = [LliPool::new()];
+        let (tx_channel, rx_channel) = (&self.spi.tx_channel, &self.spi.rx_channel);
+        tx_channel.lli_config(
+            &mut tx_lli,
+            1,
+            src,
+            &spi.fifo_write as *const _ as u32,
+            0,
+            chunk as u32,
+        );
+        rx_channel.lli_config(
+            &mut rx_lli,
+            1,
+            &spi.fifo_read as *const _ as u32,
+            dst,
+            0,
+            chunk as u32,
+        );
+        compiler_fence(Ordering::Release);
+        // Receive channel is ready before the first word is shifted.
+        rx_channel.lli_load(&rx_lli[0]);
+        rx_channel.start();
+        tx_channel.lli_load(&tx_lli[0]);
+        tx_channel.start();
+    }
+
+    #[inline]
+    fn poll(&mut self) -> bool {
+        if self.done {
+            return true;
+        }
+        // Every word sent is received back, receive channel ends last.
+        if self.spi.rx_channel.is_busy() {
+            return false;
+        }
+        self.offset += core::cmp::min(self.len - self.offset, DMA_CHUNK_LENGTH);
+        if self.offset < self.len {
+            self.start_chunk();
+            false
+        } else {
+            compiler_fence(Ordering::Acquire);
+            if self.rx_increment {
+                // Buffer was flushed before the transfer and only DMA wrote it since.
+                unsafe { invalidate_dcache(self.ptr, self.len) };
+            }
+            self.finish();
+            true
+        }
+    }
+
+    #[inline]
+    fn finish(&mut self) {
+        self.spi.tx_channel.stop();
+        self.spi.rx_channel.stop();
+        unsafe { self.spi.spi.config.modify(|config| config.disable_master()) };
+        self.done = true;
+    }
+}
+
+impl<'s, 'b, SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, T: PeripheralId + Copy, B>
+    Drop for DmaTransfer<'s, 'b, SPI, PADS, I, T, B>
+{
+    /// Stops the DMA channels and the peripheral before the buffer could be released.
+    #[inline]
+    fn drop(&mut self) {
+        if !self.done {
+            self.finish();
+            unsafe {
+                self.spi
+                    .spi
+                    .fifo_config_0
+                    .modify(|val| val.clear_transmit_fifo().clear_receive_fifo())
+            };
+        }
+    }
+}
+
+/// Slave mode bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlaveConfig {
+    /// Clock polarity and phase, as driven by the host.
+    pub mode: Mode,
+    /// Idle peripheral clocks before a slave timeout is signaled, at most 4095.
+    pub timeout: u16,
+    /// Frame transfers without chip select signal.
+    ///
+    /// Frames then only end on slave timeout.
+    pub three_pin: bool,
+}
+
+impl SlaveConfig {
+    /// Create a slave configuration with clock mode and four-pin framing.
+    #[inline]
+    pub const fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            timeout: 0xfff,
+            three_pin: false,
+        }
+    }
+    /// Set idle peripheral clocks before slave timeout.
+    #[inline]
+    pub const fn set_timeout(self, timeout: u16) -> Self {
+        Self { timeout, ..self }
+    }
+    /// Frame transfers by slave timeout without chip select signal.
+    #[inline]
+    pub const fn enable_three_pin(self) -> Self {
+        Self {
+            three_pin: true,
+            ..self
+        }
+    }
+}
+
+impl Default for SlaveConfig {
+    /// Slave configuration defaults to SPI mode 0, framed by chip select.
+    #[inline]
+    fn default() -> Self {
+        Self::new(embedded_hal::spi::MODE_0)
+    }
+}
+
+/// Managed Serial Peripheral Interface peripheral in slave mode.
+///
+/// Bus clock is driven by the host; a frame starts when the host asserts chip select, and ends
+/// when chip select is deasserted or the bus stays idle until slave timeout.
+pub struct SpiSlave<SPI, PADS, const I: usize> {
+    spi: SPI,
+    pads: PADS,
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS> SpiSlave<SPI, PADS, 0> {
+    /// Create a new Serial Peripheral Interface instance in slave mode.
+    ///
+    /// SPI0 is clocked by the global peripheral. Use `new_mm` for multi-media SPI1 of BL808.
+    #[inline]
+    pub fn new<GLB>(spi: SPI, pads: PADS, config: SlaveConfig, glb: &GLB) -> Self
+    where
+        PADS: Pads<0>,
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+    {
+        unsafe {
+            // Bus signals are sampled by the undivided peripheral clock.
+            glb.spi_config.modify(|c| {
+                c.enable_clock()
+                    .set_clock_source(SpiClockSource::MuxPll160M)
+                    .set_clock_divide(0)
+            });
+            glb.param_config
+                .modify(|c| c.set_spi_mode::<0>(SpiMode::Slave));
+        }
+        Self::from_parts(spi, pads, config)
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS> SpiSlave<SPI, PADS, 1> {
+    /// Create a new Serial Peripheral Interface instance in slave mode on multi-media subsystem.
+    #[inline]
+    pub fn new_mm<GLB, MMGLB>(
+        spi: SPI,
+        pads: PADS,
+        config: SlaveConfig,
+        glb: &GLB,
+        mm_glb: &MMGLB,
+    ) -> Self
+    where
+        PADS: Pads<1>,
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+        MMGLB: Deref<Target = glb::mm::RegisterBlock>,
+    {
+        unsafe {
+            // Bus signals are sampled by the undivided peripheral clock.
+            mm_glb
+                .cpu_config_0
+                .modify(|c| c.set_spi_clock_source(glb::mm::SpiClockSource::MuxPll160M));
+            mm_glb
+                .peripheral_config_0
+                .modify(|c| c.set_spi_clock_divide(0).enable_spi_clock_divide());
+            glb.param_config
+                .modify(|c| c.set_spi_mode::<1>(SpiMode::Slave));
+        }
+        Self::from_parts(spi, pads, config)
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> SpiSlave<SPI, PADS, I> {
+    #[inline]
+    fn from_parts(spi: SPI, pads: PADS, config: SlaveConfig) -> Self {
+        let mut function_config = FunctionConfig(0)
+            .disable_deglitch()
+            .disable_master_continuous()
+            .disable_byte_inverse()
+            .disable_bit_inverse()
+            .set_frame_size(FrameSize::Eight)
+            .disable_master();
+        function_config = match config.three_pin {
+            true => function_config.enable_slave_three_pin(),
+            false => function_config.disable_slave_three_pin(),
+        };
+        function_config = match config.mode.phase {
+            embedded_hal::spi::Phase::CaptureOnFirstTransition => {
+                function_config.set_clock_phase(Phase::CaptureOnFirstTransition)
+            }
+            embedded_hal::spi::Phase::CaptureOnSecondTransition => {
+                function_config.set_clock_phase(Phase::CaptureOnSecondTransition)
+            }
+        };
+        function_config = match config.mode.polarity {
+            embedded_hal::spi::Polarity::IdleHigh => {
+                function_config.set_clock_polarity(Polarity::IdleHigh)
+            }
+            embedded_hal::spi::Polarity::IdleLow => {
+                function_config.set_clock_polarity(Polarity::IdleLow)
+            }
+        };
+
+        unsafe {
+            spi.config.write(function_config);
+            spi.slave_timeout
+                .write(SlaveTimeout(0).set_threshold(config.timeout));
+            spi.fifo_config_0.write(
+                FifoConfig0(0)
+                    .disable_dma_receive()
+                    .disable_dma_transmit()
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo(),
+            );
+            spi.fifo_config_1.write(
+                FifoConfig1(0)
+                    .set_receive_threshold(0)
+                    .set_transmit_threshold(0),
+            );
+            spi.config.modify(|config| config.enable_slave());
+        }
+        SpiSlave { spi, pads }
+    }
+
+    /// Release the SPI instance and return the pads.
+    #[inline]
+    pub fn free(self) -> (SPI, PADS) {
+        unsafe { self.spi.config.modify(|config| config.disable_slave()) };
+        (self.spi, self.pads)
+    }
+
+    /// Push words into transmit queue for the host to read, returns number of words pushed.
+    #[inline]
+    pub fn write_fifo(&mut self, words: &[u8]) -> usize {
+        let space = self.spi.fifo_config_1.read().transmit_available_bytes() as usize;
+        let count = core::cmp::min(space, words.len());
+        for &word in &words[..count] {
+            unsafe { self.spi.fifo_write.write(word as u32) };
+        }
+        count
+    }
+
+    /// Pop words written by the host from receive queue, returns number of words popped.
+    #[inline]
+    pub fn read_fifo(&mut self, buf: &mut [u8]) -> usize {
+        let available = self.spi.fifo_config_1.read().receive_available_bytes() as usize;
+        let count = core::cmp::min(available, buf.len());
+        for slot in &mut buf[..count] {
+            *slot = self.spi.fifo_read.read() as u8;
+        }
+        count
+    }
+
+    /// Discard words in transmit and receive queues, clearing overflow and underflow states.
+    #[inline]
+    pub fn clear_fifo(&mut self) {
+        spi_clear_fifo(&self.spi);
+    }
+
+    /// Serve one frame of the host, sending `write` and receiving into `read`.
+    ///
+    /// Blocks until the frame ends, and returns number of words the host clocked. Words
+    /// beyond `write` are sent as zeros and words beyond `read` are discarded, up to the
+    /// longer of both buffers. A host clocking further runs the transmit queue empty, which
+    /// ends the transfer with `Error::Underrun`; a host pausing mid-frame ends it after
+    /// `SlaveConfig::timeout` idle clocks.
+    #[inline]
+    pub fn transfer_frame(&mut self, read: &mut [u8], write: &[u8]) -> Result<usize, Error> {
+        let spi = &*self.spi;
+        spi_clear_fifo(spi);
+        unsafe {
+            spi.interrupt_config.modify(|val| {
+                val.clear_interrupt(Interrupt::TransferEnd)
+                    .clear_interrupt(Interrupt::SlaveTimeout)
+                    .clear_interrupt(Interrupt::SlaveUnderrun)
+            })
+        };
+        let frame_len = max(read.len(), write.len());
+        let (mut tx, mut rx) = (0, 0);
+        loop {
+            // Words received before the end of frame are already in the queue.
+            let state = spi.interrupt_config.read();
+            let ended = state.has_interrupt(Interrupt::TransferEnd)
+                || state.has_interrupt(Interrupt::SlaveTimeout);
+            let fifo = spi.fifo_config_1.read();
+            let space = fifo.transmit_available_bytes() as usize;
+            for _ in 0..core::cmp::min(space, frame_len - tx) {
+                let word = write.get(tx).copied().unwrap_or(0);
+                unsafe { spi.fifo_write.write(word as u32) };
+                tx += 1;
+            }
+            for _ in 0..fifo.receive_available_bytes() {
+                let word = spi.fifo_read.read() as u8;
+                if let Some(slot) = read.get_mut(rx) {
+                    *slot = word;
+                }
+                rx += 1;
+            }
+            let fifo_state = spi.fifo_config_0.read();
+            if fifo_state.is_receive_overflow() {
+                spi_clear_fifo(spi);
+                return Err(Error::Overrun);
+            }
+            if state.has_interrupt(Interrupt::SlaveUnderrun) {
+                spi_clear_fifo(spi);
+                return Err(Error::Underrun);
+            }
+            if ended {
+                break;
+            }
+        }
+        // Drop words pushed ahead for a next frame that never came.
+        spi_clear_fifo(spi);
+        Ok(rx)
+    }
+
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, interrupt: Interrupt) -> bool {
+        self.spi.interrupt_config.read().has_interrupt(interrupt)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, interrupt: Interrupt) {
+        unsafe {
+            self.spi
+                .interrupt_config
+                .modify(|val| val.clear_interrupt(interrupt))
+        }
+    }
+    /// Mask interrupt.
+    #[inline]
+    pub fn mask_interrupt(&mut self, interrupt: Interrupt) {
+        unsafe {
+            self.spi
+                .interrupt_config
+                .modify(|val| val.mask_interrupt(interrupt))
+        }
+    }
+    /// Unmask and enable interrupt.
+    #[inline]
+    pub fn unmask_interrupt(&mut self, interrupt: Interrupt) {
+        unsafe {
+            self.spi
+                .interrupt_config
+                .modify(|val| val.unmask_interrupt(interrupt).enable_interrupt(interrupt))
+        }
+    }
+}
+
+#[inline]
+fn spi_clear_fifo(spi: &RegisterBlock) {
+    unsafe {
+        spi.fifo_config_0
+            .modify(|val| val.clear_transmit_fifo().clear_receive_fifo())
+    };
+}
+
+/// Valid SPI pads.
+///
+/// SPI0 and multi-media SPI1 functions share the same signal on each pad.
+///
+/// Use `()` in place of chip select pad when chip select is driven by a GPIO output,
+/// see `ExclusiveDevice` and `RefCellDevice`.
+pub trait Pads<const I: usize> {}
+
+impl<'a, 'b, const N1: usize, const N2: usize, const I: usize> Pads<I>
+    for (
+        Alternate<'a, N1, gpio::Spi<I>>,
+        Alternate<'b, N2, gpio::Spi<I>>,
+        (),
+    )
+where
+    Alternate<'a, N1, gpio::Spi<I>>: HasClkSignal,
+    Alternate<'b, N2, gpio::Spi<I>>: HasMosiSignal,
+{
+}
+
+impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize, const I: usize> Pads<I>
+    for (
+        Alternate<'a, N1, gpio::Spi<I>>,
+        Alternate<'b, N2, gpio::Spi<I>>,
+        Alternate<'c, N3, gpio::Spi<I>>,
+        (),
+    )
+where
+    Alternate<'a, N1, gpio::Spi<I>>: HasClkSignal,
+    Alternate<'b, N2, gpio::Spi<I>>: HasMosiSignal,
+    Alternate<'c, N3, gpio::Spi<I>>: HasMisoSignal,
+{
+}
+
+impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize, const I: usize> Pads<I>
+    for (
+        Alternate<'a, N1, gpio::Spi<I>>,
+        Alternate<'b, N2, gpio::Spi<I>>,
+        Alternate<'c, N3, gpio::Spi<I>>,
+    )
+where
+    Alternate<'a, N1, gpio::Spi<I>>: HasClkSignal,
+    Alternate<'b, N2, gpio::Spi<I>>: HasMosiSignal,
+    Alternate<'c, N3, gpio::Spi<I>>: HasCsSignal,
+{
+}
+
+impl<
+    'a,
+    'b,
+    'c,
+    'd,
+    const N1: usize,
+    const N2: usize,
+    const N3: usize,
+    const N4: usize,
+    const I: usize,
+> Pads<I>
+    for (
+        Alternate<'a, N1, gpio::Spi<I>>,
+        Alternate<'b, N2, gpio::Spi<I>>,
+        Alternate<'c, N3, gpio::Spi<I>>,
+        Alternate<'d, N4, gpio::Spi<I>>,
+    )
+where
+    Alternate<'a, N1, gpio::Spi<I>>: HasClkSignal,
+    Alternate<'b, N2, gpio::Spi<I>>: HasMosiSignal,
+    Alternate<'c, N3, gpio::Spi<I>>: HasMisoSignal,
+    Alternate<'d, N4, gpio::Spi<I>>: HasCsSignal,
+{
+}
+
+/// Check if target gpio `Pin` is internally connected to SPI clock signal.
+pub trait HasClkSignal {}
+
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 3, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 7, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 11, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 15, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 19, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 23, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 27, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 31, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 35, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 39, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasClkSignal for Alternate<'a, 43, gpio::Spi<I>> {}
+
+/// Check if target gpio `Pin` is internally connected to SPI MISO signal.
+pub trait HasMisoSignal {}
+
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 2, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 6, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 10, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 14, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 18, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 22, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 26, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 30, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 34, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 38, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMisoSignal for Alternate<'a, 42, gpio::Spi<I>> {}
+
+/// Check if target gpio `Pin` is internally connected to SPI MOSI signal.
+pub trait HasMosiSignal {}
+
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 1, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 5, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 9, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 13, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 17, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 21, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 25, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 29, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 33, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 37, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 41, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasMosiSignal for Alternate<'a, 45, gpio::Spi<I>> {}
+
+/// Check if target gpio `Pin` is internally connected to SPI CS signal.
+pub trait HasCsSignal {}
+
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 0, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 4, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 8, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 12, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 16, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 20, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 24, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 28, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 32, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 36, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 40, gpio::Spi<I>> {}
+impl<'a, const I: usize> HasCsSignal for Alternate<'a, 44, gpio::Spi<I>> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BusBusy, Config, ConfigError, DMA_SINK, DMA_ZERO, DmaSpi, Error, FifoConfig0, FifoConfig1,
+        FrameSize, FunctionConfig, HardwareCsDevice, Interrupt, InterruptConfig, Pads,
+        PeriodInterval, PeriodSignal, Phase, Polarity, ReceiveIgnore, RegisterBlock, SlaveConfig,
+        SlaveTimeout, Spi, SpiSlave, SpiState, Word, spi_timing,
+    };
+    use crate::dma::mock::{MockDma, MockRequest};
+    use crate::{
+        clocks::Clocks,
+        glb,
+        gpio::{self, Alternate},
+    };
+    use core::ptr::addr_of_mut;
+    use core::{
+        cell::UnsafeCell, future::Future, mem::offset_of, ops::Deref, sync::atomic::Ordering,
+    };
+    use embedded_hal_async::spi::SpiBus as _;
+    use embedded_time::rate::Hertz;
+
+    /// Register block in memory, with a received word always in the queue.
+    struct MockSpi(UnsafeCell<[u32; 0x24]>);
+
+    impl MockSpi {
+        fn new(receive_available: u32, fifo_read: u32) -> Spi<MockSpi, (), 1> {
+            let mut regs = [0u32; 0x24];
+            regs[0x84 / 4] = 0x20 | (receive_available << 8);
+            regs[0x8c / 4] = fifo_read;
+            Spi {
+                spi: MockSpi(UnsafeCell::new(regs)),
+                pads: (),
+            }
+        }
+    }
+
+    impl Deref for MockSpi {
+        type Target = RegisterBlock;
+
+        fn deref(&self) -> &RegisterBlock {
+            unsafe { &*(self.0.get() as *const RegisterBlock) }
+        }
+    }
+
+    fn poll_once<F: Future>(f: F) -> F::Output {
+        let mut f = core::pin::pin!(f);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        match f.as_mut().poll(&mut cx) {
+            core::task::Poll::Ready(ans) => ans,
+            core::task::Poll::Pending => panic!("future is pending"),
+        }
+    }
 
     #[test]
     fn struct_register_block_offset() {
@@ -994,8 +2338,8 @@ mod tests {
     }
 
     #[test]
-    fn struct_config_functions() {
-        let mut config = Config(0x0);
+    fn struct_function_config_functions() {
+        let mut config = FunctionConfig(0x0);
 
         config = config.enable_master();
         assert_eq!(config.0, 0x00000001);
@@ -1004,7 +2348,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_master_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_slave();
         assert_eq!(config.0, 0x00000002);
         assert!(config.is_slave_enabled());
@@ -1012,7 +2356,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_slave_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_frame_size(FrameSize::Eight);
         assert_eq!(config.0, 0x0);
         assert_eq!(config.frame_size(), FrameSize::Eight);
@@ -1026,7 +2370,7 @@ mod tests {
         assert_eq!(config.0, 0xc);
         assert_eq!(config.frame_size(), FrameSize::ThirtyTwo);
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_clock_polarity(Polarity::IdleHigh);
         assert_eq!(config.0, 0x00000010);
         assert_eq!(config.clock_polarity(), Polarity::IdleHigh);
@@ -1034,7 +2378,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert_eq!(config.clock_polarity(), Polarity::IdleLow);
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_clock_phase(Phase::CaptureOnFirstTransition);
         assert_eq!(config.0, 0x00000020);
         assert_eq!(config.clock_phase(), Phase::CaptureOnFirstTransition);
@@ -1042,7 +2386,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert_eq!(config.clock_phase(), Phase::CaptureOnSecondTransition);
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_bit_inverse();
         assert_eq!(config.0, 0x00000040);
         assert!(config.is_bit_inverse_enabled());
@@ -1050,7 +2394,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_bit_inverse_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_byte_inverse();
         assert_eq!(config.0, 0x00000080);
         assert!(config.is_byte_inverse_enabled());
@@ -1058,7 +2402,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_byte_inverse_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_receive_ignore();
         assert_eq!(config.0, 0x00000100);
         assert!(config.is_receive_ignore_enabled());
@@ -1066,7 +2410,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_receive_ignore_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_master_continuous();
         assert_eq!(config.0, 0x00000200);
         assert!(config.is_master_continuous_enabled());
@@ -1074,7 +2418,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_master_continuous_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_slave_three_pin();
         assert_eq!(config.0, 0x00000400);
         assert!(config.is_slave_three_pin_enabled());
@@ -1082,7 +2426,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_slave_three_pin_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.enable_deglitch();
         assert_eq!(config.0, 0x00000800);
         assert!(config.is_deglitch_enabled());
@@ -1090,7 +2434,7 @@ mod tests {
         assert_eq!(config.0, 0x00000000);
         assert!(!config.is_deglitch_enabled());
 
-        config = Config(0x0);
+        config = FunctionConfig(0x0);
         config = config.set_deglitch_cycle(0x11);
         assert_eq!(config.0, 0x00011000);
         assert_eq!(config.deglitch_cycle(), 0x01);
@@ -1261,4 +2605,365 @@ mod tests {
         assert_eq!(config.0, 0x1f000000);
         assert_eq!(config.receive_threshold(), 0x1f);
     }
+
+    #[test]
+    fn function_spi_timing() {
+        let source = Hertz(160_000_000);
+        assert_eq!(spi_timing(source, Hertz(40_000_000)), Ok((0, [1, 1])));
+        assert_eq!(spi_timing(source, Hertz(30_000_000)), Ok((0, [2, 2])));
+        assert_eq!(spi_timing(source, Hertz(400_000)), Ok((0, [199, 199])));
+        assert_eq!(spi_timing(source, Hertz(100_000)), Ok((3, [199, 199])));
+        assert_eq!(spi_timing(source, Hertz(80_000_000)), Ok((0, [0, 0])));
+        assert_eq!(spi_timing(source, Hertz(100_000_000)), Ok((0, [0, 0])));
+        assert_eq!(
+            spi_timing(source, Hertz(200_000_000)),
+            Err(ConfigError::FrequencyTooHigh)
+        );
+        assert_eq!(
+            spi_timing(source, Hertz(1_000)),
+            Err(ConfigError::FrequencyTooLow)
+        );
+        assert_eq!(
+            spi_timing(source, Hertz(0)),
+            Err(ConfigError::FrequencyTooLow)
+        );
+    }
+
+    #[test]
+    fn async_spi_transfer() {
+        let state = SpiState::new();
+        let mut spi = MockSpi::new(1, 0x5a).into_async(&state);
+        let mut words = [0x01, 0x02, 0x03];
+        poll_once(spi.transfer_in_place(&mut words)).unwrap();
+        assert_eq!(words, [0x5a; 3]);
+        let mut read = [0u8; 4];
+        poll_once(spi.transfer(&mut read, &[0x11, 0x22])).unwrap();
+        assert_eq!(read, [0x5a; 4]);
+        poll_once(spi.write(&[0x11, 0x22])).unwrap();
+        assert_eq!(unsafe { (*spi.spi.0.get())[0x88 / 4] } & 0xff, 0x22);
+        // Master is disabled and interrupts masked once the transfer completes.
+        assert!(!spi.spi.config.read().is_master_enabled());
+        let config = spi.spi.interrupt_config.read();
+        assert!(config.is_interrupted(Interrupt::ReceiveFifoReady));
+        assert!(config.is_interrupt_enabled(Interrupt::ReceiveFifoReady));
+    }
+
+    fn dma_spi(dma: &MockDma) -> DmaSpi<'_, MockSpi, (), 1, MockRequest> {
+        let tx = MockRequest {
+            id: 11,
+            address: 0x3000_8088,
+        };
+        let rx = MockRequest {
+            id: 10,
+            address: 0x3000_808c,
+        };
+        MockSpi::new(0, 0).with_dma((dma.channel(0), tx), (dma.channel(1), rx))
+    }
+
+    #[test]
+    fn struct_dma_spi_chunked_transfer() {
+        static mut BUFFER: [u8; 4100] = [0; 4100];
+        let dma = MockDma::new();
+        let mut spi = dma_spi(&dma);
+        let buffer = unsafe { &mut *addr_of_mut!(BUFFER) };
+        let ptr = buffer.as_ptr() as u32;
+        let mut transfer = spi.transfer_in_place(buffer).ok().unwrap();
+        // First chunk fills one linked list item on both channels.
+        assert!(transfer.spi.spi.config.read().is_master_enabled());
+        assert_eq!(dma.channel_reg(0, 0x00), ptr);
+        assert_eq!(dma.channel_reg(1, 0x04), ptr);
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 4064);
+        assert_eq!(dma.channel_reg(1, 0x0c) & 0xfff, 4064);
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 1);
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 1);
+        assert!(!transfer.is_done());
+
+        // Next chunk starts once receive channel has moved the first one.
+        dma.complete(0);
+        assert!(!transfer.is_done());
+        dma.complete(1);
+        assert!(!transfer.is_done());
+        assert_eq!(dma.channel_reg(0, 0x00), ptr + 4064);
+        assert_eq!(dma.channel_reg(1, 0x04), ptr + 4064);
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 36);
+        assert_eq!(dma.channel_reg(1, 0x0c) & 0xfff, 36);
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 1);
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 1);
+
+        dma.complete(0);
+        dma.complete(1);
+        let returned = transfer.wait();
+        assert_eq!(returned.as_ptr() as u32, ptr);
+        assert!(!spi.spi.config.read().is_master_enabled());
+
+        let (spi, _, _) = spi.free();
+        assert!(!spi.spi.fifo_config_0.read().is_dma_transmit_enabled());
+        assert!(!spi.spi.fifo_config_0.read().is_dma_receive_enabled());
+    }
+
+    #[test]
+    fn struct_dma_spi_read_write() {
+        static mut BUFFER: [u8; 8] = [0; 8];
+        let dma = MockDma::new();
+        let mut spi = dma_spi(&dma);
+        let buffer = unsafe { &mut *addr_of_mut!(BUFFER) };
+        let ptr = buffer.as_ptr() as u32;
+        // Read sends the same zero byte over and over.
+        let transfer = spi.read(buffer).ok().unwrap();
+        assert_eq!(dma.channel_reg(0, 0x00), &DMA_ZERO as *const u8 as u32);
+        assert_eq!(dma.channel_reg(0, 0x0c) & (1 << 26), 0);
+        assert_eq!(dma.channel_reg(1, 0x04), ptr);
+        assert_ne!(dma.channel_reg(1, 0x0c) & (1 << 27), 0);
+        dma.complete(0);
+        dma.complete(1);
+        let buffer = transfer.wait();
+
+        // Write discards received bytes into the same sink byte.
+        let transfer = spi.write(buffer).ok().unwrap();
+        assert_eq!(dma.channel_reg(0, 0x00), ptr);
+        assert_ne!(dma.channel_reg(0, 0x0c) & (1 << 26), 0);
+        assert_eq!(dma.channel_reg(1, 0x04), DMA_SINK.as_ptr() as u32);
+        assert_eq!(dma.channel_reg(1, 0x0c) & (1 << 27), 0);
+        dma.complete(0);
+        dma.complete(1);
+        transfer.wait();
+
+        // Empty buffers are returned back.
+        static mut EMPTY: [u8; 0] = [];
+        assert!(spi.read(unsafe { &mut *addr_of_mut!(EMPTY) }).is_err());
+    }
+
+    #[test]
+    fn struct_dma_spi_drop() {
+        static mut BUFFER: [u8; 8] = [0; 8];
+        let dma = MockDma::new();
+        let mut spi = dma_spi(&dma);
+        let buffer = unsafe { &mut *addr_of_mut!(BUFFER) };
+        let transfer = spi.transfer_in_place(buffer).ok().unwrap();
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 1);
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 1);
+        drop(transfer);
+        // Dropped transfer stops both channels and master before the buffer is released.
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 0);
+        assert!(!spi.spi.config.read().is_master_enabled());
+        // Transmit and receive FIFO clear bits.
+        assert_eq!(spi.spi.fifo_config_0.read().0 & 0b1100, 0b1100);
+    }
+
+    #[test]
+    fn async_spi_cancel() {
+        let state = SpiState::new();
+        let mut spi = MockSpi::new(0, 0x5a).into_async(&state);
+        let regs = spi.spi.0.get() as *mut u32;
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut buf = [0u8; 4];
+        {
+            let mut transfer = core::pin::pin!(spi.transfer(&mut buf, &[0x11, 0x22]));
+            assert!(transfer.as_mut().poll(&mut cx).is_pending());
+            let spi = unsafe { &*(regs as *const RegisterBlock) };
+            assert!(spi.config.read().is_master_enabled());
+            assert!(
+                !spi.interrupt_config
+                    .read()
+                    .is_interrupted(Interrupt::ReceiveFifoReady)
+            );
+        }
+        // Dropped transfer stops master and leaves no words queued.
+        assert!(!spi.spi.config.read().is_master_enabled());
+        assert!(
+            spi.spi
+                .interrupt_config
+                .read()
+                .is_interrupted(Interrupt::ReceiveFifoReady)
+        );
+        // Transmit and receive FIFO clear bits.
+        assert_eq!(spi.spi.fifo_config_0.read().0 & 0b1100, 0b1100);
+    }
+
+    #[test]
+    fn async_spi_on_interrupt() {
+        let state = SpiState::new();
+        let mut spi = MockSpi::new(0, 0x5a).into_async(&state);
+        // Mock registers move together with the peripheral, point the state to the new place.
+        let regs = spi.spi.0.get() as *mut u32;
+        state.ref_to_spi.store(regs as usize, Ordering::Release);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut buf = [0u8; 2];
+        let mut read = core::pin::pin!(spi.read(&mut buf));
+        assert!(read.as_mut().poll(&mut cx).is_pending());
+        // Waiting on received words unmasks receive FIFO ready interrupt.
+        let spi = unsafe { &*(regs as *const RegisterBlock) };
+        let config = spi.interrupt_config.read();
+        assert!(!config.is_interrupted(Interrupt::ReceiveFifoReady));
+        assert!(config.is_interrupted(Interrupt::TransferEnd));
+        unsafe {
+            *regs.add(1) |= 1 << (Interrupt::ReceiveFifoReady as u32);
+            *regs.add(0x84 / 4) |= 2 << 8;
+        }
+        state.on_interrupt();
+        assert!(
+            spi.interrupt_config
+                .read()
+                .is_interrupted(Interrupt::ReceiveFifoReady)
+        );
+        assert!(matches!(
+            read.as_mut().poll(&mut cx),
+            core::task::Poll::Ready(Ok(()))
+        ));
+    }
+
+    #[test]
+    fn slave_transfer_frame() {
+        let Spi { spi, pads } = MockSpi::new(1, 0x5a);
+        // Host has deasserted chip select after one word.
+        unsafe { (*spi.0.get())[1] = 1 << (Interrupt::TransferEnd as u32) };
+        let mut slave: SpiSlave<_, _, 1> = SpiSlave { spi, pads };
+        let mut read = [0u8; 4];
+        assert_eq!(slave.transfer_frame(&mut read, &[0x11, 0x22]), Ok(1));
+        assert_eq!(read, [0x5a, 0, 0, 0]);
+
+        unsafe { (*slave.spi.0.get())[1] |= 1 << (Interrupt::SlaveUnderrun as u32) };
+        assert_eq!(
+            slave.transfer_frame(&mut read, &[0x11]),
+            Err(Error::Underrun)
+        );
+
+        unsafe {
+            (*slave.spi.0.get())[1] = 1 << (Interrupt::SlaveTimeout as u32);
+            (*slave.spi.0.get())[0x80 / 4] = 1 << 6;
+        }
+        assert_eq!(slave.transfer_frame(&mut read, &[]), Err(Error::Overrun));
+    }
+
+    #[test]
+    fn slave_transfer_frame_length() {
+        let Spi { spi, pads } = MockSpi::new(0, 0x5a);
+        unsafe { (*spi.0.get())[1] = 1 << (Interrupt::TransferEnd as u32) };
+        let mut slave: SpiSlave<_, _, 1> = SpiSlave { spi, pads };
+        let fifo_write =
+            |slave: &SpiSlave<MockSpi, (), 1>| unsafe { (*slave.spi.0.get())[0x88 / 4] };
+        // Transmit queue is fed up to the longer buffer, not with endless zeros.
+        assert_eq!(
+            slave.transfer_frame(&mut [0u8; 1], &[0x11, 0x22, 0x33]),
+            Ok(0)
+        );
+        assert_eq!(fifo_write(&slave), 0x33);
+        assert_eq!(slave.transfer_frame(&mut [0u8; 4], &[0x44]), Ok(0));
+        assert_eq!(fifo_write(&slave), 0);
+        unsafe { (*slave.spi.0.get())[0x88 / 4] = 0xff };
+        assert_eq!(slave.transfer_frame(&mut [], &[]), Ok(0));
+        assert_eq!(fifo_write(&slave), 0xff);
+    }
+
+    #[test]
+    fn blocking_spi_word_sizes() {
+        use embedded_hal::spi::SpiBus;
+
+        assert_eq!(0x1234u16.into_fifo(), 0x3412);
+        assert_eq!(u16::from_fifo(0x3412), 0x1234);
+        assert_eq!(0x1234_5678u32.into_fifo(), 0x7856_3412);
+        assert_eq!(u32::from_fifo(0x7856_3412), 0x1234_5678);
+        assert_eq!(0x5au8.into_fifo(), 0x5a);
+
+        let mut spi = MockSpi::new(1, 0x3412);
+        let mut read = [0u16; 2];
+        SpiBus::<u16>::transfer(&mut spi, &mut read, &[0xabcd, 0x1234]).unwrap();
+        assert_eq!(read, [0x1234; 2]);
+        assert_eq!(spi.spi.config.read().frame_size(), FrameSize::Sixteen);
+        assert_eq!(unsafe { (*spi.spi.0.get())[0x88 / 4] }, 0x3412);
+
+        let mut read = [0u32; 1];
+        SpiBus::<u32>::transfer_in_place(&mut spi, &mut read).unwrap();
+        assert_eq!(read, [0x1234_0000]);
+        assert_eq!(spi.spi.config.read().frame_size(), FrameSize::ThirtyTwo);
+    }
+
+    /// Records events of chip select, bus and delay in order.
+    struct Recorder<'a>(
+        &'a core::cell::RefCell<[u8; 16]>,
+        &'a core::cell::Cell<usize>,
+    );
+
+    impl Recorder<'_> {
+        fn push(&self, event: u8) {
+            let idx = self.1.get();
+            self.0.borrow_mut()[idx] = event;
+            self.1.set(idx + 1);
+        }
+    }
+
+    impl embedded_hal::delay::DelayNs for Recorder<'_> {
+        fn delay_ns(&mut self, _: u32) {
+            self.push(b'd');
+        }
+    }
+
+    #[test]
+    fn hardware_cs_device_transaction() {
+        use embedded_hal::spi::{Operation, SpiDevice};
+
+        let (events, idx) = (
+            core::cell::RefCell::new([0u8; 16]),
+            core::cell::Cell::new(0),
+        );
+        let mut device = HardwareCsDevice::new(MockSpi::new(1, 0x5a), Recorder(&events, &idx));
+        let mut buf = [0u8; 2];
+        device
+            .transaction(&mut [Operation::Write(&[]), Operation::TransferInPlace(&mut buf)])
+            .unwrap();
+        assert_eq!(buf, [0x5a; 2]);
+        // Chip select would be released between operations or during delays.
+        assert_eq!(
+            device.transaction(&mut [Operation::Write(&[0x01, 0x02]), Operation::Read(&mut buf),]),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            device.transaction(&mut [Operation::<u8>::DelayNs(1000)]),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(&events.borrow()[..idx.get()], b"");
+        SpiDevice::<u8>::transaction(&mut device, &mut []).unwrap();
+        let (spi, _) = device.free();
+        // Chip select is released by disabling master after the transaction.
+        assert!(!spi.spi.config.read().is_master_enabled());
+    }
+    #[test]
+    fn trait_pads_spi0() {
+        fn assert_pads<P: Pads<0>>() {}
+        assert_pads::<(
+            Alternate<'static, 3, gpio::Spi<0>>,
+            Alternate<'static, 1, gpio::Spi<0>>,
+            (),
+        )>();
+        assert_pads::<(
+            Alternate<'static, 7, gpio::Spi<0>>,
+            Alternate<'static, 5, gpio::Spi<0>>,
+            Alternate<'static, 6, gpio::Spi<0>>,
+            Alternate<'static, 4, gpio::Spi<0>>,
+        )>();
+        type Pads0<'a> = (
+            Alternate<'a, 3, gpio::Spi<0>>,
+            Alternate<'a, 1, gpio::Spi<0>>,
+            Alternate<'a, 2, gpio::Spi<0>>,
+            Alternate<'a, 0, gpio::Spi<0>>,
+        );
+        // Only type checked, SPI0 pads cannot be built without the global peripheral.
+        fn master<'a>(
+            spi: MockSpi,
+            pads: Pads0<'a>,
+            glb: &glb::v2::RegisterBlock,
+            clocks: &Clocks,
+        ) -> Result<Spi<MockSpi, Pads0<'a>, 0>, ConfigError> {
+            Spi::new(spi, pads, Config::default(), &glb, clocks)
+        }
+        fn slave<'a>(
+            spi: MockSpi,
+            pads: Pads0<'a>,
+            glb: &glb::v2::RegisterBlock,
+        ) -> SpiSlave<MockSpi, Pads0<'a>, 0> {
+            SpiSlave::new(spi, pads, SlaveConfig::default(), &glb)
+        }
+        let _ = (master, slave);
+    }
 }
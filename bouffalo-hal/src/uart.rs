@@ -15,6 +15,10 @@ mod blocking;
 pub use blocking::*;
 mod asynch;
 pub use asynch::*;
+mod dma;
+pub use dma::*;
+mod lin;
+pub use lin::*;
 
 /// Extend constructor to owned UART register blocks.
 pub trait UartExt<'a, PADS, const I: usize> {
@@ -1,11 +1,12 @@
 use super::{
-    Config, ConfigError, Error, Interrupt, InterruptClear, Pads, RegisterBlock, uart_config,
+    Config, ConfigError, Error, Interrupt, InterruptClear, InterruptEnable, Pads, RegisterBlock,
+    uart_config,
 };
 use crate::clocks::Clocks;
 use core::{
-    future::Future,
+    future::{Future, poll_fn},
     pin::Pin,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
     task::{Context, Poll},
 };
 
@@ -32,8 +33,14 @@ impl<'a, PADS> AsyncSerial<'a, PADS> {
         PADS: Pads<I>,
     {
         // Calculate transmit interval and register values from configuration.
-        let (bit_period, data_config, transmit_config, receive_config) =
-            uart_config::<I, PADS>(config, &clocks)?;
+        let (
+            bit_period,
+            data_config,
+            transmit_config,
+            receive_config,
+            receive_timeout,
+            rs485_config,
+        ) = uart_config::<I, PADS>(config, &clocks)?;
 
         // Write bit period.
         unsafe { uart.bit_period.write(bit_period) };
@@ -43,26 +50,75 @@ impl<'a, PADS> AsyncSerial<'a, PADS> {
         unsafe { uart.transmit_config.write(transmit_config.enable_freerun()) };
         // Configure receive feature.
         unsafe { uart.receive_config.write(receive_config) };
+        // Write receive time-out.
+        unsafe { uart.receive_timeout.write(receive_timeout) };
+        // Configure RS-485 driver-enable.
+        unsafe { uart.rs485_config.write(rs485_config) };
 
-        state
-            .ref_to_serial
-            .store(&*uart as *const _ as usize, Ordering::Release);
+        state.attach(uart);
 
         Ok(AsyncSerial { uart, pads, state })
     }
 
+    /// Read bytes until the receive line goes idle or the buffer is full.
+    ///
+    /// The read ends when no bytes arrive for the receive time-out configured by
+    /// `Config::set_receive_timeout`, or when the receive transfer length is reached.
+    /// Returns the number of bytes read in this frame.
+    #[inline]
+    pub async fn read_until_idle(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        uart_read_until_idle_async(self.uart, buf, self.state).await
+    }
+
     /// Release serial instance and return its peripheral and pads.
     #[inline]
     pub fn free(self) -> PADS {
         self.pads
     }
+
+    /// Split serial instance into transmit and receive halves.
+    ///
+    /// Both halves share the waker set, so they can be used from different tasks.
+    #[inline]
+    pub fn split<const I: usize>(self) -> <PADS as Pads<I>>::AsyncSplit<'a>
+    where
+        PADS: Pads<I>,
+    {
+        self.pads.async_split(self.uart, self.state)
+    }
+}
+
+/// Transmit half from splitted async/await serial structure.
+pub struct AsyncTransmitHalf<'a, PADS> {
+    pub(crate) uart: &'a RegisterBlock,
+    pub(crate) state: &'a SerialState,
+    pub(crate) _pads: PADS,
+}
+
+/// Receive half from splitted async/await serial structure.
+pub struct AsyncReceiveHalf<'a, PADS> {
+    pub(crate) uart: &'a RegisterBlock,
+    pub(crate) state: &'a SerialState,
+    pub(crate) _pads: PADS,
+}
+
+impl<'a, PADS> AsyncReceiveHalf<'a, PADS> {
+    /// Read bytes until the receive line goes idle or the buffer is full.
+    ///
+    /// See `AsyncSerial::read_until_idle` for details.
+    #[inline]
+    pub async fn read_until_idle(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        uart_read_until_idle_async(self.uart, buf, self.state).await
+    }
 }
 
 /// Set of wakers as the state for an async/await serial peripheral.
 #[derive(Debug)]
 pub struct SerialState {
-    transmit_ready: atomic_waker::AtomicWaker,
-    receive_ready: atomic_waker::AtomicWaker,
+    pub(super) transmit_ready: atomic_waker::AtomicWaker,
+    pub(super) receive_ready: atomic_waker::AtomicWaker,
+    pub(super) receive_idle: AtomicBool,
+    interrupt_enable: AtomicU32,
     ref_to_serial: AtomicUsize,
 }
 
@@ -73,18 +129,59 @@ impl SerialState {
         SerialState {
             transmit_ready: atomic_waker::AtomicWaker::new(),
             receive_ready: atomic_waker::AtomicWaker::new(),
+            receive_idle: AtomicBool::new(false),
+            interrupt_enable: AtomicU32::new(0),
             ref_to_serial: AtomicUsize::new(0),
         }
     }
+    /// Point this state to the serial peripheral, taking over its interrupt enable register.
+    #[inline]
+    pub(super) fn attach(&self, uart: &RegisterBlock) {
+        self.interrupt_enable
+            .store(uart.interrupt_enable.read().0, Ordering::Release);
+        self.ref_to_serial
+            .store(uart as *const _ as usize, Ordering::Release);
+    }
+    /// Change interrupt enable register shared by transmit and receive halves.
+    ///
+    /// Changes are made on an atomic copy of the register, and the copy is written until
+    /// no other change happened in between. A half preempted during the update by the other
+    /// half never loses the other half's interrupt enable bits.
+    #[inline]
+    pub(super) fn modify_interrupts(
+        &self,
+        uart: &RegisterBlock,
+        f: impl Fn(InterruptEnable) -> InterruptEnable,
+    ) {
+        let _ = self
+            .interrupt_enable
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |val| {
+                Some(f(InterruptEnable(val)).0)
+            });
+        loop {
+            let val = self.interrupt_enable.load(Ordering::Acquire);
+            unsafe { uart.interrupt_enable.write(InterruptEnable(val)) };
+            if self.interrupt_enable.load(Ordering::Acquire) == val {
+                break;
+            }
+        }
+    }
     /// Use this waker set to handle interrupt.
     #[inline]
     pub fn on_interrupt(&self) {
         let uart =
             unsafe { &*(self.ref_to_serial.load(Ordering::Acquire) as *const RegisterBlock) };
         let state = uart.interrupt_state.read();
+        if state.has_interrupt(Interrupt::ReceiveTimeout)
+            || state.has_interrupt(Interrupt::ReceiveEnd)
+        {
+            self.receive_idle.store(true, Ordering::Release);
+        }
         for (interrupt, waker) in [
             (Interrupt::ReceiveFifoReady, &self.receive_ready),
             (Interrupt::TransmitFifoReady, &self.transmit_ready),
+            (Interrupt::ReceiveTimeout, &self.receive_ready),
+            (Interrupt::ReceiveEnd, &self.receive_ready),
         ] {
             if state.has_interrupt(interrupt) {
                 waker.wake();
@@ -97,6 +194,24 @@ impl SerialState {
     }
 }
 
+/// Disables serial interrupts when a wait on them ends or is abandoned.
+pub(super) struct DisableOnDrop<'a, const N: usize>(
+    pub &'a RegisterBlock,
+    pub &'a SerialState,
+    pub [Interrupt; N],
+);
+
+impl<const N: usize> Drop for DisableOnDrop<'_, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.1.modify_interrupts(self.0, |val| {
+            self.2
+                .iter()
+                .fold(val, |val, &interrupt| val.disable_interrupt(interrupt))
+        });
+    }
+}
+
 struct WaitForInterrupt<'r> {
     uart: &'r RegisterBlock,
     interrupt: Interrupt,
@@ -141,17 +256,17 @@ impl Future for WaitForInterrupt<'_> {
 async fn uart_write_async(
     uart: &RegisterBlock,
     buf: &[u8],
-    registry: &atomic_waker::AtomicWaker,
+    state: &SerialState,
 ) -> Result<usize, Error> {
     let buf = match buf.len() {
         0 => return Ok(0),
         _ => buf,
     };
-    unsafe {
-        uart.interrupt_enable
-            .modify(|val| val.enable_interrupt(Interrupt::TransmitFifoReady))
-    };
-    WaitForInterrupt::new(uart, Interrupt::TransmitFifoReady, registry).await;
+    state.modify_interrupts(uart, |val| {
+        val.enable_interrupt(Interrupt::TransmitFifoReady)
+    });
+    let _guard = DisableOnDrop(uart, state, [Interrupt::TransmitFifoReady]);
+    WaitForInterrupt::new(uart, Interrupt::TransmitFifoReady, &state.transmit_ready).await;
     let len = core::cmp::min(
         uart.fifo_config_1.read().transmit_available_bytes() as usize,
         buf.len(),
@@ -166,17 +281,31 @@ async fn uart_write_async(
 async fn uart_read_async(
     uart: &RegisterBlock,
     buf: &mut [u8],
-    registry: &atomic_waker::AtomicWaker,
+    state: &SerialState,
 ) -> Result<usize, Error> {
     let buf = match buf.len() {
         0 => return Ok(0),
         _ => buf,
     };
-    unsafe {
-        uart.interrupt_enable
-            .modify(|val| val.enable_interrupt(Interrupt::ReceiveFifoReady))
-    };
-    WaitForInterrupt::new(uart, Interrupt::ReceiveFifoReady, registry).await;
+    // Wake on receive time-out as well, or bytes below FIFO threshold would never be read.
+    state.modify_interrupts(uart, |val| {
+        val.enable_interrupt(Interrupt::ReceiveFifoReady)
+            .enable_interrupt(Interrupt::ReceiveTimeout)
+    });
+    let _guard = DisableOnDrop(
+        uart,
+        state,
+        [Interrupt::ReceiveFifoReady, Interrupt::ReceiveTimeout],
+    );
+    poll_fn(|cx| {
+        state.receive_ready.register(cx.waker());
+        if uart.fifo_config_1.read().receive_available_bytes() > 0 {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
     let len = core::cmp::min(
         uart.fifo_config_1.read().receive_available_bytes() as usize,
         buf.len(),
@@ -187,6 +316,62 @@ async fn uart_read_async(
     Ok(len)
 }
 
+#[inline]
+async fn uart_read_until_idle_async(
+    uart: &RegisterBlock,
+    buf: &mut [u8],
+    state: &SerialState,
+) -> Result<usize, Error> {
+    if buf.is_empty() {
+        return Ok(0);
+    }
+    // Drop idle events from before this frame.
+    if uart.fifo_config_1.read().receive_available_bytes() == 0 {
+        state.receive_idle.store(false, Ordering::Release);
+    }
+    // Transfer length of zero raises receive end on every byte, use time-out only.
+    let receive_end = uart.receive_config.read().transfer_length() != 0;
+    state.modify_interrupts(uart, |val| {
+        let val = val
+            .enable_interrupt(Interrupt::ReceiveFifoReady)
+            .enable_interrupt(Interrupt::ReceiveTimeout);
+        match receive_end {
+            true => val.enable_interrupt(Interrupt::ReceiveEnd),
+            false => val,
+        }
+    });
+    let _guard = DisableOnDrop(
+        uart,
+        state,
+        [
+            Interrupt::ReceiveFifoReady,
+            Interrupt::ReceiveTimeout,
+            Interrupt::ReceiveEnd,
+        ],
+    );
+    let mut len = 0;
+    poll_fn(|cx| {
+        state.receive_ready.register(cx.waker());
+        // Check idle flag before draining, so bytes arrived ahead of the idle event are kept.
+        let idle = state.receive_idle.load(Ordering::Acquire);
+        let available = uart.fifo_config_1.read().receive_available_bytes() as usize;
+        for slot in buf[len..].iter_mut().take(available) {
+            *slot = uart.fifo_read.read();
+            len += 1;
+        }
+        if idle {
+            state.receive_idle.store(false, Ordering::Release);
+        }
+        if len == buf.len() || (idle && len > 0) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+    Ok(len)
+}
+
 impl<'a, PADS> embedded_io_async::ErrorType for AsyncSerial<'a, PADS> {
     type Error = Error;
 }
@@ -194,13 +379,128 @@ impl<'a, PADS> embedded_io_async::ErrorType for AsyncSerial<'a, PADS> {
 impl<'a, PADS> embedded_io_async::Write for AsyncSerial<'a, PADS> {
     #[inline]
     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
-        uart_write_async(&self.uart, buf, &self.state.transmit_ready).await
+        uart_write_async(self.uart, buf, self.state).await
     }
 }
 
 impl<'a, PADS> embedded_io_async::Read for AsyncSerial<'a, PADS> {
     #[inline]
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        uart_read_async(&self.uart, buf, &self.state.receive_ready).await
+        uart_read_async(self.uart, buf, self.state).await
+    }
+}
+
+impl<'a, PADS> embedded_io_async::ErrorType for AsyncTransmitHalf<'a, PADS> {
+    type Error = Error;
+}
+
+impl<'a, PADS> embedded_io_async::ErrorType for AsyncReceiveHalf<'a, PADS> {
+    type Error = Error;
+}
+
+impl<'a, PADS> embedded_io_async::Write for AsyncTransmitHalf<'a, PADS> {
+    #[inline]
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        uart_write_async(self.uart, buf, self.state).await
+    }
+}
+
+impl<'a, PADS> embedded_io_async::Read for AsyncReceiveHalf<'a, PADS> {
+    #[inline]
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        uart_read_async(self.uart, buf, self.state).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SerialState, uart_read_async, uart_read_until_idle_async, uart_write_async};
+    use crate::uart::RegisterBlock;
+    use core::cell::UnsafeCell;
+    use core::future::Future;
+    use core::task::Poll;
+
+    struct MockUart(UnsafeCell<[u32; 0x90 / 4]>);
+
+    impl MockUart {
+        fn new() -> Self {
+            MockUart(UnsafeCell::new([0; 0x90 / 4]))
+        }
+        fn regs(&self) -> &RegisterBlock {
+            unsafe { &*(self.0.get() as *const RegisterBlock) }
+        }
+        fn reg(&self, offset: usize) -> u32 {
+            unsafe { (*self.0.get())[offset / 4] }
+        }
+        fn set_reg(&self, offset: usize, val: u32) {
+            unsafe { (*self.0.get())[offset / 4] = val };
+        }
+    }
+
+    #[test]
+    fn async_uart_read_interrupts() {
+        let (uart, state) = (MockUart::new(), SerialState::new());
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut buf = [0u8; 4];
+        {
+            let mut read = core::pin::pin!(uart_read_async(uart.regs(), &mut buf, &state));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            // Wakes on receive FIFO ready or on receive time-out.
+            assert_eq!(uart.reg(0x2c), (1 << 3) | (1 << 4));
+            uart.set_reg(0x8c, 0x5a);
+            uart.set_reg(0x84, 2 << 8);
+            assert!(matches!(read.as_mut().poll(&mut cx), Poll::Ready(Ok(2))));
+            assert_eq!(uart.reg(0x2c), 0);
+        }
+        assert_eq!(&buf[..2], &[0x5a, 0x5a]);
+
+        // Abandoned reads disable their interrupts as well.
+        uart.set_reg(0x84, 0);
+        {
+            let mut read = core::pin::pin!(uart_read_async(uart.regs(), &mut buf, &state));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            assert_ne!(uart.reg(0x2c), 0);
+        }
+        assert_eq!(uart.reg(0x2c), 0);
+    }
+
+    #[test]
+    fn async_uart_read_until_idle_interrupts() {
+        let (uart, state) = (MockUart::new(), SerialState::new());
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut buf = [0u8; 4];
+        // Non-zero transfer length, so receive end is used as well.
+        uart.set_reg(0x04, 8 << 16);
+        {
+            let mut read =
+                core::pin::pin!(uart_read_until_idle_async(uart.regs(), &mut buf, &state));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(uart.reg(0x2c), (1 << 1) | (1 << 3) | (1 << 4));
+        }
+        assert_eq!(uart.reg(0x2c), 0);
+
+        {
+            let mut read =
+                core::pin::pin!(uart_read_until_idle_async(uart.regs(), &mut buf, &state));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            uart.set_reg(0x84, 4 << 8);
+            assert!(matches!(read.as_mut().poll(&mut cx), Poll::Ready(Ok(4))));
+        }
+        assert_eq!(uart.reg(0x2c), 0);
+    }
+
+    #[test]
+    fn async_uart_write_interrupts() {
+        let (uart, state) = (MockUart::new(), SerialState::new());
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        {
+            let mut write = core::pin::pin!(uart_write_async(uart.regs(), b"hi", &state));
+            assert!(write.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(uart.reg(0x2c), 1 << 2);
+            uart.set_reg(0x20, 1 << 2);
+            uart.set_reg(0x84, 32);
+            assert!(matches!(write.as_mut().poll(&mut cx), Poll::Ready(Ok(2))));
+        }
+        assert_eq!(uart.reg(0x2c), 0);
     }
 }
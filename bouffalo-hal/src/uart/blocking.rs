@@ -1,5 +1,9 @@
-use super::{Config, ConfigError, Error, Pads, RegisterBlock, uart_config};
+use super::{
+    AutoBaudrate, Config, ConfigError, DmaSerial, DmaSerialBuffers, Error, Interrupt,
+    InterruptClear, Pads, RegisterBlock, SerialState, lin_frame_id, lin_protected_id, uart_config,
+};
 use crate::clocks::Clocks;
+use crate::dma::{DmaState, Endpoint, PeripheralId, TypedChannel};
 
 /// Managed blocking serial peripheral.
 pub struct BlockingSerial<'a, PADS> {
@@ -21,8 +25,14 @@ impl<'a, PADS> BlockingSerial<'a, PADS> {
         PADS: Pads<I>,
     {
         // Calculate transmit interval and register values from configuration.
-        let (bit_period, data_config, transmit_config, receive_config) =
-            uart_config::<I, PADS>(config, &clocks)?;
+        let (
+            bit_period,
+            data_config,
+            transmit_config,
+            receive_config,
+            receive_timeout,
+            rs485_config,
+        ) = uart_config::<I, PADS>(config, &clocks)?;
 
         // Write bit period.
         unsafe { uart.bit_period.write(bit_period) };
@@ -35,6 +45,10 @@ impl<'a, PADS> BlockingSerial<'a, PADS> {
         unsafe { uart.transmit_config.write(val) };
         // Configure receive feature.
         unsafe { uart.receive_config.write(receive_config) };
+        // Write receive time-out.
+        unsafe { uart.receive_timeout.write(receive_timeout) };
+        // Configure RS-485 driver-enable.
+        unsafe { uart.rs485_config.write(rs485_config) };
 
         Ok(Self { uart, pads })
     }
@@ -67,6 +81,181 @@ impl<'a, PADS> BlockingSerial<'a, PADS> {
         self
     }
 
+    /// Get DMA endpoint on transmit FIFO of this UART.
+    ///
+    /// `request` must be the transmit request of this UART; use `enable_tx_dma` to let the
+    /// FIFO raise it.
+    #[inline]
+    pub fn tx_dma_endpoint<T: PeripheralId>(&self, request: T) -> Endpoint<T> {
+        Endpoint::from_register(request, &self.uart.fifo_write)
+    }
+
+    /// Get DMA endpoint on receive FIFO of this UART.
+    ///
+    /// `request` must be the receive request of this UART; use `enable_rx_dma` to let the
+    /// FIFO raise it.
+    #[inline]
+    pub fn rx_dma_endpoint<T: PeripheralId>(&self, request: T) -> Endpoint<T> {
+        Endpoint::from_register(request, &self.uart.fifo_read)
+    }
+
+    /// Detect baudrate from incoming data and reconfigure bit period of both halves.
+    ///
+    /// Blocks until the remote sends a start bit or the 0x55 pattern depending on `method`,
+    /// then returns the measured bit period in clocks.
+    #[inline]
+    pub fn detect_baudrate(&mut self, method: AutoBaudrate) -> u16 {
+        let interrupt = match method {
+            AutoBaudrate::StartBit => Interrupt::ReceiveAutoBaudrateByStartBit,
+            AutoBaudrate::FiveFive => Interrupt::ReceiveAutoBaudrateByFiveFive,
+        };
+        unsafe {
+            self.uart
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(interrupt));
+            self.uart
+                .receive_config
+                .modify(|val| val.enable_auto_baudrate());
+        }
+        while !self.uart.interrupt_state.read().has_interrupt(interrupt) {
+            core::hint::spin_loop();
+        }
+        let result = self.uart.auto_baudrate_period.read();
+        let period = match method {
+            AutoBaudrate::StartBit => result.start_bit(),
+            AutoBaudrate::FiveFive => result.five_five(),
+        }
+        .saturating_add(1);
+        unsafe {
+            self.uart
+                .receive_config
+                .modify(|val| val.disable_auto_baudrate());
+            self.uart.interrupt_clear.write(
+                InterruptClear::default()
+                    .clear_interrupt(Interrupt::ReceiveAutoBaudrateByStartBit)
+                    .clear_interrupt(Interrupt::ReceiveAutoBaudrateByFiveFive),
+            );
+            self.uart.bit_period.modify(|val| {
+                val.set_transmit_time_interval(period)
+                    .set_receive_time_interval(period)
+            });
+            // Drop the pattern bytes used for measurement.
+            self.uart
+                .fifo_config_0
+                .modify(|val| val.clear_receive_fifo());
+        }
+        period
+    }
+
+    /// Send LIN frame header as master, made of break, 0x55 synchronize field and protected identifier.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - Frame identifier, the value should be 0 ~ 63.
+    /// * `break_bits` - Break field bits in addition to 13 bits, the value should be 0 ~ 7.
+    #[inline]
+    pub fn send_lin_header(&mut self, id: u8, break_bits: u8) -> Result<(), Error> {
+        uart_flush(self.uart)?;
+        let config = self.uart.transmit_config.read();
+        unsafe {
+            // Transmit one protected identifier byte after hardware generated break and sync.
+            self.uart.transmit_config.write(
+                config
+                    .disable_txd()
+                    .disable_freerun()
+                    .enable_lin_transmit()
+                    .set_lin_break_bits(break_bits)
+                    .set_transfer_length(0),
+            );
+            self.uart
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::TransmitEnd));
+            self.uart.fifo_write.write(lin_protected_id(id));
+            self.uart.transmit_config.modify(|val| val.enable_txd());
+        }
+        while !self
+            .uart
+            .interrupt_state
+            .read()
+            .has_interrupt(Interrupt::TransmitEnd)
+        {
+            core::hint::spin_loop();
+        }
+        unsafe {
+            self.uart
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::TransmitEnd));
+            self.uart.transmit_config.write(config);
+        }
+        Ok(())
+    }
+
+    /// Wait for a LIN frame header as slave and return its frame identifier.
+    ///
+    /// Both halves follow master baudrate measured from the 0x55 synchronize field, so the
+    /// response is sent at the same rate.
+    /// Returns `Error::Sync` on a malformed break or synchronize field, and `Error::Parity`
+    /// if the protected identifier fails parity check.
+    #[inline]
+    pub fn wait_lin_header(&mut self) -> Result<u8, Error> {
+        let clear = InterruptClear::default()
+            .clear_interrupt(Interrupt::ReceiveSyncError)
+            .clear_interrupt(Interrupt::ReceiveAutoBaudrateByFiveFive);
+        unsafe {
+            self.uart.interrupt_clear.write(clear);
+            self.uart
+                .receive_config
+                .modify(|val| val.enable_lin_receive().enable_auto_baudrate());
+        }
+        let result = loop {
+            let state = self.uart.interrupt_state.read();
+            if state.has_interrupt(Interrupt::ReceiveSyncError) {
+                break Err(Error::Sync);
+            }
+            if state.has_interrupt(Interrupt::ReceiveAutoBaudrateByFiveFive)
+                && self.uart.fifo_config_1.read().receive_available_bytes() > 0
+            {
+                let period = self
+                    .uart
+                    .auto_baudrate_period
+                    .read()
+                    .five_five()
+                    .saturating_add(1);
+                unsafe {
+                    self.uart.bit_period.modify(|val| {
+                        val.set_transmit_time_interval(period)
+                            .set_receive_time_interval(period)
+                    })
+                };
+                break lin_frame_id(self.uart.fifo_read.read()).ok_or(Error::Parity);
+            }
+            core::hint::spin_loop();
+        };
+        unsafe {
+            self.uart
+                .receive_config
+                .modify(|val| val.disable_lin_receive().disable_auto_baudrate());
+            self.uart.interrupt_clear.write(clear);
+        }
+        result
+    }
+
+    /// Hand over transmission and circular reception to DMA channels.
+    ///
+    /// Each channel comes with the DMA peripheral request of this UART on its DMA controller.
+    /// `dma_state` is the waker state of that DMA controller, used by async reads and writes.
+    #[inline]
+    pub fn with_dma<'b, T: PeripheralId + Copy>(
+        self,
+        tx: (TypedChannel<'b, T>, T),
+        rx: (TypedChannel<'b, T>, T),
+        buffers: DmaSerialBuffers,
+        state: &'a SerialState,
+        dma_state: &'a DmaState,
+    ) -> Result<DmaSerial<'a, 'b, PADS, T>, ConfigError> {
+        DmaSerial::__new(self.uart, self.pads, tx, rx, buffers, (state, dma_state))
+    }
+
     /// Release serial instance and return its peripheral and pads.
     #[inline]
     pub fn free(self) -> PADS {
@@ -120,7 +309,7 @@ fn uart_write_nb(uart: &RegisterBlock, word: u8) -> nb::Result<(), Error> {
 }
 
 #[inline]
-fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
+pub(super) fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
     // There are maximum 32 bytes in transmit FIFO queue, wait until all bytes are available,
     // meaning that all data in queue has been sent into UART bus.
     while uart.fifo_config_1.read().transmit_available_bytes() != 32 {
@@ -256,3 +445,82 @@ impl<'a, PADS> embedded_hal_nb::serial::Read for BlockingReceiveHalf<'a, PADS> {
         uart_read_nb(&self.uart)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BlockingSerial;
+    use crate::uart::{AutoBaudrate, Error, RegisterBlock, lin_protected_id};
+    use core::cell::UnsafeCell;
+
+    struct MockUart(UnsafeCell<[u32; 0x90 / 4]>);
+
+    impl MockUart {
+        fn new() -> Self {
+            MockUart(UnsafeCell::new([0; 0x90 / 4]))
+        }
+        fn serial(&self) -> BlockingSerial<'_, ()> {
+            BlockingSerial {
+                uart: unsafe { &*(self.0.get() as *const RegisterBlock) },
+                pads: (),
+            }
+        }
+        fn reg(&self, offset: usize) -> u32 {
+            unsafe { (*self.0.get())[offset / 4] }
+        }
+        fn set_reg(&self, offset: usize, val: u32) {
+            unsafe { (*self.0.get())[offset / 4] = val };
+        }
+    }
+
+    #[test]
+    fn blocking_detect_baudrate() {
+        let uart = MockUart::new();
+        uart.set_reg(0x08, 0x0100_0100);
+        // Start bit measured as 0x3ff clocks, 0x55 pattern as 0x1ff clocks.
+        uart.set_reg(0x34, 0x01ff_03ff);
+        uart.set_reg(0x20, 1 << 10);
+        assert_eq!(uart.serial().detect_baudrate(AutoBaudrate::StartBit), 0x400);
+        assert_eq!(uart.reg(0x08), 0x0400_0400);
+        // Auto baudrate detection is turned off afterwards.
+        assert_eq!(uart.reg(0x04) & (1 << 1), 0);
+
+        uart.set_reg(0x20, 1 << 11);
+        assert_eq!(uart.serial().detect_baudrate(AutoBaudrate::FiveFive), 0x200);
+        assert_eq!(uart.reg(0x08), 0x0200_0200);
+    }
+
+    #[test]
+    fn blocking_send_lin_header() {
+        let uart = MockUart::new();
+        uart.set_reg(0x00, 0x0000_0005);
+        uart.set_reg(0x84, 32);
+        uart.set_reg(0x20, 1 << 0);
+        assert!(uart.serial().send_lin_header(0x3c, 2).is_ok());
+        assert_eq!(uart.reg(0x88), lin_protected_id(0x3c) as u32);
+        // Transmit configuration is restored once the header is sent.
+        assert_eq!(uart.reg(0x00), 0x0000_0005);
+    }
+
+    #[test]
+    fn blocking_wait_lin_header() {
+        let uart = MockUart::new();
+        uart.set_reg(0x08, 0x0100_0100);
+        uart.set_reg(0x34, 0x013f_0000);
+        uart.set_reg(0x84, 1 << 8);
+        uart.set_reg(0x8c, lin_protected_id(0x10) as u32);
+        uart.set_reg(0x20, 1 << 11);
+        assert!(matches!(uart.serial().wait_lin_header(), Ok(0x10)));
+        // Response is sent at the measured baudrate as well.
+        assert_eq!(uart.reg(0x08), 0x0140_0140);
+        assert_eq!(uart.reg(0x04) & ((1 << 1) | (1 << 3)), 0);
+
+        uart.set_reg(0x8c, lin_protected_id(0x10) as u32 ^ 0x80);
+        assert!(matches!(
+            uart.serial().wait_lin_header(),
+            Err(Error::Parity)
+        ));
+
+        uart.set_reg(0x20, 1 << 8);
+        assert!(matches!(uart.serial().wait_lin_header(), Err(Error::Sync)));
+    }
+}
@@ -1,4 +1,6 @@
-use super::{BitPeriod, DataConfig, Pads, ReceiveConfig, TransmitConfig};
+use super::{
+    BitPeriod, DataConfig, Pads, ReceiveConfig, ReceiveTimeout, Rs485Config, TransmitConfig,
+};
 use crate::clocks::Clocks;
 use embedded_time::rate::{Baud, Extensions};
 
@@ -21,6 +23,10 @@ pub struct Config {
     pub transmit_word_length: WordLength,
     /// Data word length on the receive half.
     pub receive_word_length: WordLength,
+    /// Idle time in bit periods before the receive half times out.
+    pub receive_timeout: u8,
+    /// Drive request-to-send pad as RS-485 driver-enable with given polarity.
+    pub rs485_driver_enable: Option<DriverEnablePolarity>,
 }
 
 impl Config {
@@ -53,8 +59,29 @@ impl Config {
             ..self
         }
     }
+    /// Set idle time in bit periods before the receive half times out.
+    ///
+    /// A receive time-out ends reads waiting for the line to go idle.
+    #[inline]
+    pub const fn set_receive_timeout(self, bits: u8) -> Self {
+        Self {
+            receive_timeout: bits,
+            ..self
+        }
+    }
+    /// Enable RS-485 half-duplex mode.
+    ///
+    /// Request-to-send pad is driven active during transmissions to switch transceiver
+    /// direction, instead of being used for receive flow control.
     #[inline]
-    fn into_registers(self) -> (DataConfig, TransmitConfig, ReceiveConfig) {
+    pub const fn set_rs485_driver_enable(self, polarity: DriverEnablePolarity) -> Self {
+        Self {
+            rs485_driver_enable: Some(polarity),
+            ..self
+        }
+    }
+    #[inline]
+    fn into_registers(self) -> (DataConfig, TransmitConfig, ReceiveConfig, ReceiveTimeout) {
         let data_config = DataConfig::default().set_bit_order(self.bit_order);
         let transmit_config = TransmitConfig::default()
             .set_parity(self.transmit_parity)
@@ -63,12 +90,19 @@ impl Config {
         let receive_config = ReceiveConfig::default()
             .set_parity(self.receive_parity)
             .set_word_length(self.receive_word_length);
-        (data_config, transmit_config, receive_config)
+        let receive_timeout = ReceiveTimeout::default().set_timeout(self.receive_timeout);
+        (
+            data_config,
+            transmit_config,
+            receive_config,
+            receive_timeout,
+        )
     }
 }
 
 impl Default for Config {
-    /// Serial configuration defaults to 115200 Bd, 8-bit word, no parity check, 1 stop bit, LSB first.
+    /// Serial configuration defaults to 115200 Bd, 8-bit word, no parity check, 1 stop bit, LSB first,
+    /// with receive time-out after 16 idle bit periods.
     #[inline]
     fn default() -> Self {
         Config {
@@ -80,6 +114,8 @@ impl Default for Config {
             stop_bits: StopBits::One,
             transmit_word_length: WordLength::Eight,
             receive_word_length: WordLength::Eight,
+            receive_timeout: 16,
+            rs485_driver_enable: None,
         }
     }
 }
@@ -88,7 +124,17 @@ impl Default for Config {
 pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     config: Config,
     clocks: &Clocks,
-) -> Result<(BitPeriod, DataConfig, TransmitConfig, ReceiveConfig), ConfigError> {
+) -> Result<
+    (
+        BitPeriod,
+        DataConfig,
+        TransmitConfig,
+        ReceiveConfig,
+        ReceiveTimeout,
+        Rs485Config,
+    ),
+    ConfigError,
+> {
     let uart_clock = match clocks.uart_clock::<I>() {
         Some(freq) => freq,
         None => return Err(ConfigError::ClockSource),
@@ -108,7 +154,8 @@ pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     let bit_period = BitPeriod::default()
         .set_transmit_time_interval(transmit_interval as u16)
         .set_receive_time_interval(receive_interval as u16);
-    let (data_config, mut transmit_config, mut receive_config) = config.into_registers();
+    let (data_config, mut transmit_config, mut receive_config, receive_timeout) =
+        config.into_registers();
     if PADS::TXD {
         transmit_config = transmit_config.enable_txd();
     }
@@ -118,7 +165,19 @@ pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     if PADS::RXD {
         receive_config = receive_config.enable_rxd();
     }
-    Ok((bit_period, data_config, transmit_config, receive_config))
+    let rs485_config = match config.rs485_driver_enable {
+        Some(_) if !PADS::RTS => return Err(ConfigError::MissingRequestToSend),
+        Some(polarity) => Rs485Config::default().enable_rs485().set_polarity(polarity),
+        None => Rs485Config::default(),
+    };
+    Ok((
+        bit_period,
+        data_config,
+        transmit_config,
+        receive_config,
+        receive_timeout,
+        rs485_config,
+    ))
 }
 
 /// Errors on serial configuration.
@@ -134,6 +193,30 @@ pub enum ConfigError {
     ReceiveBaudrateTooLow,
     /// Clock source unavailable.
     ClockSource,
+    /// Linked list item pool too small to cover the DMA buffer.
+    LliPoolTooSmall,
+    /// DMA receive buffer is empty or has an odd length; it is received in two halves.
+    DmaBufferLength,
+    /// RS-485 driver-enable requires a request-to-send pad.
+    MissingRequestToSend,
+}
+
+/// Method of automatic baudrate detection.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AutoBaudrate {
+    /// Measure the width of start bit.
+    StartBit,
+    /// Measure the 0x55 synchronize pattern.
+    FiveFive,
+}
+
+/// Active level of RS-485 driver-enable signal.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DriverEnablePolarity {
+    /// Driver is enabled when signal is high.
+    ActiveHigh,
+    /// Driver is enabled when signal is low.
+    ActiveLow,
 }
 
 /// Order of the bits transmitted and received on the wire.
@@ -0,0 +1,512 @@
+use super::{ConfigError, DisableOnDrop, Error, Interrupt, RegisterBlock, SerialState, uart_flush};
+use crate::dma::{
+    BurstSize, CircularBuffers, CircularReader, DmaChannelConfig, DmaMode, DmaState, Endpoint,
+    LliPool, PeripheralId, TransferWidth, TypedChannel, UntypedChannel, clean_dcache,
+};
+use as_slice::AsSlice;
+use core::{
+    future::poll_fn,
+    ops::Deref,
+    sync::atomic::{Ordering, compiler_fence},
+    task::Poll,
+};
+
+/// Bytes moved by one linked list item in byte transfer width.
+const LLI_TRANSFER_SIZE: usize = 4064;
+
+/// Buffers lent to a DMA serial peripheral.
+#[derive(Debug)]
+pub struct DmaSerialBuffers {
+    /// Circular buffer continuously filled by receive DMA, received in two halves.
+    pub rx_buffer: &'static mut [u8],
+    /// Linked list items forming the receive ring.
+    pub rx_lli: &'static mut [LliPool],
+    /// Linked list items used by transmit transfers.
+    pub tx_lli: &'static mut [LliPool],
+}
+
+/// Managed serial peripheral with DMA transmit and circular DMA receive.
+pub struct DmaSerial<'a, 'b, PADS, T> {
+    uart: &'a RegisterBlock,
+    pads: PADS,
+    tx_channel: TypedChannel<'b, T>,
+    rx: CircularReader<'b, T>,
+    tx_lli: &'static mut [LliPool],
+    state: &'a SerialState,
+    dma_state: &'a DmaState,
+}
+
+impl<'a, 'b, PADS, T: PeripheralId + Copy> DmaSerial<'a, 'b, PADS, T> {
+    /// Creates the DMA serial peripheral from a configured serial, DMA channels and buffers.
+    ///
+    /// `tx_request` and `rx_request` are the DMA peripheral requests of this UART on the DMA
+    /// controller owning both channels.
+    #[doc(hidden)]
+    #[inline]
+    pub fn __new(
+        uart: &'a RegisterBlock,
+        pads: PADS,
+        (mut tx_channel, tx_request): (TypedChannel<'b, T>, T),
+        (rx_channel, rx_request): (TypedChannel<'b, T>, T),
+        buffers: DmaSerialBuffers,
+        (state, dma_state): (&'a SerialState, &'a DmaState),
+    ) -> Result<Self, ConfigError> {
+        if buffers.tx_lli.is_empty() {
+            return Err(ConfigError::LliPoolTooSmall);
+        }
+        // Receive ring waits for DMA requests, which UART only raises once enabled below.
+        let rx = rx_channel
+            .into_circular_reader(
+                Endpoint::from_register(rx_request, &uart.fifo_read),
+                CircularBuffers {
+                    buffer: buffers.rx_buffer,
+                    lli: buffers.rx_lli,
+                },
+                2,
+                TransferWidth::Byte,
+            )
+            .map_err(|e| match e {
+                crate::dma::Error::LliPoolTooSmall => ConfigError::LliPoolTooSmall,
+                _ => ConfigError::DmaBufferLength,
+            })?;
+
+        tx_channel.configure(DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            src_req: None,
+            dst_req: Some(tx_request),
+            src_addr_inc: true,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::Byte,
+            dst_transfer_width: TransferWidth::Byte,
+        });
+
+        // Request DMA on every byte received, or bytes would stay in FIFO below the threshold.
+        unsafe {
+            uart.fifo_config_1
+                .modify(|val| val.set_transmit_threshold(7).set_receive_threshold(0));
+            uart.fifo_config_0.modify(|val| {
+                val.enable_transmit_dma()
+                    .enable_receive_dma()
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo()
+            });
+        }
+
+        state.attach(uart);
+
+        Ok(DmaSerial {
+            uart,
+            pads,
+            tx_channel,
+            rx,
+            tx_lli: buffers.tx_lli,
+            state,
+            dma_state,
+        })
+    }
+
+    /// Number of received bytes waiting in the circular buffer.
+    ///
+    /// Returns `Error::Overrun` if receive DMA has overwritten bytes not yet read; they are
+    /// dropped and reception goes on. Check at least once a half buffer to catch every overrun.
+    #[inline]
+    pub fn available(&mut self) -> Result<usize, Error> {
+        self.rx.available().map_err(|_| Error::Overrun)
+    }
+
+    /// Starts a DMA transmission from an owned buffer.
+    ///
+    /// Returns the buffer back if it is empty or does not fit into transmit linked list items.
+    #[inline]
+    pub fn transmit<B>(&mut self, buffer: B) -> Result<Transmit<'_, 'a, 'b, PADS, T, B>, B>
+    where
+        B: Deref + 'static,
+        B::Target: AsSlice<Element = u8>,
+    {
+        let slice = buffer.as_slice();
+        if slice.is_empty() || slice.len() > self.tx_lli.len() * LLI_TRANSFER_SIZE {
+            return Err(buffer);
+        }
+        self.start_transmit(slice.as_ptr(), slice.len());
+        Ok(Transmit {
+            serial: self,
+            buffer: Some(buffer),
+        })
+    }
+
+    /// Release serial instance and return its pads, DMA channels and buffers.
+    #[inline]
+    pub fn free(
+        self,
+    ) -> (
+        PADS,
+        TypedChannel<'b, T>,
+        TypedChannel<'b, T>,
+        DmaSerialBuffers,
+    ) {
+        self.tx_channel.stop();
+        let (rx_channel, rx_buffers) = self.rx.free();
+        unsafe {
+            self.uart
+                .fifo_config_0
+                .modify(|val| val.disable_transmit_dma().disable_receive_dma());
+        }
+        let buffers = DmaSerialBuffers {
+            rx_buffer: rx_buffers.buffer,
+            rx_lli: rx_buffers.lli,
+            tx_lli: self.tx_lli,
+        };
+        (self.pads, self.tx_channel, rx_channel, buffers)
+    }
+
+    #[inline]
+    fn start_transmit(&mut self, src: *const u8, len: usize) -> usize {
+        let len = core::cmp::min(len, self.tx_lli.len() * LLI_TRANSFER_SIZE);
+        let count = len.div_ceil(LLI_TRANSFER_SIZE);
+        self.tx_channel.lli_config(
+            self.tx_lli,
+            count as u32,
+            src as u32,
+            &self.uart.fifo_write as *const _ as u32,
+            LLI_TRANSFER_SIZE as u32,
+            (len - (count - 1) * LLI_TRANSFER_SIZE) as u32,
+        );
+        self.tx_channel.clear_states();
+        compiler_fence(Ordering::Release);
+        clean_dcache(src as usize, len);
+        self.tx_channel.lli_load(&self.tx_lli[0]);
+        self.tx_channel.start();
+        len
+    }
+}
+
+/// Ongoing DMA transmission from an owned buffer.
+pub struct Transmit<'s, 'a, 'b, PADS, T, B> {
+    serial: &'s mut DmaSerial<'a, 'b, PADS, T>,
+    buffer: Option<B>,
+}
+
+impl<'s, 'a, 'b, PADS, T, B> Transmit<'s, 'a, 'b, PADS, T, B> {
+    /// Checks whether the transmission is still ongoing.
+    #[inline]
+    pub fn is_ongoing(&self) -> bool {
+        self.serial.tx_channel.is_busy()
+    }
+    /// Waits for the transmission to end and returns the buffer.
+    #[inline]
+    pub fn wait(mut self) -> B {
+        while self.serial.tx_channel.is_busy() {
+            core::hint::spin_loop();
+        }
+        self.buffer.take().unwrap()
+    }
+}
+
+impl<'s, 'a, 'b, PADS, T, B> Drop for Transmit<'s, 'a, 'b, PADS, T, B> {
+    /// Stops the DMA channel before the buffer could be released.
+    #[inline]
+    fn drop(&mut self) {
+        self.serial.tx_channel.stop();
+    }
+}
+
+/// Stops the DMA channel when a borrowed transfer is dropped.
+struct StopOnDrop<'c, 'b>(&'c UntypedChannel<'b>);
+
+impl Drop for StopOnDrop<'_, '_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.stop();
+    }
+}
+
+impl<'a, 'b, PADS, T> embedded_io::ErrorType for DmaSerial<'a, 'b, PADS, T> {
+    type Error = Error;
+}
+
+impl<'a, 'b, PADS, T: PeripheralId + Copy> embedded_io::Write for DmaSerial<'a, 'b, PADS, T> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = self.start_transmit(buf.as_ptr(), buf.len());
+        let _guard = StopOnDrop(&self.tx_channel);
+        while self.tx_channel.is_busy() {
+            core::hint::spin_loop();
+        }
+        Ok(len)
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        uart_flush(self.uart)
+    }
+}
+
+impl<'a, 'b, PADS, T: PeripheralId + Copy> embedded_io::Read for DmaSerial<'a, 'b, PADS, T> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.available()? == 0 {
+            core::hint::spin_loop();
+        }
+        self.rx.read(buf).map_err(|_| Error::Overrun)
+    }
+}
+
+impl<'a, 'b, PADS, T: PeripheralId + Copy> embedded_io_async::Write for DmaSerial<'a, 'b, PADS, T> {
+    /// Write bytes, sleeping until transmit DMA completes.
+    ///
+    /// `DmaState` of this serial must be notified with `DmaState::on_interrupt` in the DMA
+    /// interrupt handler.
+    #[inline]
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = self.start_transmit(buf.as_ptr(), buf.len());
+        let _guard = StopOnDrop(&self.tx_channel);
+        poll_fn(|cx| {
+            if self.tx_channel.is_busy() {
+                self.tx_channel.listen(self.dma_state, cx.waker());
+                // Completed before listening, its interrupt might be missed.
+                if self.tx_channel.is_busy() {
+                    return Poll::Pending;
+                }
+            }
+            Poll::Ready(())
+        })
+        .await;
+        Ok(len)
+    }
+}
+
+impl<'a, 'b, PADS, T: PeripheralId + Copy> embedded_io_async::Read for DmaSerial<'a, 'b, PADS, T> {
+    /// Read received bytes, sleeping until the line goes idle or half of the buffer is filled.
+    ///
+    /// Both `SerialState` and `DmaState` of this serial must be notified in the UART and DMA
+    /// interrupt handlers.
+    #[inline]
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        // Receive DMA drains the FIFO, wake on receive time-out when the line goes idle.
+        let _guard = DisableOnDrop(self.uart, self.state, [Interrupt::ReceiveTimeout]);
+        poll_fn(|cx| {
+            self.state.receive_ready.register(cx.waker());
+            self.rx.listen(self.dma_state, cx.waker());
+            match self.available() {
+                Ok(0) => {
+                    self.state.modify_interrupts(self.uart, |val| {
+                        val.enable_interrupt(Interrupt::ReceiveTimeout)
+                    });
+                    Poll::Pending
+                }
+                Ok(_) => Poll::Ready(Ok(())),
+                Err(e) => Poll::Ready(Err(e)),
+            }
+        })
+        .await?;
+        self.rx.read(buf).map_err(|_| Error::Overrun)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DmaSerial, DmaSerialBuffers};
+    use crate::dma::mock::{MockDma, MockRequest};
+    use crate::dma::{DmaState, LliPool};
+    use crate::uart::{ConfigError, Error, RegisterBlock, SerialState};
+    use core::cell::UnsafeCell;
+    use core::future::Future;
+    use core::task::Poll;
+
+    struct MockUart(UnsafeCell<[u32; 0x90 / 4]>);
+
+    impl MockUart {
+        fn new() -> Self {
+            MockUart(UnsafeCell::new([0; 0x90 / 4]))
+        }
+        fn regs(&self) -> &RegisterBlock {
+            unsafe { &*(self.0.get() as *const RegisterBlock) }
+        }
+        fn reg(&self, offset: usize) -> u32 {
+            unsafe { *(self.0.get() as *const u32).add(offset / 4) }
+        }
+        fn address(&self, offset: usize) -> u32 {
+            self.0.get() as usize as u32 + offset as u32
+        }
+    }
+
+    fn request(id: u8) -> MockRequest {
+        MockRequest { id, address: 0 }
+    }
+
+    /// Serial and DMA register blocks in memory, with waker states of both.
+    struct Fixture {
+        uart: MockUart,
+        dma: MockDma,
+        state: SerialState,
+        dma_state: DmaState,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Fixture {
+                uart: MockUart::new(),
+                dma: MockDma::new(),
+                state: SerialState::new(),
+                dma_state: DmaState::new(),
+            }
+        }
+        /// Transmit on channel 0 and receive on channel 1.
+        fn serial(
+            &self,
+            buffers: DmaSerialBuffers,
+        ) -> Result<DmaSerial<'_, '_, (), MockRequest>, ConfigError> {
+            DmaSerial::__new(
+                self.uart.regs(),
+                (),
+                (self.dma.channel(0), request(21)),
+                (self.dma.channel(1), request(20)),
+                buffers,
+                (&self.state, &self.dma_state),
+            )
+        }
+    }
+
+    /// Buffers in statics of each expansion, so that tests never share them.
+    macro_rules! buffers {
+        ($rx_len:expr, $rx_lli:expr, $tx_lli:expr) => {{
+            static mut RX_BUFFER: [u8; $rx_len] = [0; $rx_len];
+            static mut RX_LLI: [LliPool; $rx_lli] = [const { LliPool::new() }; $rx_lli];
+            static mut TX_LLI: [LliPool; $tx_lli] = [const { LliPool::new() }; $tx_lli];
+            DmaSerialBuffers {
+                rx_buffer: unsafe { &mut *core::ptr::addr_of_mut!(RX_BUFFER) },
+                rx_lli: unsafe { &mut *core::ptr::addr_of_mut!(RX_LLI) },
+                tx_lli: unsafe { &mut *core::ptr::addr_of_mut!(TX_LLI) },
+            }
+        }};
+    }
+
+    #[test]
+    fn struct_dma_serial_errors() {
+        let f = Fixture::new();
+        assert!(matches!(
+            f.serial(buffers!(14, 2, 0)),
+            Err(ConfigError::LliPoolTooSmall)
+        ));
+        assert!(matches!(
+            f.serial(buffers!(15, 2, 1)),
+            Err(ConfigError::DmaBufferLength)
+        ));
+        assert!(matches!(
+            f.serial(buffers!(0, 2, 1)),
+            Err(ConfigError::DmaBufferLength)
+        ));
+        assert!(matches!(
+            f.serial(buffers!(14, 1, 1)),
+            Err(ConfigError::LliPoolTooSmall)
+        ));
+        // Receive DMA stays disabled on UART.
+        assert_eq!(f.uart.reg(0x80) & 0b11, 0);
+    }
+
+    #[test]
+    fn struct_dma_serial_receive() {
+        let f = Fixture::new();
+        let (uart, dma) = (&f.uart, &f.dma);
+        let buffers = buffers!(16, 2, 1);
+        let ring = buffers.rx_buffer.as_mut_ptr();
+        let lli = buffers.rx_lli.as_ptr();
+        let base = ring as usize as u32;
+        let mut serial = f.serial(buffers).ok().unwrap();
+        assert_eq!(uart.reg(0x80) & 0b11, 0b11);
+        let lli = unsafe { core::slice::from_raw_parts(lli, 2) };
+        assert_eq!(lli[0].src_addr, uart.address(0x8c));
+        assert_eq!(lli[1].dst_addr, base.wrapping_add(8));
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 1);
+
+        unsafe { core::ptr::copy_nonoverlapping(b"hello".as_ptr(), ring, 5) };
+        dma.set_channel_reg(1, 0x04, base.wrapping_add(5));
+        assert_eq!(serial.available().ok(), Some(5));
+        let mut buf = [0u8; 8];
+        assert_eq!(embedded_io::Read::read(&mut serial, &mut buf).ok(), Some(5));
+        assert_eq!(&buf[..5], b"hello");
+
+        // Half completed while receive DMA is back in the same half: one whole lap is lost.
+        dma.set_channel_reg(1, 0x04, base.wrapping_add(6));
+        dma.set_raw_complete(1, true);
+        assert!(matches!(
+            embedded_io::Read::read(&mut serial, &mut buf),
+            Err(Error::Overrun)
+        ));
+        dma.set_raw_complete(1, false);
+        assert_eq!(serial.available().ok(), Some(0));
+
+        let (_, _, _, buffers) = serial.free();
+        assert_eq!(uart.reg(0x80) & 0b11, 0);
+        assert_eq!(dma.channel_reg(1, 0x10) & 1, 0);
+        assert_eq!(buffers.rx_buffer.len(), 16);
+    }
+
+    #[test]
+    fn struct_dma_serial_transmit() {
+        static HELLO: &[u8] = b"hello";
+        let f = Fixture::new();
+        let (uart, dma) = (&f.uart, &f.dma);
+        let mut serial = f.serial(buffers!(16, 2, 1)).ok().unwrap();
+        assert!(serial.transmit(&HELLO[..0]).is_err());
+        assert!(serial.transmit(&[0u8; 4065][..]).is_err());
+
+        let transmit = serial.transmit(HELLO).ok().unwrap();
+        assert_eq!(dma.channel_reg(0, 0x00), HELLO.as_ptr() as usize as u32);
+        assert_eq!(dma.channel_reg(0, 0x04), uart.address(0x88));
+        assert_eq!(dma.channel_reg(0, 0x0c) & 0xfff, 5);
+        assert!(transmit.is_ongoing());
+        dma.complete(0);
+        assert!(!transmit.is_ongoing());
+        assert_eq!(transmit.wait(), HELLO);
+
+        // Dropping an ongoing transmission stops DMA.
+        let transmit = serial.transmit(HELLO).ok().unwrap();
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 1);
+        drop(transmit);
+        assert_eq!(dma.channel_reg(0, 0x10) & 1, 0);
+    }
+
+    #[test]
+    fn struct_dma_serial_async() {
+        let f = Fixture::new();
+        let (uart, dma) = (&f.uart, &f.dma);
+        let buffers = buffers!(16, 2, 1);
+        let base = buffers.rx_buffer.as_ptr() as usize as u32;
+        let mut serial = f.serial(buffers).ok().unwrap();
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        {
+            let mut write = core::pin::pin!(embedded_io_async::Write::write(&mut serial, b"hi"));
+            assert!(write.as_mut().poll(&mut cx).is_pending());
+            // Sleeps on DMA completion, not on UART transmit FIFO.
+            assert_eq!(dma.channel_reg(0, 0x10) & (0b11 << 14), 0b11 << 14);
+            assert_eq!(uart.reg(0x2c), 0);
+            dma.complete(0);
+            assert!(matches!(write.as_mut().poll(&mut cx), Poll::Ready(Ok(2))));
+        }
+        {
+            let mut buf = [0u8; 4];
+            let mut read = core::pin::pin!(embedded_io_async::Read::read(&mut serial, &mut buf));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            // Wakes on receive time-out or on a completed half.
+            assert_eq!(uart.reg(0x2c), 1 << 4);
+            assert_eq!(dma.channel_reg(1, 0x10) & (0b11 << 14), 0b11 << 14);
+            dma.set_channel_reg(1, 0x04, base.wrapping_add(3));
+            assert!(matches!(read.as_mut().poll(&mut cx), Poll::Ready(Ok(3))));
+        }
+        assert_eq!(uart.reg(0x2c), 0);
+    }
+}
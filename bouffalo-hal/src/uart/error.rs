@@ -10,6 +10,8 @@ pub enum Error {
     Overrun,
     /// Parity check error.
     Parity,
+    /// LIN break or synchronize field error.
+    Sync,
 }
 
 impl embedded_io::Error for Error {
@@ -27,6 +29,7 @@ impl embedded_hal_nb::serial::Error for Error {
             Error::Noise => embedded_hal_nb::serial::ErrorKind::Noise,
             Error::Overrun => embedded_hal_nb::serial::ErrorKind::Overrun,
             Error::Parity => embedded_hal_nb::serial::ErrorKind::Parity,
+            Error::Sync => embedded_hal_nb::serial::ErrorKind::Other,
         }
     }
 }
@@ -0,0 +1,86 @@
+//! Local Interconnect Network protocol helpers.
+
+/// Checksum model of a LIN frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinChecksum {
+    /// Classic checksum over data bytes only, used by LIN 1.x nodes.
+    Classic,
+    /// Enhanced checksum over protected identifier and data bytes, used by LIN 2.x nodes.
+    Enhanced,
+}
+
+/// Compute protected identifier from a 6-bit frame identifier.
+///
+/// Bit 6 and bit 7 of the result are parity bits P0 and P1.
+#[inline]
+pub const fn lin_protected_id(id: u8) -> u8 {
+    let id = id & 0x3f;
+    let p0 = (id ^ (id >> 1) ^ (id >> 2) ^ (id >> 4)) & 1;
+    let p1 = !((id >> 1) ^ (id >> 3) ^ (id >> 4) ^ (id >> 5)) & 1;
+    id | (p0 << 6) | (p1 << 7)
+}
+
+/// Check parity bits of a protected identifier, returns the 6-bit frame identifier.
+#[inline]
+pub const fn lin_frame_id(protected_id: u8) -> Option<u8> {
+    let id = protected_id & 0x3f;
+    if lin_protected_id(id) == protected_id {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// Compute checksum byte of a LIN frame.
+///
+/// `protected_id` is only used by the enhanced checksum model.
+#[inline]
+pub fn lin_checksum(model: LinChecksum, protected_id: u8, data: &[u8]) -> u8 {
+    let init = match model {
+        LinChecksum::Classic => 0,
+        LinChecksum::Enhanced => protected_id as u16,
+    };
+    let sum = data.iter().fold(init, |sum, &byte| {
+        let sum = sum + byte as u16;
+        // Add carry back into the lower byte.
+        (sum & 0xff) + (sum >> 8)
+    });
+    !(sum as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinChecksum, lin_checksum, lin_frame_id, lin_protected_id};
+
+    #[test]
+    fn function_lin_protected_id() {
+        assert_eq!(lin_protected_id(0x00), 0x80);
+        assert_eq!(lin_protected_id(0x01), 0xc1);
+        assert_eq!(lin_protected_id(0x10), 0x50);
+        assert_eq!(lin_protected_id(0x3c), 0x3c);
+        assert_eq!(lin_protected_id(0x3d), 0x7d);
+        assert_eq!(lin_protected_id(0x7f), 0x3f | 0x80);
+        for id in 0..0x40 {
+            assert_eq!(lin_frame_id(lin_protected_id(id)), Some(id));
+        }
+        assert_eq!(lin_frame_id(0x00), None);
+        assert_eq!(lin_frame_id(0x3d), None);
+    }
+
+    #[test]
+    fn function_lin_checksum() {
+        assert_eq!(lin_checksum(LinChecksum::Classic, 0x00, &[]), 0xff);
+        assert_eq!(
+            lin_checksum(LinChecksum::Classic, 0x00, &[0x4a, 0x55, 0x93, 0xe5]),
+            0xe6
+        );
+        assert_eq!(
+            lin_checksum(LinChecksum::Enhanced, 0x50, &[0x4a, 0x55, 0x93, 0xe5]),
+            0x96
+        );
+        assert_eq!(
+            lin_checksum(LinChecksum::Classic, 0x00, &[0xff, 0xff, 0xff]),
+            0x00
+        );
+    }
+}
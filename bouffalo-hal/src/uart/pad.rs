@@ -1,6 +1,6 @@
 use super::{
-    BlockingReceiveHalf, BlockingTransmitHalf, MuxCts, MuxRts, MuxRxd, MuxTxd, RegisterBlock,
-    UartMux,
+    AsyncReceiveHalf, AsyncTransmitHalf, BlockingReceiveHalf, BlockingTransmitHalf, MuxCts, MuxRts,
+    MuxRxd, MuxTxd, RegisterBlock, SerialState, UartMux,
 };
 use crate::gpio::{Alternate, MmUart, Uart};
 
@@ -76,6 +76,15 @@ pub trait Pads<const U: usize> {
     type Split<'a>;
 
     fn split<'a>(self, uart: &'a RegisterBlock) -> Self::Split<'a>;
+
+    /// Valid async/await split configuration type for current pads and multiplexers.
+    type AsyncSplit<'a>;
+
+    fn async_split<'a>(
+        self,
+        uart: &'a RegisterBlock,
+        state: &'a SerialState,
+    ) -> Self::AsyncSplit<'a>;
 }
 
 #[inline]
@@ -90,6 +99,27 @@ fn from_pads<'a, TX, RX>(
     )
 }
 
+#[inline]
+fn from_pads_async<'a, TX, RX>(
+    uart: &'a RegisterBlock,
+    state: &'a SerialState,
+    tx: TX,
+    rx: RX,
+) -> (AsyncTransmitHalf<'a, TX>, AsyncReceiveHalf<'a, RX>) {
+    (
+        AsyncTransmitHalf {
+            uart,
+            state,
+            _pads: tx,
+        },
+        AsyncReceiveHalf {
+            uart,
+            state,
+            _pads: rx,
+        },
+    )
+}
+
 impl<'a, 'b, const I: usize, const U: usize, const N: usize> Pads<U>
     for (Alternate<'a, N, Uart>, UartMux<'b, I, MuxTxd<U>>)
 where
@@ -107,6 +137,18 @@ where
     fn split<'u>(self, uart: &'u RegisterBlock) -> Self::Split<'u> {
         from_pads(uart, self, ())
     }
+    type AsyncSplit<'u> = (
+        AsyncTransmitHalf<'u, (Alternate<'a, N, Uart>, UartMux<'b, I, MuxTxd<U>>)>,
+        AsyncReceiveHalf<'u, ()>,
+    );
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        from_pads_async(uart, state, self, ())
+    }
 }
 
 impl<
@@ -140,6 +182,18 @@ where
     fn split<'u>(self, uart: &'u RegisterBlock) -> Self::Split<'u> {
         from_pads(uart, self.0, self.1)
     }
+    type AsyncSplit<'u> = (
+        AsyncTransmitHalf<'u, (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>)>,
+        AsyncReceiveHalf<'u, (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>)>,
+    );
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        from_pads_async(uart, state, self.0, self.1)
+    }
 }
 
 impl<
@@ -159,7 +213,7 @@ impl<
     )
 where
     Alternate<'a, N1, Uart>: HasUartSignal<I1>,
-    Alternate<'d, N2, Uart>: HasUartSignal<I2>,
+    Alternate<'c, N2, Uart>: HasUartSignal<I2>,
 {
     const RTS: bool = false;
     const CTS: bool = true;
@@ -176,6 +230,25 @@ where
     fn split<'u>(self, uart: &'u RegisterBlock) -> Self::Split<'u> {
         BlockingTransmitHalf { uart, _pads: self }
     }
+    type AsyncSplit<'u> = AsyncTransmitHalf<
+        'u,
+        (
+            (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>),
+            (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxCts<U>>),
+        ),
+    >;
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        AsyncTransmitHalf {
+            uart,
+            state,
+            _pads: self,
+        }
+    }
 }
 
 impl<
@@ -209,10 +282,10 @@ where
     Alternate<'e, N3, Uart>: HasUartSignal<I3>,
     Alternate<'g, N4, Uart>: HasUartSignal<I4>,
 {
-    const RTS: bool = false;
+    const RTS: bool = true;
     const CTS: bool = true;
     const TXD: bool = true;
-    const RXD: bool = false;
+    const RXD: bool = true;
     type Split<'u> = (
         BlockingTransmitHalf<
             'u,
@@ -233,6 +306,211 @@ where
     fn split<'u>(self, uart: &'u RegisterBlock) -> Self::Split<'u> {
         from_pads(uart, (self.0, self.3), (self.1, self.2))
     }
+    type AsyncSplit<'u> = (
+        AsyncTransmitHalf<
+            'u,
+            (
+                (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>),
+                (Alternate<'g, N4, Uart>, UartMux<'h, I4, MuxCts<U>>),
+            ),
+        >,
+        AsyncReceiveHalf<
+            'u,
+            (
+                (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>),
+                (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxRts<U>>),
+            ),
+        >,
+    );
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        from_pads_async(uart, state, (self.0, self.3), (self.1, self.2))
+    }
+}
+
+impl<
+    'a,
+    'b,
+    'c,
+    'd,
+    const I1: usize,
+    const I2: usize,
+    const U: usize,
+    const N1: usize,
+    const N2: usize,
+> Pads<U>
+    for (
+        (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxRxd<U>>),
+        (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRts<U>>),
+    )
+where
+    Alternate<'a, N1, Uart>: HasUartSignal<I1>,
+    Alternate<'c, N2, Uart>: HasUartSignal<I2>,
+{
+    const RTS: bool = true;
+    const CTS: bool = false;
+    const TXD: bool = false;
+    const RXD: bool = true;
+    type Split<'u> = BlockingReceiveHalf<
+        'u,
+        (
+            (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxRxd<U>>),
+            (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRts<U>>),
+        ),
+    >;
+    #[inline]
+    fn split<'u>(self, uart: &'u RegisterBlock) -> Self::Split<'u> {
+        BlockingReceiveHalf { uart, _pads: self }
+    }
+    type AsyncSplit<'u> = AsyncReceiveHalf<
+        'u,
+        (
+            (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxRxd<U>>),
+            (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRts<U>>),
+        ),
+    >;
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        AsyncReceiveHalf {
+            uart,
+            state,
+            _pads: self,
+        }
+    }
+}
+
+impl<
+    'a,
+    'b,
+    'c,
+    'd,
+    'e,
+    'f,
+    const I1: usize,
+    const I2: usize,
+    const I3: usize,
+    const U: usize,
+    const N1: usize,
+    const N2: usize,
+    const N3: usize,
+> Pads<U>
+    for (
+        (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>),
+        (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>),
+        (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxRts<U>>),
+    )
+where
+    Alternate<'a, N1, Uart>: HasUartSignal<I1>,
+    Alternate<'c, N2, Uart>: HasUartSignal<I2>,
+    Alternate<'e, N3, Uart>: HasUartSignal<I3>,
+{
+    const RTS: bool = true;
+    const CTS: bool = false;
+    const TXD: bool = true;
+    const RXD: bool = true;
+    type Split<'u> = (
+        BlockingTransmitHalf<'u, (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>)>,
+        BlockingReceiveHalf<
+            'u,
+            (
+                (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>),
+                (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxRts<U>>),
+            ),
+        >,
+    );
+    #[inline]
+    fn split<'u>(self, uart: &'u RegisterBlock) -> Self::Split<'u> {
+        from_pads(uart, self.0, (self.1, self.2))
+    }
+    type AsyncSplit<'u> = (
+        AsyncTransmitHalf<'u, (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>)>,
+        AsyncReceiveHalf<
+            'u,
+            (
+                (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>),
+                (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxRts<U>>),
+            ),
+        >,
+    );
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        from_pads_async(uart, state, self.0, (self.1, self.2))
+    }
+}
+
+impl<
+    'a,
+    'b,
+    'c,
+    'd,
+    'e,
+    'f,
+    const I1: usize,
+    const I2: usize,
+    const I3: usize,
+    const U: usize,
+    const N1: usize,
+    const N2: usize,
+    const N3: usize,
+> Pads<U>
+    for (
+        (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>),
+        (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>),
+        (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxCts<U>>),
+    )
+where
+    Alternate<'a, N1, Uart>: HasUartSignal<I1>,
+    Alternate<'c, N2, Uart>: HasUartSignal<I2>,
+    Alternate<'e, N3, Uart>: HasUartSignal<I3>,
+{
+    const RTS: bool = false;
+    const CTS: bool = true;
+    const TXD: bool = true;
+    const RXD: bool = true;
+    type Split<'u> = (
+        BlockingTransmitHalf<
+            'u,
+            (
+                (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>),
+                (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxCts<U>>),
+            ),
+        >,
+        BlockingReceiveHalf<'u, (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>)>,
+    );
+    #[inline]
+    fn split<'u>(self, uart: &'u RegisterBlock) -> Self::Split<'u> {
+        from_pads(uart, (self.0, self.2), self.1)
+    }
+    type AsyncSplit<'u> = (
+        AsyncTransmitHalf<
+            'u,
+            (
+                (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>),
+                (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxCts<U>>),
+            ),
+        >,
+        AsyncReceiveHalf<'u, (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>)>,
+    );
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        from_pads_async(uart, state, (self.0, self.2), self.1)
+    }
 }
 
 // TODO: support split for MmUart pads.
@@ -253,6 +531,15 @@ where
         let _ = uart;
         ()
     }
+    type AsyncSplit<'u> = ();
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        let _ = (uart, state);
+    }
 }
 
 impl<'a, 'b, const N1: usize, const N2: usize> Pads<MMUART_UART_ID>
@@ -271,6 +558,15 @@ where
         let _ = uart;
         ()
     }
+    type AsyncSplit<'u> = ();
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        let _ = (uart, state);
+    }
 }
 
 impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> Pads<MMUART_UART_ID>
@@ -294,6 +590,15 @@ where
         let _ = uart;
         ()
     }
+    type AsyncSplit<'u> = ();
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        let _ = (uart, state);
+    }
 }
 
 impl<'a, 'b, 'c, 'd, const N1: usize, const N2: usize, const N3: usize, const N4: usize>
@@ -320,4 +625,13 @@ where
         let _ = uart;
         ()
     }
+    type AsyncSplit<'u> = ();
+    #[inline]
+    fn async_split<'u>(
+        self,
+        uart: &'u RegisterBlock,
+        state: &'u SerialState,
+    ) -> Self::AsyncSplit<'u> {
+        let _ = (uart, state);
+    }
 }
@@ -1,4 +1,4 @@
-use super::{BitOrder, Parity, StopBits, WordLength};
+use super::{BitOrder, DriverEnablePolarity, Parity, StopBits, WordLength};
 use volatile_register::{RO, RW, WO};
 
 /// Universal Asynchronous Receiver/Transmitter registers.
@@ -12,7 +12,10 @@ pub struct RegisterBlock {
     pub bit_period: RW<BitPeriod>,
     /// Data format configuration.
     pub data_config: RW<DataConfig>,
-    _reserved1: [u8; 0x10],
+    _reserved1: [u8; 0x8],
+    /// Receive time-out configuration.
+    pub receive_timeout: RW<ReceiveTimeout>,
+    _reserved2: [u8; 0x4],
     /// Interrupt state register.
     pub interrupt_state: RO<InterruptState>,
     /// Interrupt mask register.
@@ -23,14 +26,19 @@ pub struct RegisterBlock {
     pub interrupt_enable: RW<InterruptEnable>,
     /// Bus state.
     pub bus_state: RO<BusState>,
-    _reserved2: [u8; 0x4c],
+    /// Automatic baudrate detection result.
+    pub auto_baudrate_period: RO<AutoBaudratePeriod>,
+    _reserved3: [u8; 0x1c],
+    /// RS-485 driver-enable configuration.
+    pub rs485_config: RW<Rs485Config>,
+    _reserved4: [u8; 0x28],
     /// First-in first-out queue configuration 0.
     pub fifo_config_0: RW<FifoConfig0>,
     /// First-in first-out queue configuration 1.
     pub fifo_config_1: RW<FifoConfig1>,
     /// Write data into first-in first-out queue.
     pub fifo_write: WO<u8>,
-    _reserved3: [u8; 0x3],
+    _reserved5: [u8; 0x3],
     /// Read data from first-in first-out queue.
     pub fifo_read: RO<u8>,
 }
@@ -535,6 +543,33 @@ impl Default for DataConfig {
     }
 }
 
+/// Receive time-out configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ReceiveTimeout(u32);
+
+impl ReceiveTimeout {
+    const VALUE: u32 = 0xff;
+
+    /// Set idle time in bit periods before receive time-out interrupt occurs.
+    #[inline]
+    pub const fn set_timeout(self, val: u8) -> Self {
+        Self(self.0 & !Self::VALUE | val as u32)
+    }
+    /// Get idle time in bit periods before receive time-out interrupt occurs.
+    #[inline]
+    pub const fn timeout(self) -> u8 {
+        (self.0 & Self::VALUE) as u8
+    }
+}
+
+impl Default for ReceiveTimeout {
+    #[inline]
+    fn default() -> Self {
+        Self(0x0000_000f)
+    }
+}
+
 /// Interrupt event.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -605,7 +640,7 @@ impl InterruptClear {
 /// Interrupt enable register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
-pub struct InterruptEnable(u32);
+pub struct InterruptEnable(pub(super) u32);
 
 impl InterruptEnable {
     /// Enable interrupt.
@@ -646,6 +681,70 @@ impl BusState {
     }
 }
 
+/// Automatic baudrate detection result register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct AutoBaudratePeriod(u32);
+
+impl AutoBaudratePeriod {
+    const START_BIT: u32 = 0xffff;
+    const FIVE_FIVE: u32 = 0xffff << 16;
+
+    /// Get bit period in clocks measured by the start bit.
+    #[inline]
+    pub const fn start_bit(self) -> u16 {
+        (self.0 & Self::START_BIT) as u16
+    }
+    /// Get bit period in clocks measured by the 0x55 synchronize pattern.
+    #[inline]
+    pub const fn five_five(self) -> u16 {
+        ((self.0 & Self::FIVE_FIVE) >> 16) as u16
+    }
+}
+
+/// RS-485 driver-enable configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Rs485Config(u32);
+
+impl Rs485Config {
+    const ENABLE: u32 = 1 << 0;
+    const POLARITY: u32 = 1 << 1;
+
+    /// Enable driving request-to-send signal as RS-485 driver-enable.
+    #[inline]
+    pub const fn enable_rs485(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable RS-485 driver-enable.
+    #[inline]
+    pub const fn disable_rs485(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if RS-485 driver-enable is enabled.
+    #[inline]
+    pub const fn is_rs485_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set active level of RS-485 driver-enable signal.
+    #[inline]
+    pub const fn set_polarity(self, val: DriverEnablePolarity) -> Self {
+        match val {
+            DriverEnablePolarity::ActiveHigh => Self(self.0 & !Self::POLARITY),
+            DriverEnablePolarity::ActiveLow => Self(self.0 | Self::POLARITY),
+        }
+    }
+    /// Get active level of RS-485 driver-enable signal.
+    #[inline]
+    pub const fn polarity(self) -> DriverEnablePolarity {
+        if self.0 & Self::POLARITY == 0 {
+            DriverEnablePolarity::ActiveHigh
+        } else {
+            DriverEnablePolarity::ActiveLow
+        }
+    }
+}
+
 /// First-in first-out queue configuration 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -768,7 +867,7 @@ impl FifoConfig1 {
 
 #[cfg(test)]
 mod tests {
-    use crate::uart::{StopBits, WordLength};
+    use crate::uart::{DriverEnablePolarity, StopBits, WordLength};
 
     use super::{BitPeriod, Parity, ReceiveConfig, RegisterBlock, TransmitConfig};
     use core::mem::offset_of;
@@ -779,11 +878,14 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, receive_config), 0x4);
         assert_eq!(offset_of!(RegisterBlock, bit_period), 0x08);
         assert_eq!(offset_of!(RegisterBlock, data_config), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, receive_timeout), 0x18);
         assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x20);
         assert_eq!(offset_of!(RegisterBlock, interrupt_mask), 0x24);
         assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x28);
         assert_eq!(offset_of!(RegisterBlock, interrupt_enable), 0x2c);
         assert_eq!(offset_of!(RegisterBlock, bus_state), 0x30);
+        assert_eq!(offset_of!(RegisterBlock, auto_baudrate_period), 0x34);
+        assert_eq!(offset_of!(RegisterBlock, rs485_config), 0x54);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_0), 0x80);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
         assert_eq!(offset_of!(RegisterBlock, fifo_write), 0x88);
@@ -1042,6 +1144,21 @@ mod tests {
         assert_eq!(default.bit_order(), super::BitOrder::LsbFirst);
     }
 
+    #[test]
+    fn struct_receive_timeout_functions() {
+        let mut val: super::ReceiveTimeout = super::ReceiveTimeout(0x0);
+
+        val = val.set_timeout(0x4f);
+        assert_eq!(val.0, 0x0000004f);
+        assert_eq!(val.timeout(), 0x4f);
+        val = val.set_timeout(0xff);
+        assert_eq!(val.0, 0x000000ff);
+        assert_eq!(val.timeout(), 0xff);
+
+        let default = super::ReceiveTimeout::default();
+        assert_eq!(default.timeout(), 0x0f);
+    }
+
     #[test]
     fn struct_interrupt_state_functions() {
         let val: super::InterruptState = super::InterruptState(0x0);
@@ -1153,6 +1270,33 @@ mod tests {
         assert_eq!(val.receive_busy(), false);
     }
 
+    #[test]
+    fn struct_auto_baudrate_period_functions() {
+        let val: super::AutoBaudratePeriod = super::AutoBaudratePeriod(0x12345678);
+
+        assert_eq!(val.start_bit(), 0x5678);
+        assert_eq!(val.five_five(), 0x1234);
+    }
+
+    #[test]
+    fn struct_rs485_config_functions() {
+        let mut val: super::Rs485Config = super::Rs485Config(0x0);
+
+        val = val.enable_rs485();
+        assert_eq!(val.0, 0x00000001);
+        assert!(val.is_rs485_enabled());
+        val = val.disable_rs485();
+        assert_eq!(val.0, 0x00000000);
+        assert!(!val.is_rs485_enabled());
+
+        val = val.set_polarity(DriverEnablePolarity::ActiveLow);
+        assert_eq!(val.0, 0x00000002);
+        assert_eq!(val.polarity(), DriverEnablePolarity::ActiveLow);
+        val = val.set_polarity(DriverEnablePolarity::ActiveHigh);
+        assert_eq!(val.0, 0x00000000);
+        assert_eq!(val.polarity(), DriverEnablePolarity::ActiveHigh);
+    }
+
     #[test]
     fn struct_fifo_config0_functions() {
         let mut val: super::FifoConfig0 = super::FifoConfig0(0x0);
@@ -166,6 +166,7 @@ MEMORY {
     FLASH : ORIGIN = 0x58000000, LENGTH = 32M - 4K
     DRAM : ORIGIN = 0x3EFF7000, LENGTH = 4K
     VRAM : ORIGIN = 0x3F000000, LENGTH = 32K
+    NOCACHE : ORIGIN = 0x22020000, LENGTH = 64K
 }
 SECTIONS {
     .head : ALIGN(8) {
@@ -225,6 +226,13 @@ SECTIONS {
         *(.sbss .sbss.*)
         ebss = .;
     } > VRAM
+    /* DMA buffers and descriptors, on OCRAM through its uncached alias */
+    .nocache (NOLOAD) : ALIGN(64) {
+        snocache = .;
+        *(.nocache .nocache.*)
+        . = ALIGN(64);
+        enocache = .;
+    } > NOCACHE
     /DISCARD/ : {
         *(.eh_frame)
     }
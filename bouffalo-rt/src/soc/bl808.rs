@@ -78,6 +78,13 @@ unsafe extern "C" fn start() -> ! {
         sd      zero, 0(t1)
         addi    t1, t1, 8 
         j       1b
+    1:",
+        "   la      t1, snocache
+        la      t2, enocache
+    1:  bgeu    t1, t2, 1f
+        sd      zero, 0(t1)
+        addi    t1, t1, 8
+        j       1b
     1:",
         "   la      t3, sidata
         la      t4, sdata
@@ -1,7 +1,11 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{i2c::I2c, prelude::*, uart::Config};
+use bouffalo_hal::{
+    i2c::{Config as I2cConfig, I2c},
+    prelude::*,
+    uart::Config,
+};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use embedded_time::rate::*;
 use panic_halt as _;
@@ -23,7 +27,7 @@ fn main(p: Peripherals, c: Clocks) -> ! {
 
     let scl = p.gpio.io6.into_i2c::<0>();
     let sda = p.gpio.io7.into_i2c::<0>();
-    let mut i2c = I2c::new(p.i2c0, (scl, sda), &p.glb);
+    let mut i2c = I2c::new(p.i2c0, (scl, sda), I2cConfig::STANDARD, &p.glb, &c).unwrap();
     i2c.enable_sub_address(SCREEN_TOUCH_SUB_ADDRESS);
 
     writeln!(serial, "Hello Rust🦀!").ok();
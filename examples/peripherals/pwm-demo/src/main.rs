@@ -14,7 +14,7 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     let led = p.gpio.io8.into_pull_down_pwm::<0>();
 
     let mut pwm = Pwm::new(p.pwm, SingleEnd, SingleEnd, &p.glb);
-    pwm.group0.set_clock(1_000_000.Hz(), Xclk, &c);
+    pwm.group0.set_clock(1_000_000.Hz(), Xclk, &c).unwrap();
     pwm.group0.set_max_duty_cycle(100);
     pwm.group0.start();
     let mut led = pwm.group0.channel0.positive_signal_pin(led);
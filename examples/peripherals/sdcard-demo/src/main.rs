@@ -1,7 +1,11 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{prelude::*, spi::Spi, uart::Config};
+use bouffalo_hal::{
+    prelude::*,
+    spi::{self, ExclusiveDevice, Spi},
+    uart::Config,
+};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use embedded_hal::spi::MODE_3;
 use embedded_sdmmc::{SdCard, VolumeManager};
@@ -35,15 +39,21 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     let spi_clk = p.gpio.io3.into_spi::<1>();
     let spi_mosi = p.gpio.io1.into_spi::<1>();
     let spi_miso = p.gpio.io2.into_spi::<1>();
-    let spi_cs = p.gpio.io0.into_spi::<1>();
-    let spi_sd = Spi::new(
+    let spi_cs = p.gpio.io0.into_floating_output();
+    let spi_sd = Spi::new_mm(
         p.spi1,
-        (spi_clk, spi_mosi, spi_miso, spi_cs),
-        MODE_3,
+        (spi_clk, spi_mosi, spi_miso, ()),
+        spi::Config::new(400_000.Hz(), MODE_3),
         &p.glb,
-    );
+        &p.mmglb,
+        &c,
+    )
+    .unwrap();
 
     let delay = riscv::delay::McycleDelay::new(40_000_000);
+    // SD card commands need chip select held across operations, drive it from a GPIO output.
+    let spi_sd =
+        ExclusiveDevice::new(spi_sd, spi_cs, riscv::delay::McycleDelay::new(40_000_000)).unwrap();
     let sdcard = SdCard::new(spi_sd, delay);
     while sdcard.get_card_type().is_none() {
         core::hint::spin_loop();
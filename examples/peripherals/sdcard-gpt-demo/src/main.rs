@@ -1,7 +1,11 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{prelude::*, spi::Spi, uart::Config};
+use bouffalo_hal::{
+    prelude::*,
+    spi::{self, ExclusiveDevice, Spi},
+    uart::Config,
+};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use embedded_hal::spi::MODE_3;
 use embedded_sdmmc::*;
@@ -205,16 +209,22 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     let spi_clk = p.gpio.io3.into_spi::<1>();
     let spi_mosi = p.gpio.io1.into_spi::<1>();
     let spi_miso = p.gpio.io2.into_spi::<1>();
-    let spi_cs = p.gpio.io0.into_spi::<1>();
+    let spi_cs = p.gpio.io0.into_floating_output();
 
-    let spi_sd = Spi::new(
+    let spi_sd = Spi::new_mm(
         p.spi1,
-        (spi_clk, spi_mosi, spi_miso, spi_cs),
-        MODE_3,
+        (spi_clk, spi_mosi, spi_miso, ()),
+        spi::Config::new(400_000.Hz(), MODE_3),
         &p.glb,
-    );
+        &p.mmglb,
+        &c,
+    )
+    .unwrap();
 
     let delay = riscv::delay::McycleDelay::new(40_000_000);
+    // SD card commands need chip select held across operations, drive it from a GPIO output.
+    let spi_sd =
+        ExclusiveDevice::new(spi_sd, spi_cs, riscv::delay::McycleDelay::new(40_000_000)).unwrap();
     let sdcard = SdCard::new(spi_sd, delay);
     while sdcard.get_card_type().is_none() {
         core::hint::spin_loop();
@@ -246,15 +256,19 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     // TODO: switch to disk.into_inner() once Disk struct have this function
     let my_sdcard: MySdCard<
         SdCard<
-            Spi<
-                bouffalo_rt::soc::bl808::SPI0,
-                (
-                    bouffalo_hal::gpio::Alternate<3, bouffalo_hal::gpio::Spi<1>>,
-                    bouffalo_hal::gpio::Alternate<1, bouffalo_hal::gpio::Spi<1>>,
-                    bouffalo_hal::gpio::Alternate<2, bouffalo_hal::gpio::Spi<1>>,
-                    bouffalo_hal::gpio::Alternate<0, bouffalo_hal::gpio::Spi<1>>,
-                ),
-                1,
+            ExclusiveDevice<
+                Spi<
+                    bouffalo_rt::soc::bl808::SPI1,
+                    (
+                        bouffalo_hal::gpio::Alternate<3, bouffalo_hal::gpio::Spi<1>>,
+                        bouffalo_hal::gpio::Alternate<1, bouffalo_hal::gpio::Spi<1>>,
+                        bouffalo_hal::gpio::Alternate<2, bouffalo_hal::gpio::Spi<1>>,
+                        (),
+                    ),
+                    1,
+                >,
+                bouffalo_hal::gpio::Output<0, bouffalo_hal::gpio::Floating>,
+                riscv::delay::McycleDelay,
             >,
             riscv::delay::McycleDelay,
         >,
@@ -1,7 +1,10 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{prelude::*, spi::Spi};
+use bouffalo_hal::{
+    prelude::*,
+    spi::{self, HardwareCsDevice, Spi},
+};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use embedded_graphics::{
     draw_target::DrawTarget,
@@ -12,12 +15,13 @@ use embedded_graphics::{
     text::Text,
 };
 use embedded_hal::spi::MODE_0;
+use embedded_time::rate::*;
 use mipidsi::Builder;
 use mipidsi::{models::ST7789, options::ColorInversion};
 use panic_halt as _;
 
 #[entry]
-fn main(p: Peripherals, _c: Clocks) -> ! {
+fn main(p: Peripherals, c: Clocks) -> ! {
     let mut led = p.gpio.io8.into_floating_output();
     let mut led_state = PinState::Low;
 
@@ -27,9 +31,18 @@ fn main(p: Peripherals, _c: Clocks) -> ! {
     let lcd_dc = p.gpio.io13.into_floating_output();
     let mut lcd_bl = p.gpio.io11.into_floating_output();
     let lcd_rst = p.gpio.io24.into_floating_output();
-    let spi_lcd = Spi::new(p.spi1, (spi_clk, spi_mosi, spi_cs), MODE_0, &p.glb);
+    let spi_lcd = Spi::new_mm(
+        p.spi1,
+        (spi_clk, spi_mosi, spi_cs),
+        spi::Config::new(40_000_000.Hz(), MODE_0),
+        &p.glb,
+        &p.mmglb,
+        &c,
+    )
+    .unwrap();
 
     let mut delay = riscv::delay::McycleDelay::new(40_000_000);
+    let spi_lcd = HardwareCsDevice::new(spi_lcd, riscv::delay::McycleDelay::new(40_000_000));
     let di = display_interface_spi::SPIInterface::new(spi_lcd, lcd_dc);
 
     let mut display = Builder::new(ST7789, di)
@@ -1,11 +1,21 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{dma::*, prelude::*, uart::Config};
+use bouffalo_hal::{
+    dma::*,
+    prelude::*,
+    uart::{Config, DmaSerialBuffers, SerialState},
+};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use embedded_time::rate::*;
 use panic_halt as _;
 
+static mut RX_BUFFER: [u8; 256] = [0u8; 256];
+static mut RX_LLI: [LliPool; 2] = [LliPool::new(); 2];
+static mut TX_LLI: [LliPool; 1] = [LliPool::new(); 1];
+static UART0_STATE: SerialState = SerialState::new();
+static DMA0_STATE: DmaState = DmaState::new();
+
 #[entry]
 fn main(p: Peripherals, c: Clocks) -> ! {
     let tx = p.gpio.io14.into_uart();
@@ -16,52 +26,37 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     let mut led = p.gpio.io8.into_floating_output();
 
     let config = Config::default().set_baudrate(2000000.Bd());
-    let mut serial = p.uart0.freerun(config, pads, &c).unwrap().enable_tx_dma();
-    let tx_config = DmaChannelConfig {
-        direction: DmaMode::Mem2Periph,
-        src_req: None,
-        dst_req: Some(Periph4Dma01::Uart0Tx),
-        src_addr_inc: true,
-        dst_addr_inc: false,
-        src_burst_size: BurstSize::INCR1,
-        dst_burst_size: BurstSize::INCR1,
-        src_transfer_width: TransferWidth::Byte,
-        dst_transfer_width: TransferWidth::Byte,
+    let dma0 = p.dma0.split(&p.glb);
+    let buffers = DmaSerialBuffers {
+        rx_buffer: unsafe { &mut *core::ptr::addr_of_mut!(RX_BUFFER) },
+        rx_lli: unsafe { &mut *core::ptr::addr_of_mut!(RX_LLI) },
+        tx_lli: unsafe { &mut *core::ptr::addr_of_mut!(TX_LLI) },
     };
-    let mut dma0 = p.dma0.split(&p.glb);
-    dma0.ch0.configure(tx_config);
-    let dma0_ch0 = dma0.ch0;
-    let tx_lli_pool = &mut [LliPool::new(); 1];
-    let hello = b"Welcome to Universal Asynchronous Receiver/Transmitter with Direct Memory Access demo!\r\nHello world!";
-    let hello_ptr = hello.as_ptr();
-    let hello_len = hello.len();
-    let tx_transfer = &mut [LliTransfer {
-        src_addr: hello_ptr as u32,
-        dst_addr: DmaAddr::Uart0Tx as u32,
-        nbytes: hello_len as u32,
-    }];
-    if dma0_ch0.lli_reload(tx_lli_pool, 1, tx_transfer, 1) == -12 {
-        writeln!(serial, "Out of memory.").unwrap();
-        loop {
-            riscv::asm::delay(20_000);
-            led.set_high().ok();
-            riscv::asm::delay(20_000);
-            led.set_low().ok();
-        }
-    }
-    dma0_ch0.start();
-    // TODO: use interrupt to know when DMA transfer is done.
-    // Wait for transfer to complete.
-    while dma0_ch0.is_busy() {
-        core::hint::spin_loop();
-    }
+    let mut serial = p
+        .uart0
+        .freerun(config, pads, &c)
+        .unwrap()
+        .with_dma(
+            (dma0.ch0, Periph4Dma01::Uart0Tx),
+            (dma0.ch1, Periph4Dma01::Uart0Rx),
+            buffers,
+            &UART0_STATE,
+            &DMA0_STATE,
+        )
+        .unwrap();
 
-    dma0_ch0.stop();
+    static HELLO: &[u8] = b"Welcome to Universal Asynchronous Receiver/Transmitter with Direct Memory Access demo!\r\nHello world!\r\n";
+    let transmit = serial.transmit(HELLO).ok().unwrap();
+    while transmit.is_ongoing() {
+        led.set_high().ok();
+    }
+    transmit.wait();
+    led.set_low().ok();
 
+    // Echo everything received by DMA ring buffer.
+    let mut buf = [0u8; 64];
     loop {
-        led.set_low().ok();
-        riscv::asm::delay(100_000);
-        led.set_high().ok();
-        riscv::asm::delay(100_000);
+        let len = embedded_io::Read::read(&mut serial, &mut buf).unwrap();
+        embedded_io::Write::write_all(&mut serial, &buf[..len]).unwrap();
     }
 }